@@ -6,6 +6,31 @@ pub const VERIFICATION_SEED: &[u8] = b"verification";
 pub const SPLIT_SEED: &[u8] = b"split";
 pub const SIGNAL_SEED: &[u8] = b"signal";
 pub const REPUTATION_SEED: &[u8] = b"reputation";
+pub const BALLOT_SEED: &[u8] = b"ballot";
+pub const CHALLENGE_SEED: &[u8] = b"challenge";
+pub const CHALLENGE_VAULT_SEED: &[u8] = b"challenge-vault";
+pub const STAKE_SEED: &[u8] = b"stake";
+pub const STAKE_VAULT_SEED: &[u8] = b"stake-vault";
+pub const RISK_FLAG_TABLE_SEED: &[u8] = b"risk-flags";
+pub const CATEGORY_SEED: &[u8] = b"category";
+pub const EPOCH_SNAPSHOT_SEED: &[u8] = b"epoch-snapshot";
+pub const COMMITMENT_SEED: &[u8] = b"commitment";
+pub const SIGNAL_BATCH_SEED: &[u8] = b"signal-batch";
+pub const PNL_STATS_SEED: &[u8] = b"pnl-stats";
+pub const SCORED_TRADES_SEED: &[u8] = b"scored-trades";
+pub const LEADERBOARD_SEED: &[u8] = b"leaderboard";
+pub const SETTLEMENT_MINT_SEED: &[u8] = b"settlement-mint";
+pub const SETTLEMENT_VAULT_SEED: &[u8] = b"settlement-vault";
+pub const CLAIM_LEDGER_SEED: &[u8] = b"claim-ledger";
+pub const CLAIMABLE_SEED: &[u8] = b"claimable";
+pub const FEE_SCHEDULE_SEED: &[u8] = b"fee-schedule";
+pub const SETTLEMENT_VOLUME_SEED: &[u8] = b"settlement-volume";
+pub const VESTING_SEED: &[u8] = b"vesting";
+pub const VESTING_VAULT_SEED: &[u8] = b"vesting-vault";
+pub const RESERVE_SEED: &[u8] = b"reserve";
+pub const REFERRAL_SEED: &[u8] = b"referral";
+pub const REFERRAL_PROGRAM_SEED: &[u8] = b"referral-program";
+pub const SOL_VAULT_SEED: &[u8] = b"sol-vault";
 pub const RECEIPT_SEED: &[u8] = b"receipt";
 pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault-authority";
 
@@ -20,5 +45,37 @@ pub const STATUS_VERIFIED: u8 = 2;
 pub const STATUS_REJECTED: u8 = 3;
 pub const STATUS_SUSPENDED: u8 = 4;
 
+pub const CHALLENGE_NONE: u8 = 0;
+pub const CHALLENGE_OPEN: u8 = 1;
+pub const CHALLENGE_UPHELD: u8 = 2;
+pub const CHALLENGE_REJECTED: u8 = 3;
+// Scored before the upgrade; the signal carries no correction data.
+pub const CHALLENGE_LEGACY: u8 = 4;
+
+pub const AGGREGATION_EMA: u8 = 0;
+pub const AGGREGATION_WINDOW: u8 = 1;
+pub const AGGREGATION_BETA: u8 = 2;
+
 pub const MAX_BPS: u16 = 10_000;
 pub const MAX_URI_LEN: usize = 256;
+pub const MAX_BALLOT_VOTES: usize = 7;
+// Ballot votes below this confidence are rejected so every vote carries weight in the median.
+pub const MIN_VOTE_CONFIDENCE_BPS: u16 = 1_000;
+pub const MAX_UNSTAKE_REQUESTS: usize = 4;
+pub const SCORE_HISTOGRAM_BUCKETS: usize = 10;
+pub const MAX_SCORE_WINDOW: usize = 32;
+pub const RISK_FLAG_BITS: usize = 8;
+pub const MAX_SPLIT_RECIPIENTS: usize = 8;
+pub const MAX_BATCH_ENTRIES: usize = 16;
+pub const MAX_FEE_TIERS: usize = 16;
+pub const MAX_REFERRAL_LEVELS: usize = 8;
+pub const MAX_REFERRAL_CHAIN: usize = 32;
+pub const VOLUME_WINDOW_DAYS: usize = 30;
+pub const SECONDS_PER_DAY: i64 = 86_400;
+pub const MAX_VESTING_DURATION: i64 = 365 * SECONDS_PER_DAY;
+// One tranche per day boundary a deposit can end on, plus the partial day at each edge.
+pub const MAX_VESTING_TRANCHES: usize = (MAX_VESTING_DURATION / SECONDS_PER_DAY) as usize + 2;
+pub const MAX_RESERVE_LOCKUP: i64 = 730 * SECONDS_PER_DAY;
+pub const MAX_LEADERBOARD_CAPACITY: u16 = 100;
+pub const MAX_MERKLE_DEPTH: usize = 14;
+pub const MAX_SIGNAL_BATCH_LEAVES: u32 = 1 << MAX_MERKLE_DEPTH;
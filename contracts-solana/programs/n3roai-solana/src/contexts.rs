@@ -1,15 +1,26 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{self, TokenInterface};
 
 use crate::constants::{
-    AGENT_SEED, IDENTITY_REGISTRY_SEED, PROTOCOL_CONFIG_SEED, RECEIPT_SEED, REPUTATION_SEED,
+    AGENT_SEED, BALLOT_SEED, CATEGORY_SEED, CHALLENGE_SEED, CHALLENGE_VAULT_SEED, CLAIMABLE_SEED,
+    CLAIM_LEDGER_SEED, COMMITMENT_SEED, EPOCH_SNAPSHOT_SEED, FEE_SCHEDULE_SEED,
+    IDENTITY_REGISTRY_SEED, LEADERBOARD_SEED, PNL_STATS_SEED, PROTOCOL_CONFIG_SEED, RECEIPT_SEED,
+    REFERRAL_PROGRAM_SEED, REFERRAL_SEED, REPUTATION_SEED, RESERVE_SEED, RISK_FLAG_TABLE_SEED,
     ROLE_ORACLE, ROLE_REVENUE_OPERATOR, ROLE_SEED, ROLE_SIGNALER, ROLE_VERIFICATION_OPERATOR,
-    SIGNAL_SEED, SPLIT_SEED, VAULT_AUTHORITY_SEED, VERIFICATION_SEED,
+    SCORED_TRADES_SEED, SETTLEMENT_MINT_SEED, SETTLEMENT_VAULT_SEED, SETTLEMENT_VOLUME_SEED,
+    SIGNAL_BATCH_SEED, SIGNAL_SEED, SOL_VAULT_SEED, SPLIT_SEED, STAKE_SEED, STAKE_VAULT_SEED,
+    VAULT_AUTHORITY_SEED, VERIFICATION_SEED, VESTING_SEED, VESTING_VAULT_SEED,
 };
 use crate::errors::ErrorCode;
 use crate::state::{
-    AgentIdentity, DistributionReceipt, IdentityRegistryState, ProtocolConfig, ReputationState,
-    RevenueSplitConfig, RoleAssignment, TradeSignal, VerificationRecord,
+    AgentIdentity, ClaimLedger, ClaimableBalance, CompressedSignalLeaf, DistributionReceipt,
+    FeeSchedule, IdentityRegistryState, Leaderboard, MemberStake, PnlStats, ProtocolConfig,
+    ReferralAccount, ReferralProgram, ReputationCategory, ReputationSnapshot, ReputationState,
+    ReserveVault, RevenueSplitConfig, RiskFlagTable, RoleAssignment, ScoreBallot, ScoreChallenge,
+    SettlementMintConfig, SettlementVolume, SignalBatch, SignalCommitment, TradeSignal,
+    VerificationRecord, VestingEscrow,
 };
 
 #[derive(Accounts)]
@@ -135,6 +146,235 @@ pub struct AdminUpdate<'info> {
     pub admin: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct InitializeRiskFlagTable<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [RISK_FLAG_TABLE_SEED],
+        bump,
+        space = RiskFlagTable::SPACE
+    )]
+    pub risk_flag_table: Account<'info, RiskFlagTable>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRiskFlagTable<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut, seeds = [RISK_FLAG_TABLE_SEED], bump = risk_flag_table.bump)]
+    pub risk_flag_table: Account<'info, RiskFlagTable>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(category_id: u16)]
+pub struct SetCategory<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [CATEGORY_SEED, &category_id.to_le_bytes()],
+        bump,
+        space = ReputationCategory::SPACE
+    )]
+    pub category: Account<'info, ReputationCategory>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(category_id: u16, capacity: u16)]
+pub struct InitializeLeaderboard<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [LEADERBOARD_SEED, &category_id.to_le_bytes()],
+        bump,
+        space = Leaderboard::space(capacity)
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetLeaderboardConfig<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        mut,
+        seeds = [LEADERBOARD_SEED, &leaderboard.category.to_le_bytes()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddSettlementMint<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub mint: InterfaceAccount<'info, token_interface::Mint>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [SETTLEMENT_MINT_SEED, mint.key().as_ref()],
+        bump,
+        space = SettlementMintConfig::SPACE
+    )]
+    pub settlement_mint_config: Account<'info, SettlementMintConfig>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [SETTLEMENT_VAULT_SEED, mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+        token::token_program = token_program
+    )]
+    pub settlement_vault: InterfaceAccount<'info, token_interface::TokenAccount>,
+    /// CHECK: PDA authority of the per-mint settlement vault.
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub treasury_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSettlementMint<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        mut,
+        seeds = [SETTLEMENT_MINT_SEED, settlement_mint_config.mint.as_ref()],
+        bump = settlement_mint_config.bump
+    )]
+    pub settlement_mint_config: Account<'info, SettlementMintConfig>,
+    pub treasury_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeSchedule<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [FEE_SCHEDULE_SEED],
+        bump,
+        space = FeeSchedule::SPACE
+    )]
+    pub fee_schedule: Account<'info, FeeSchedule>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetReferralLevels<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [REFERRAL_PROGRAM_SEED],
+        bump,
+        space = ReferralProgram::SPACE
+    )]
+    pub referral_program: Account<'info, ReferralProgram>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(parent: Pubkey)]
+pub struct RegisterReferral<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        init,
+        payer = referrer,
+        seeds = [REFERRAL_SEED, referrer.key().as_ref()],
+        bump,
+        space = ReferralAccount::SPACE
+    )]
+    pub referral_account: Account<'info, ReferralAccount>,
+    #[account(seeds = [REFERRAL_SEED, parent.as_ref()], bump = parent_referral.bump)]
+    pub parent_referral: Option<Account<'info, ReferralAccount>>,
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetReferralParent<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        mut,
+        seeds = [REFERRAL_SEED, referral_account.referrer.as_ref()],
+        bump = referral_account.bump
+    )]
+    pub referral_account: Account<'info, ReferralAccount>,
+    pub admin: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct RequestVerification<'info> {
     #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
@@ -205,6 +445,25 @@ pub struct SetSplit<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct SetSplitVesting<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        mut,
+        seeds = [SPLIT_SEED, agent_identity.key().as_ref()],
+        bump = split_config.bump
+    )]
+    pub split_config: Account<'info, RevenueSplitConfig>,
+    pub admin: Signer<'info>,
+}
+
 #[derive(Accounts)]
 #[instruction(trade_id_hash: [u8; 32])]
 pub struct SubmitSignal<'info> {
@@ -217,8 +476,10 @@ pub struct SubmitSignal<'info> {
         bump = role_assignment.bump
     )]
     pub role_assignment: Account<'info, RoleAssignment>,
+    pub member_stake: Option<Account<'info, MemberStake>>,
     #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
     pub agent_identity: Account<'info, AgentIdentity>,
+    pub category: Option<Account<'info, ReputationCategory>>,
     #[account(
         init,
         payer = signaler,
@@ -231,83 +492,1328 @@ pub struct SubmitSignal<'info> {
 }
 
 #[derive(Accounts)]
-#[instruction(trade_id_hash: [u8; 32])]
-pub struct SubmitScore<'info> {
+#[instruction(commitment: [u8; 32])]
+pub struct CommitSignal<'info> {
     #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
     pub protocol_config: Account<'info, ProtocolConfig>,
     #[account(mut)]
-    pub oracle: Signer<'info>,
+    pub signaler: Signer<'info>,
     #[account(
-        seeds = [ROLE_SEED, &[ROLE_ORACLE], oracle.key().as_ref()],
+        seeds = [ROLE_SEED, &[ROLE_SIGNALER], signaler.key().as_ref()],
         bump = role_assignment.bump
     )]
     pub role_assignment: Account<'info, RoleAssignment>,
+    pub member_stake: Option<Account<'info, MemberStake>>,
     #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
     pub agent_identity: Account<'info, AgentIdentity>,
     #[account(
-        mut,
-        seeds = [SIGNAL_SEED, agent_identity.key().as_ref(), trade_id_hash.as_ref()],
-        bump = trade_signal.bump
-    )]
-    pub trade_signal: Account<'info, TradeSignal>,
-    #[account(
-        init_if_needed,
-        payer = oracle,
-        seeds = [VERIFICATION_SEED, agent_identity.key().as_ref()],
-        bump,
-        space = VerificationRecord::SPACE
-    )]
-    pub verification_record: Account<'info, VerificationRecord>,
-    #[account(
-        init_if_needed,
-        payer = oracle,
-        seeds = [REPUTATION_SEED, agent_identity.key().as_ref()],
+        init,
+        payer = signaler,
+        seeds = [COMMITMENT_SEED, agent_identity.key().as_ref(), commitment.as_ref()],
         bump,
-        space = ReputationState::SPACE
+        space = SignalCommitment::SPACE
     )]
-    pub reputation_state: Account<'info, ReputationState>,
+    pub signal_commitment: Account<'info, SignalCommitment>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(reference: [u8; 32])]
-pub struct DistributeSettlement<'info> {
+#[instruction(commitment: [u8; 32], trade_id_hash: [u8; 32])]
+pub struct RevealSignal<'info> {
     #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
     pub protocol_config: Account<'info, ProtocolConfig>,
     #[account(mut)]
-    pub operator: Signer<'info>,
+    pub signaler: Signer<'info>,
     #[account(
-        seeds = [ROLE_SEED, &[ROLE_REVENUE_OPERATOR], operator.key().as_ref()],
+        seeds = [ROLE_SEED, &[ROLE_SIGNALER], signaler.key().as_ref()],
         bump = role_assignment.bump
     )]
     pub role_assignment: Account<'info, RoleAssignment>,
+    pub member_stake: Option<Account<'info, MemberStake>>,
     #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
     pub agent_identity: Account<'info, AgentIdentity>,
-    #[account(seeds = [SPLIT_SEED, agent_identity.key().as_ref()], bump = split_config.bump)]
-    pub split_config: Account<'info, RevenueSplitConfig>,
-    #[account(mut)]
-    pub settlement_vault: Account<'info, TokenAccount>,
-    /// CHECK: PDA signer for transfer from settlement vault.
-    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
-    pub vault_authority: UncheckedAccount<'info>,
-    #[account(mut)]
-    pub agent_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub platform_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub referrer_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub reserve_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub protocol_treasury_token_account: Account<'info, TokenAccount>,
+    pub category: Option<Account<'info, ReputationCategory>>,
+    #[account(
+        mut,
+        close = signaler,
+        seeds = [COMMITMENT_SEED, agent_identity.key().as_ref(), commitment.as_ref()],
+        bump = signal_commitment.bump,
+        constraint = signal_commitment.signaler == signaler.key() @ ErrorCode::Unauthorized
+    )]
+    pub signal_commitment: Account<'info, SignalCommitment>,
     #[account(
         init,
-        payer = operator,
-        seeds = [RECEIPT_SEED, agent_identity.key().as_ref(), reference.as_ref()],
+        payer = signaler,
+        seeds = [SIGNAL_SEED, agent_identity.key().as_ref(), trade_id_hash.as_ref()],
         bump,
-        space = DistributionReceipt::SPACE
+        space = TradeSignal::SPACE
     )]
-    pub distribution_receipt: Account<'info, DistributionReceipt>,
-    pub token_program: Program<'info, Token>,
+    pub trade_signal: Account<'info, TradeSignal>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32])]
+pub struct ExpireSignalCommitment<'info> {
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        mut,
+        close = signaler,
+        seeds = [COMMITMENT_SEED, agent_identity.key().as_ref(), commitment.as_ref()],
+        bump = signal_commitment.bump
+    )]
+    pub signal_commitment: Account<'info, SignalCommitment>,
+    /// CHECK: Receives the commitment rent; must match the recorded signaler.
+    #[account(mut, address = signal_commitment.signaler @ ErrorCode::Unauthorized)]
+    pub signaler: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(trade_id_hash: [u8; 32])]
+pub struct ReportTradePnl<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub signaler: Signer<'info>,
+    #[account(
+        seeds = [ROLE_SEED, &[ROLE_SIGNALER], signaler.key().as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub member_stake: Option<Account<'info, MemberStake>>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        mut,
+        seeds = [SIGNAL_SEED, agent_identity.key().as_ref(), trade_id_hash.as_ref()],
+        bump = trade_signal.bump,
+        constraint = trade_signal.reporter == signaler.key() @ ErrorCode::Unauthorized
+    )]
+    pub trade_signal: Account<'info, TradeSignal>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateReputationTrack<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub reputation_state: Account<'info, ReputationState>,
+    /// CHECK: Read for the leaderboard verification gate when it exists.
+    #[account(seeds = [VERIFICATION_SEED, reputation_state.agent.as_ref()], bump)]
+    pub verification_record: UncheckedAccount<'info>,
+    /// CHECK: Leaderboard for the track's category; re-ranked when it exists.
+    #[account(mut, seeds = [LEADERBOARD_SEED, &reputation_state.category.to_le_bytes()], bump)]
+    pub leaderboard: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProtocolConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: Protocol config under any earlier layout; owner, discriminator and admin are
+    /// checked before it is grown.
+    #[account(mut, seeds = [PROTOCOL_CONFIG_SEED], bump)]
+    pub protocol_config: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Program account under an earlier layout; owner and discriminator are checked
+    /// before it is grown.
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateDistributionReceipt<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Distribution receipt under an earlier layout; owner and discriminator are checked
+    /// before it is grown.
+    #[account(mut)]
+    pub distribution_receipt: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(category_id: u16)]
+pub struct InitializeCategoryReputation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(seeds = [CATEGORY_SEED, &category_id.to_le_bytes()], bump = category.bump)]
+    pub category: Account<'info, ReputationCategory>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [REPUTATION_SEED, agent_identity.key().as_ref(), &category_id.to_le_bytes()],
+        bump,
+        space = ReputationState::SPACE
+    )]
+    pub reputation_state: Account<'info, ReputationState>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(trade_id_hash: [u8; 32])]
+pub struct SubmitScore<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    #[account(
+        seeds = [ROLE_SEED, &[ROLE_ORACLE], oracle.key().as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub member_stake: Option<Account<'info, MemberStake>>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        mut,
+        seeds = [SIGNAL_SEED, agent_identity.key().as_ref(), trade_id_hash.as_ref()],
+        bump = trade_signal.bump
+    )]
+    pub trade_signal: Account<'info, TradeSignal>,
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        seeds = [VERIFICATION_SEED, agent_identity.key().as_ref()],
+        bump,
+        space = VerificationRecord::SPACE
+    )]
+    pub verification_record: Account<'info, VerificationRecord>,
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        seeds = [REPUTATION_SEED, agent_identity.key().as_ref()],
+        bump,
+        space = ReputationState::SPACE
+    )]
+    pub reputation_state: Account<'info, ReputationState>,
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        seeds = [PNL_STATS_SEED, agent_identity.key().as_ref()],
+        bump,
+        space = PnlStats::SPACE
+    )]
+    pub pnl_stats: Account<'info, PnlStats>,
+    /// CHECK: The agent's scored trade index; created on the agent's first scored trade.
+    #[account(mut, seeds = [SCORED_TRADES_SEED, agent_identity.key().as_ref()], bump)]
+    pub scored_trades: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = oracle,
+        seeds = [
+            EPOCH_SNAPSHOT_SEED,
+            reputation_state.key().as_ref(),
+            &reputation_state.epoch.to_le_bytes()
+        ],
+        bump,
+        space = ReputationSnapshot::SPACE
+    )]
+    pub epoch_snapshot: Option<Account<'info, ReputationSnapshot>>,
+    pub category: Option<Account<'info, ReputationCategory>>,
+    #[account(mut)]
+    pub category_reputation_state: Option<Account<'info, ReputationState>>,
+    /// CHECK: Snapshot PDA for the category track's elapsed epoch; created when the track rolls.
+    #[account(mut)]
+    pub category_epoch_snapshot: Option<UncheckedAccount<'info>>,
+    /// CHECK: Global leaderboard; re-ranked when it exists.
+    #[account(mut, seeds = [LEADERBOARD_SEED, &0u16.to_le_bytes()], bump)]
+    pub leaderboard: UncheckedAccount<'info>,
+    /// CHECK: Leaderboard for the signal's category; re-ranked when it exists.
+    #[account(mut, seeds = [LEADERBOARD_SEED, &trade_signal.category.to_le_bytes()], bump)]
+    pub category_leaderboard: UncheckedAccount<'info>,
+    /// CHECK: Admin risk flag table; penalties and suspensions apply when it exists.
+    #[account(seeds = [RISK_FLAG_TABLE_SEED], bump)]
+    pub risk_flag_table: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], leaf_count: u32)]
+pub struct SubmitSignalBatchRoot<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub signaler: Signer<'info>,
+    #[account(
+        seeds = [ROLE_SEED, &[ROLE_SIGNALER], signaler.key().as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub member_stake: Option<Account<'info, MemberStake>>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        init,
+        payer = signaler,
+        seeds = [SIGNAL_BATCH_SEED, agent_identity.key().as_ref(), root.as_ref()],
+        bump,
+        space = SignalBatch::space(leaf_count)
+    )]
+    pub signal_batch: Account<'info, SignalBatch>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], leaf: CompressedSignalLeaf)]
+pub struct SubmitCompressedScore<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    #[account(
+        seeds = [ROLE_SEED, &[ROLE_ORACLE], oracle.key().as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub member_stake: Option<Account<'info, MemberStake>>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        mut,
+        seeds = [SIGNAL_BATCH_SEED, agent_identity.key().as_ref(), root.as_ref()],
+        bump = signal_batch.bump
+    )]
+    pub signal_batch: Account<'info, SignalBatch>,
+    /// CHECK: The agent's signal address for the leaf's trade id; must be empty.
+    #[account(
+        seeds = [SIGNAL_SEED, agent_identity.key().as_ref(), leaf.trade_id_hash.as_ref()],
+        bump
+    )]
+    pub trade_signal: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        seeds = [VERIFICATION_SEED, agent_identity.key().as_ref()],
+        bump,
+        space = VerificationRecord::SPACE
+    )]
+    pub verification_record: Account<'info, VerificationRecord>,
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        seeds = [REPUTATION_SEED, agent_identity.key().as_ref()],
+        bump,
+        space = ReputationState::SPACE
+    )]
+    pub reputation_state: Account<'info, ReputationState>,
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        seeds = [PNL_STATS_SEED, agent_identity.key().as_ref()],
+        bump,
+        space = PnlStats::SPACE
+    )]
+    pub pnl_stats: Account<'info, PnlStats>,
+    /// CHECK: The agent's scored trade index; created on the agent's first scored trade.
+    #[account(mut, seeds = [SCORED_TRADES_SEED, agent_identity.key().as_ref()], bump)]
+    pub scored_trades: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = oracle,
+        seeds = [
+            EPOCH_SNAPSHOT_SEED,
+            reputation_state.key().as_ref(),
+            &reputation_state.epoch.to_le_bytes()
+        ],
+        bump,
+        space = ReputationSnapshot::SPACE
+    )]
+    pub epoch_snapshot: Option<Account<'info, ReputationSnapshot>>,
+    pub category: Option<Account<'info, ReputationCategory>>,
+    #[account(mut)]
+    pub category_reputation_state: Option<Account<'info, ReputationState>>,
+    /// CHECK: Snapshot PDA for the category track's elapsed epoch; created when the track rolls.
+    #[account(mut)]
+    pub category_epoch_snapshot: Option<UncheckedAccount<'info>>,
+    /// CHECK: Global leaderboard; re-ranked when it exists.
+    #[account(mut, seeds = [LEADERBOARD_SEED, &0u16.to_le_bytes()], bump)]
+    pub leaderboard: UncheckedAccount<'info>,
+    /// CHECK: Leaderboard for the signal's category; re-ranked when it exists.
+    #[account(mut, seeds = [LEADERBOARD_SEED, &leaf.category.to_le_bytes()], bump)]
+    pub category_leaderboard: UncheckedAccount<'info>,
+    /// CHECK: Admin risk flag table; penalties and suspensions apply when it exists.
+    #[account(seeds = [RISK_FLAG_TABLE_SEED], bump)]
+    pub risk_flag_table: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitSignalsBatch<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub signaler: Signer<'info>,
+    #[account(
+        seeds = [ROLE_SEED, &[ROLE_SIGNALER], signaler.key().as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub member_stake: Option<Account<'info, MemberStake>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitScoresBatch<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    #[account(
+        seeds = [ROLE_SEED, &[ROLE_ORACLE], oracle.key().as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub member_stake: Option<Account<'info, MemberStake>>,
+    /// CHECK: Admin risk flag table; penalties and suspensions apply when it exists.
+    #[account(seeds = [RISK_FLAG_TABLE_SEED], bump)]
+    pub risk_flag_table: UncheckedAccount<'info>,
+    /// CHECK: Global leaderboard; re-ranked when it exists.
+    #[account(mut, seeds = [LEADERBOARD_SEED, &0u16.to_le_bytes()], bump)]
+    pub leaderboard: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(trade_id_hash: [u8; 32])]
+pub struct SubmitScoreVote<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    #[account(
+        seeds = [ROLE_SEED, &[ROLE_ORACLE], oracle.key().as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub member_stake: Option<Account<'info, MemberStake>>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        seeds = [SIGNAL_SEED, agent_identity.key().as_ref(), trade_id_hash.as_ref()],
+        bump = trade_signal.bump
+    )]
+    pub trade_signal: Account<'info, TradeSignal>,
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        seeds = [BALLOT_SEED, trade_signal.key().as_ref()],
+        bump,
+        space = ScoreBallot::SPACE
+    )]
+    pub score_ballot: Account<'info, ScoreBallot>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(trade_id_hash: [u8; 32])]
+pub struct FinalizeScore<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    #[account(
+        seeds = [ROLE_SEED, &[ROLE_ORACLE], oracle.key().as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub member_stake: Option<Account<'info, MemberStake>>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        mut,
+        seeds = [SIGNAL_SEED, agent_identity.key().as_ref(), trade_id_hash.as_ref()],
+        bump = trade_signal.bump
+    )]
+    pub trade_signal: Account<'info, TradeSignal>,
+    #[account(
+        mut,
+        close = ballot_opener,
+        seeds = [BALLOT_SEED, trade_signal.key().as_ref()],
+        bump = score_ballot.bump
+    )]
+    pub score_ballot: Account<'info, ScoreBallot>,
+    /// CHECK: Receives the ballot rent; must match the oracle that opened it.
+    #[account(mut, address = score_ballot.opened_by @ ErrorCode::Unauthorized)]
+    pub ballot_opener: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        seeds = [VERIFICATION_SEED, agent_identity.key().as_ref()],
+        bump,
+        space = VerificationRecord::SPACE
+    )]
+    pub verification_record: Account<'info, VerificationRecord>,
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        seeds = [REPUTATION_SEED, agent_identity.key().as_ref()],
+        bump,
+        space = ReputationState::SPACE
+    )]
+    pub reputation_state: Account<'info, ReputationState>,
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        seeds = [PNL_STATS_SEED, agent_identity.key().as_ref()],
+        bump,
+        space = PnlStats::SPACE
+    )]
+    pub pnl_stats: Account<'info, PnlStats>,
+    /// CHECK: The agent's scored trade index; created on the agent's first scored trade.
+    #[account(mut, seeds = [SCORED_TRADES_SEED, agent_identity.key().as_ref()], bump)]
+    pub scored_trades: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = oracle,
+        seeds = [
+            EPOCH_SNAPSHOT_SEED,
+            reputation_state.key().as_ref(),
+            &reputation_state.epoch.to_le_bytes()
+        ],
+        bump,
+        space = ReputationSnapshot::SPACE
+    )]
+    pub epoch_snapshot: Option<Account<'info, ReputationSnapshot>>,
+    pub category: Option<Account<'info, ReputationCategory>>,
+    #[account(mut)]
+    pub category_reputation_state: Option<Account<'info, ReputationState>>,
+    /// CHECK: Snapshot PDA for the category track's elapsed epoch; created when the track rolls.
+    #[account(mut)]
+    pub category_epoch_snapshot: Option<UncheckedAccount<'info>>,
+    /// CHECK: Global leaderboard; re-ranked when it exists.
+    #[account(mut, seeds = [LEADERBOARD_SEED, &0u16.to_le_bytes()], bump)]
+    pub leaderboard: UncheckedAccount<'info>,
+    /// CHECK: Leaderboard for the signal's category; re-ranked when it exists.
+    #[account(mut, seeds = [LEADERBOARD_SEED, &trade_signal.category.to_le_bytes()], bump)]
+    pub category_leaderboard: UncheckedAccount<'info>,
+    /// CHECK: Admin risk flag table; penalties and suspensions apply when it exists.
+    #[account(seeds = [RISK_FLAG_TABLE_SEED], bump)]
+    pub risk_flag_table: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(trade_id_hash: [u8; 32])]
+pub struct CorrectScore<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == authority.key()
+            || protocol_config.arbiter == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        mut,
+        seeds = [SIGNAL_SEED, agent_identity.key().as_ref(), trade_id_hash.as_ref()],
+        bump = trade_signal.bump
+    )]
+    pub trade_signal: Account<'info, TradeSignal>,
+    #[account(
+        mut,
+        seeds = [REPUTATION_SEED, agent_identity.key().as_ref()],
+        bump = reputation_state.bump
+    )]
+    pub reputation_state: Account<'info, ReputationState>,
+    pub category: Option<Account<'info, ReputationCategory>>,
+    #[account(mut)]
+    pub category_reputation_state: Option<Account<'info, ReputationState>>,
+    /// CHECK: Read for the leaderboard verification gate when it exists.
+    #[account(seeds = [VERIFICATION_SEED, agent_identity.key().as_ref()], bump)]
+    pub verification_record: UncheckedAccount<'info>,
+    /// CHECK: Global leaderboard; re-ranked when it exists.
+    #[account(mut, seeds = [LEADERBOARD_SEED, &0u16.to_le_bytes()], bump)]
+    pub leaderboard: UncheckedAccount<'info>,
+    /// CHECK: Leaderboard for the signal's category; re-ranked when it exists.
+    #[account(mut, seeds = [LEADERBOARD_SEED, &trade_signal.category.to_le_bytes()], bump)]
+    pub category_leaderboard: UncheckedAccount<'info>,
+    /// CHECK: Admin risk flag table; penalties and suspensions apply when it exists.
+    #[account(seeds = [RISK_FLAG_TABLE_SEED], bump)]
+    pub risk_flag_table: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseReputationEpoch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub reputation_state: Account<'info, ReputationState>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            EPOCH_SNAPSHOT_SEED,
+            reputation_state.key().as_ref(),
+            &reputation_state.epoch.to_le_bytes()
+        ],
+        bump,
+        space = ReputationSnapshot::SPACE
+    )]
+    pub epoch_snapshot: Account<'info, ReputationSnapshot>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(trade_id_hash: [u8; 32])]
+pub struct OpenChallenge<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    pub role_assignment: Option<Account<'info, RoleAssignment>>,
+    pub member_stake: Option<Account<'info, MemberStake>>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        mut,
+        seeds = [SIGNAL_SEED, agent_identity.key().as_ref(), trade_id_hash.as_ref()],
+        bump = trade_signal.bump
+    )]
+    pub trade_signal: Account<'info, TradeSignal>,
+    #[account(
+        init,
+        payer = challenger,
+        seeds = [CHALLENGE_SEED, trade_signal.key().as_ref()],
+        bump,
+        space = ScoreChallenge::SPACE
+    )]
+    pub score_challenge: Account<'info, ScoreChallenge>,
+    pub settlement_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub challenger_token_account: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = challenger,
+        seeds = [CHALLENGE_VAULT_SEED, score_challenge.key().as_ref()],
+        bump,
+        token::mint = settlement_mint,
+        token::authority = vault_authority
+    )]
+    pub challenge_vault: Account<'info, TokenAccount>,
+    /// CHECK: PDA authority of the challenge bond vault.
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(trade_id_hash: [u8; 32])]
+pub struct ResolveChallenge<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.arbiter == arbiter.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub arbiter: Signer<'info>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        mut,
+        seeds = [SIGNAL_SEED, agent_identity.key().as_ref(), trade_id_hash.as_ref()],
+        bump = trade_signal.bump
+    )]
+    pub trade_signal: Account<'info, TradeSignal>,
+    #[account(
+        mut,
+        seeds = [CHALLENGE_SEED, trade_signal.key().as_ref()],
+        bump = score_challenge.bump
+    )]
+    pub score_challenge: Account<'info, ScoreChallenge>,
+    #[account(
+        mut,
+        seeds = [REPUTATION_SEED, agent_identity.key().as_ref()],
+        bump = reputation_state.bump
+    )]
+    pub reputation_state: Account<'info, ReputationState>,
+    pub category: Option<Account<'info, ReputationCategory>>,
+    #[account(mut)]
+    pub category_reputation_state: Option<Account<'info, ReputationState>>,
+    #[account(
+        mut,
+        seeds = [PNL_STATS_SEED, agent_identity.key().as_ref()],
+        bump = pnl_stats.bump
+    )]
+    pub pnl_stats: Account<'info, PnlStats>,
+    /// CHECK: Read for the leaderboard verification gate when it exists.
+    #[account(seeds = [VERIFICATION_SEED, agent_identity.key().as_ref()], bump)]
+    pub verification_record: UncheckedAccount<'info>,
+    /// CHECK: Global leaderboard; re-ranked when it exists.
+    #[account(mut, seeds = [LEADERBOARD_SEED, &0u16.to_le_bytes()], bump)]
+    pub leaderboard: UncheckedAccount<'info>,
+    /// CHECK: Leaderboard for the signal's category; re-ranked when it exists.
+    #[account(mut, seeds = [LEADERBOARD_SEED, &trade_signal.category.to_le_bytes()], bump)]
+    pub category_leaderboard: UncheckedAccount<'info>,
+    /// CHECK: Admin risk flag table; an upheld signal's flags are uncounted when it exists.
+    #[account(seeds = [RISK_FLAG_TABLE_SEED], bump)]
+    pub risk_flag_table: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [CHALLENGE_VAULT_SEED, score_challenge.key().as_ref()],
+        bump
+    )]
+    pub challenge_vault: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [SETTLEMENT_MINT_SEED, challenge_vault.mint.as_ref()],
+        bump = settlement_mint_config.bump
+    )]
+    pub settlement_mint_config: Option<Account<'info, SettlementMintConfig>>,
+    /// CHECK: PDA signer for transfers out of the challenge bond vault.
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    /// CHECK: Receives the bond vault rent; must match the recorded challenger.
+    #[account(mut, address = score_challenge.challenger @ ErrorCode::Unauthorized)]
+    pub challenger: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub challenger_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub protocol_treasury_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct GetReputationScore<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        seeds = [REPUTATION_SEED, agent_identity.key().as_ref()],
+        bump = reputation_state.bump
+    )]
+    pub reputation_state: Account<'info, ReputationState>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub member: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = member,
+        seeds = [STAKE_SEED, member.key().as_ref()],
+        bump,
+        space = MemberStake::SPACE
+    )]
+    pub member_stake: Account<'info, MemberStake>,
+    /// Opens the vault on first stake; the vault keeps that mint after the settlement mint changes.
+    pub stake_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub member_token_account: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = member,
+        seeds = [STAKE_VAULT_SEED, member.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = vault_authority
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    /// CHECK: PDA authority of the member stake vault.
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub member: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [STAKE_SEED, member.key().as_ref()],
+        bump = member_stake.bump
+    )]
+    pub member_stake: Account<'info, MemberStake>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnstaked<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub member: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [STAKE_SEED, member.key().as_ref()],
+        bump = member_stake.bump
+    )]
+    pub member_stake: Account<'info, MemberStake>,
+    #[account(mut, seeds = [STAKE_VAULT_SEED, member.key().as_ref()], bump)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    /// CHECK: PDA signer for transfers out of the stake vault.
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub member_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SlashStake<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == authority.key()
+            || protocol_config.arbiter == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub authority: Signer<'info>,
+    /// CHECK: Staked member being slashed.
+    pub member: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [STAKE_SEED, member.key().as_ref()],
+        bump = member_stake.bump
+    )]
+    pub member_stake: Account<'info, MemberStake>,
+    #[account(mut, seeds = [STAKE_VAULT_SEED, member.key().as_ref()], bump)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [SETTLEMENT_MINT_SEED, stake_vault.mint.as_ref()],
+        bump = settlement_mint_config.bump
+    )]
+    pub settlement_mint_config: Option<Account<'info, SettlementMintConfig>>,
+    /// CHECK: PDA signer for transfers out of the stake vault.
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub protocol_treasury_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(reference: [u8; 32])]
+pub struct DistributeSettlement<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    #[account(
+        seeds = [ROLE_SEED, &[ROLE_REVENUE_OPERATOR], operator.key().as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(seeds = [SPLIT_SEED, agent_identity.key().as_ref()], bump = split_config.bump)]
+    pub split_config: Account<'info, RevenueSplitConfig>,
+    #[account(
+        seeds = [SETTLEMENT_MINT_SEED, settlement_mint_config.mint.as_ref()],
+        bump = settlement_mint_config.bump
+    )]
+    pub settlement_mint_config: Option<Account<'info, SettlementMintConfig>>,
+    #[account(mut)]
+    pub settlement_vault: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(address = settlement_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub settlement_mint: InterfaceAccount<'info, token_interface::Mint>,
+    /// CHECK: PDA signer for transfer from settlement vault.
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    /// CHECK: Claim ledger of the vault; read when it exists so pushes never spend accrued claims.
+    #[account(seeds = [CLAIM_LEDGER_SEED, settlement_vault.key().as_ref()], bump)]
+    pub claim_ledger: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub agent_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(
+        mut,
+        seeds = [VESTING_SEED, agent_identity.key().as_ref(), settlement_vault.mint.as_ref()],
+        bump = vesting_escrow.bump
+    )]
+    pub vesting_escrow: Option<Account<'info, VestingEscrow>>,
+    #[account(mut)]
+    pub vesting_vault: Option<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(mut)]
+    pub platform_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(mut)]
+    pub referrer_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(mut)]
+    pub reserve_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(
+        mut,
+        seeds = [RESERVE_SEED, reserve.scope.as_ref(), reserve.mint.as_ref()],
+        bump = reserve.bump
+    )]
+    pub reserve: Option<Account<'info, ReserveVault>>,
+    #[account(mut)]
+    pub protocol_treasury_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    /// CHECK: Admin fee schedule; applied when it exists.
+    #[account(seeds = [FEE_SCHEDULE_SEED], bump)]
+    pub fee_schedule: UncheckedAccount<'info>,
+    /// CHECK: Admin referral levels; the referrer share is split up the graph when it exists.
+    #[account(seeds = [REFERRAL_PROGRAM_SEED], bump)]
+    pub referral_program: UncheckedAccount<'info>,
+    #[account(
+        seeds = [VERIFICATION_SEED, agent_identity.key().as_ref()],
+        bump = verification_record.bump
+    )]
+    pub verification_record: Option<Account<'info, VerificationRecord>>,
+    #[account(
+        seeds = [REPUTATION_SEED, agent_identity.key().as_ref()],
+        bump = reputation_state.bump
+    )]
+    pub reputation_state: Option<Account<'info, ReputationState>>,
+    #[account(
+        init_if_needed,
+        payer = operator,
+        seeds = [
+            SETTLEMENT_VOLUME_SEED,
+            agent_identity.key().as_ref(),
+            settlement_vault.mint.as_ref()
+        ],
+        bump,
+        space = SettlementVolume::SPACE
+    )]
+    pub settlement_volume: Account<'info, SettlementVolume>,
+    #[account(
+        init,
+        payer = operator,
+        seeds = [RECEIPT_SEED, agent_identity.key().as_ref(), reference.as_ref()],
+        bump,
+        space = DistributionReceipt::SPACE
+    )]
+    pub distribution_receipt: Account<'info, DistributionReceipt>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(reference: [u8; 32])]
+pub struct DistributeSettlementSol<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    #[account(
+        seeds = [ROLE_SEED, &[ROLE_REVENUE_OPERATOR], operator.key().as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(seeds = [SPLIT_SEED, agent_identity.key().as_ref()], bump = split_config.bump)]
+    pub split_config: Account<'info, RevenueSplitConfig>,
+    #[account(mut, seeds = [SOL_VAULT_SEED], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    /// CHECK: Lamport recipient, checked against agent_identity.agent_wallet.
+    #[account(mut, address = agent_identity.agent_wallet @ ErrorCode::InvalidAddress)]
+    pub agent_wallet: UncheckedAccount<'info>,
+    /// CHECK: Lamport recipient, checked against the split when platform_bps > 0.
+    #[account(mut)]
+    pub platform_wallet: UncheckedAccount<'info>,
+    /// CHECK: Lamport recipient, checked against the split when referrer_bps > 0.
+    #[account(mut)]
+    pub referrer_wallet: UncheckedAccount<'info>,
+    /// CHECK: Lamport recipient, checked against the split when reserve_bps > 0.
+    #[account(mut)]
+    pub reserve_wallet: UncheckedAccount<'info>,
+    /// CHECK: Lamport recipient, checked against protocol_config.sol_treasury.
+    #[account(mut)]
+    pub protocol_treasury: UncheckedAccount<'info>,
+    /// CHECK: Admin fee schedule; applied when it exists.
+    #[account(seeds = [FEE_SCHEDULE_SEED], bump)]
+    pub fee_schedule: UncheckedAccount<'info>,
+    /// CHECK: Admin referral levels; upline levels reject a referrer share on this path.
+    #[account(seeds = [REFERRAL_PROGRAM_SEED], bump)]
+    pub referral_program: UncheckedAccount<'info>,
+    #[account(
+        seeds = [VERIFICATION_SEED, agent_identity.key().as_ref()],
+        bump = verification_record.bump
+    )]
+    pub verification_record: Option<Account<'info, VerificationRecord>>,
+    #[account(
+        seeds = [REPUTATION_SEED, agent_identity.key().as_ref()],
+        bump = reputation_state.bump
+    )]
+    pub reputation_state: Option<Account<'info, ReputationState>>,
+    #[account(
+        init_if_needed,
+        payer = operator,
+        seeds = [
+            SETTLEMENT_VOLUME_SEED,
+            agent_identity.key().as_ref(),
+            Pubkey::default().as_ref()
+        ],
+        bump,
+        space = SettlementVolume::SPACE
+    )]
+    pub settlement_volume: Account<'info, SettlementVolume>,
+    #[account(
+        init,
+        payer = operator,
+        seeds = [RECEIPT_SEED, agent_identity.key().as_ref(), reference.as_ref()],
+        bump,
+        space = DistributionReceipt::SPACE
+    )]
+    pub distribution_receipt: Account<'info, DistributionReceipt>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(reference: [u8; 32])]
+pub struct AccrueSettlement<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    #[account(
+        seeds = [ROLE_SEED, &[ROLE_REVENUE_OPERATOR], operator.key().as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(seeds = [SPLIT_SEED, agent_identity.key().as_ref()], bump = split_config.bump)]
+    pub split_config: Account<'info, RevenueSplitConfig>,
+    #[account(
+        seeds = [SETTLEMENT_MINT_SEED, settlement_mint_config.mint.as_ref()],
+        bump = settlement_mint_config.bump
+    )]
+    pub settlement_mint_config: Option<Account<'info, SettlementMintConfig>>,
+    pub settlement_vault: InterfaceAccount<'info, token_interface::TokenAccount>,
+    /// CHECK: PDA owner of the settlement vault.
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub protocol_treasury_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    /// CHECK: Reserve owner, checked against the split when reserve_bps > 0.
+    pub reserve_wallet: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = operator,
+        seeds = [CLAIM_LEDGER_SEED, settlement_vault.key().as_ref()],
+        bump,
+        space = ClaimLedger::SPACE
+    )]
+    pub claim_ledger: Account<'info, ClaimLedger>,
+    /// CHECK: Admin fee schedule; applied when it exists.
+    #[account(seeds = [FEE_SCHEDULE_SEED], bump)]
+    pub fee_schedule: UncheckedAccount<'info>,
+    /// CHECK: Admin referral levels; upline levels reject a referrer share on this path.
+    #[account(seeds = [REFERRAL_PROGRAM_SEED], bump)]
+    pub referral_program: UncheckedAccount<'info>,
+    #[account(
+        seeds = [VERIFICATION_SEED, agent_identity.key().as_ref()],
+        bump = verification_record.bump
+    )]
+    pub verification_record: Option<Account<'info, VerificationRecord>>,
+    #[account(
+        seeds = [REPUTATION_SEED, agent_identity.key().as_ref()],
+        bump = reputation_state.bump
+    )]
+    pub reputation_state: Option<Account<'info, ReputationState>>,
+    #[account(
+        init_if_needed,
+        payer = operator,
+        seeds = [
+            SETTLEMENT_VOLUME_SEED,
+            agent_identity.key().as_ref(),
+            settlement_vault.mint.as_ref()
+        ],
+        bump,
+        space = SettlementVolume::SPACE
+    )]
+    pub settlement_volume: Account<'info, SettlementVolume>,
+    #[account(
+        init,
+        payer = operator,
+        seeds = [RECEIPT_SEED, agent_identity.key().as_ref(), reference.as_ref()],
+        bump,
+        space = DistributionReceipt::SPACE
+    )]
+    pub distribution_receipt: Account<'info, DistributionReceipt>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [CLAIMABLE_SEED, settlement_vault.key().as_ref(), owner.key().as_ref()],
+        bump = claimable_balance.bump
+    )]
+    pub claimable_balance: Account<'info, ClaimableBalance>,
+    #[account(
+        mut,
+        seeds = [CLAIM_LEDGER_SEED, settlement_vault.key().as_ref()],
+        bump = claim_ledger.bump
+    )]
+    pub claim_ledger: Account<'info, ClaimLedger>,
+    #[account(mut)]
+    pub settlement_vault: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(address = settlement_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub settlement_mint: InterfaceAccount<'info, token_interface::Mint>,
+    /// CHECK: PDA signer for transfer from settlement vault.
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub destination_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct OpenVestingEscrow<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    pub settlement_mint: InterfaceAccount<'info, token_interface::Mint>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [VESTING_SEED, agent_identity.key().as_ref(), settlement_mint.key().as_ref()],
+        bump,
+        space = VestingEscrow::SPACE
+    )]
+    pub vesting_escrow: Account<'info, VestingEscrow>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [VESTING_VAULT_SEED, vesting_escrow.key().as_ref()],
+        bump,
+        token::mint = settlement_mint,
+        token::authority = vault_authority,
+        token::token_program = token_program
+    )]
+    pub vesting_vault: InterfaceAccount<'info, token_interface::TokenAccount>,
+    /// CHECK: PDA authority of the vesting vault.
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseVesting<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        mut,
+        seeds = [VESTING_SEED, agent_identity.key().as_ref(), vesting_escrow.mint.as_ref()],
+        bump = vesting_escrow.bump
+    )]
+    pub vesting_escrow: Account<'info, VestingEscrow>,
+    #[account(mut, address = vesting_escrow.vault @ ErrorCode::InvalidAddress)]
+    pub vesting_vault: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(address = vesting_escrow.mint @ ErrorCode::InvalidTokenMint)]
+    pub settlement_mint: InterfaceAccount<'info, token_interface::Mint>,
+    /// CHECK: PDA signer for transfer from the vesting vault.
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    /// CHECK: Verification record of the agent; release is blocked while it is suspended.
+    #[account(seeds = [VERIFICATION_SEED, agent_identity.key().as_ref()], bump)]
+    pub verification_record: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub agent_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClawbackVesting<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == authority.key()
+            || protocol_config.arbiter == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(
+        mut,
+        seeds = [VESTING_SEED, agent_identity.key().as_ref(), vesting_escrow.mint.as_ref()],
+        bump = vesting_escrow.bump
+    )]
+    pub vesting_escrow: Account<'info, VestingEscrow>,
+    #[account(mut, address = vesting_escrow.vault @ ErrorCode::InvalidAddress)]
+    pub vesting_vault: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(address = vesting_escrow.mint @ ErrorCode::InvalidTokenMint)]
+    pub settlement_mint: InterfaceAccount<'info, token_interface::Mint>,
+    /// CHECK: PDA signer for transfer from the vesting vault.
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    /// CHECK: Verification record of the agent; clawback requires it to be suspended.
+    #[account(seeds = [VERIFICATION_SEED, agent_identity.key().as_ref()], bump)]
+    pub verification_record: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(scope: Pubkey)]
+pub struct OpenReserveVault<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(seeds = [AGENT_SEED, &agent_identity.id.to_le_bytes()], bump = agent_identity.bump)]
+    pub agent_identity: Option<Account<'info, AgentIdentity>>,
+    pub mint: InterfaceAccount<'info, token_interface::Mint>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [RESERVE_SEED, scope.as_ref(), mint.key().as_ref()],
+        bump,
+        space = ReserveVault::SPACE
+    )]
+    pub reserve: Account<'info, ReserveVault>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = reserve,
+        associated_token::token_program = token_program
+    )]
+    pub reserve_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetReserveLockup<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        mut,
+        seeds = [RESERVE_SEED, reserve.scope.as_ref(), reserve.mint.as_ref()],
+        bump = reserve.bump
+    )]
+    pub reserve: Account<'info, ReserveVault>,
+    #[account(address = reserve.token_account @ ErrorCode::InvalidAddress)]
+    pub reserve_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SyncReserve<'info> {
+    #[account(
+        mut,
+        seeds = [RESERVE_SEED, reserve.scope.as_ref(), reserve.mint.as_ref()],
+        bump = reserve.bump
+    )]
+    pub reserve: Account<'info, ReserveVault>,
+    #[account(address = reserve.token_account @ ErrorCode::InvalidAddress)]
+    pub reserve_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawReserve<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        mut,
+        seeds = [RESERVE_SEED, reserve.scope.as_ref(), reserve.mint.as_ref()],
+        bump = reserve.bump,
+        constraint = reserve.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub reserve: Account<'info, ReserveVault>,
+    #[account(mut, address = reserve.token_account @ ErrorCode::InvalidAddress)]
+    pub reserve_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(address = reserve.mint @ ErrorCode::InvalidTokenMint)]
+    pub mint: InterfaceAccount<'info, token_interface::Mint>,
+    /// CHECK: Verification record of an agent-scoped reserve; withdrawal is blocked while it is
+    /// suspended. It never exists for a platform-scoped reserve.
+    #[account(seeds = [VERIFICATION_SEED, reserve.scope.as_ref()], bump)]
+    pub verification_record: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub destination_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CompensateFromReserve<'info> {
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.arbiter == arbiter.key() @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub arbiter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [RESERVE_SEED, reserve.scope.as_ref(), reserve.mint.as_ref()],
+        bump = reserve.bump
+    )]
+    pub reserve: Account<'info, ReserveVault>,
+    #[account(mut, address = reserve.token_account @ ErrorCode::InvalidAddress)]
+    pub reserve_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(address = reserve.mint @ ErrorCode::InvalidTokenMint)]
+    pub mint: InterfaceAccount<'info, token_interface::Mint>,
+    #[account(mut)]
+    pub destination_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeSettlementBatch<'info> {
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    #[account(
+        seeds = [ROLE_SEED, &[ROLE_REVENUE_OPERATOR], operator.key().as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    #[account(
+        seeds = [SETTLEMENT_MINT_SEED, settlement_mint_config.mint.as_ref()],
+        bump = settlement_mint_config.bump
+    )]
+    pub settlement_mint_config: Option<Account<'info, SettlementMintConfig>>,
+    #[account(mut)]
+    pub settlement_vault: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(address = settlement_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub settlement_mint: InterfaceAccount<'info, token_interface::Mint>,
+    /// CHECK: PDA signer for transfer from settlement vault.
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = protocol_config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    /// CHECK: Claim ledger of the vault; read when it exists so pushes never spend accrued claims.
+    #[account(seeds = [CLAIM_LEDGER_SEED, settlement_vault.key().as_ref()], bump)]
+    pub claim_ledger: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub protocol_treasury_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    /// CHECK: Admin fee schedule; applied per entry when it exists.
+    #[account(seeds = [FEE_SCHEDULE_SEED], bump)]
+    pub fee_schedule: UncheckedAccount<'info>,
+    /// CHECK: Admin referral levels; upline levels reject a referrer share on this path.
+    #[account(seeds = [REFERRAL_PROGRAM_SEED], bump)]
+    pub referral_program: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
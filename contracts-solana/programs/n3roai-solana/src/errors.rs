@@ -42,4 +42,148 @@ pub enum ErrorCode {
     InvalidTokenAccountOwner,
     #[msg("Invalid token mint")]
     InvalidTokenMint,
+    #[msg("Invalid score half-life")]
+    InvalidHalfLife,
+    #[msg("Account cannot be migrated")]
+    InvalidMigrationAccount,
+    #[msg("Invalid score quorum")]
+    InvalidQuorum,
+    #[msg("Score aggregation is enabled")]
+    ScoreAggregationEnabled,
+    #[msg("Score aggregation is disabled")]
+    ScoreAggregationDisabled,
+    #[msg("Score ballot is full")]
+    BallotFull,
+    #[msg("Score ballot is closed")]
+    BallotClosed,
+    #[msg("Score ballot is not ready")]
+    BallotNotReady,
+    #[msg("Oracle already voted")]
+    DuplicateScoreVote,
+    #[msg("Score challenges are disabled")]
+    ChallengesDisabled,
+    #[msg("Challenge window has closed")]
+    ChallengeWindowClosed,
+    #[msg("Challenge is not open")]
+    ChallengeNotOpen,
+    #[msg("Score is under challenge")]
+    ScoreChallenged,
+    #[msg("Insufficient stake for role")]
+    InsufficientStake,
+    #[msg("Unstake queue is full")]
+    UnstakeQueueFull,
+    #[msg("Nothing to withdraw")]
+    NothingToWithdraw,
+    #[msg("Invalid risk flag")]
+    InvalidRiskFlag,
+    #[msg("Invalid reputation category")]
+    InvalidCategory,
+    #[msg("Invalid reputation epoch length")]
+    InvalidEpochLength,
+    #[msg("Reputation epochs are disabled")]
+    EpochsDisabled,
+    #[msg("Reputation epoch has not elapsed")]
+    EpochNotElapsed,
+    #[msg("Epoch snapshot account required to roll the reputation epoch")]
+    EpochSnapshotRequired,
+    #[msg("Reputation epoch must be closed first")]
+    EpochNotClosed,
+    #[msg("Score cannot be corrected")]
+    ScoreNotCorrectable,
+    #[msg("Signal commit-reveal is disabled")]
+    CommitRevealDisabled,
+    #[msg("Signals must be committed before they are revealed")]
+    SignalCommitRequired,
+    #[msg("Revealed signal does not match the commitment")]
+    CommitmentMismatch,
+    #[msg("Signal commitment has expired")]
+    CommitmentExpired,
+    #[msg("Signal commitment has not expired")]
+    CommitmentNotExpired,
+    #[msg("Invalid batch size")]
+    InvalidBatch,
+    #[msg("Batch account does not match its entry")]
+    InvalidBatchAccount,
+    #[msg("Duplicate entry in batch")]
+    DuplicateBatchEntry,
+    #[msg("Trade signal already exists")]
+    SignalAlreadyExists,
+    #[msg("Invalid signal batch leaf count")]
+    InvalidLeafCount,
+    #[msg("Invalid Merkle proof")]
+    InvalidMerkleProof,
+    #[msg("Signal leaf already scored")]
+    LeafAlreadyScored,
+    #[msg("Invalid PnL report")]
+    InvalidPnl,
+    #[msg("PnL already reported for this signal")]
+    PnlAlreadyReported,
+    #[msg("Invalid leaderboard")]
+    InvalidLeaderboard,
+    #[msg("Invalid aggregation model")]
+    InvalidAggregationModel,
+    #[msg("Invalid split recipients")]
+    InvalidSplitRecipients,
+    #[msg("Settlement reference already distributed")]
+    ReferenceAlreadyDistributed,
+    #[msg("SOL vault balance would fall below rent-exempt minimum")]
+    InsufficientVaultBalance,
+    #[msg("Settlement mint is disabled")]
+    SettlementMintDisabled,
+    #[msg("Mint has an unsupported token extension")]
+    UnsupportedMintExtension,
+    #[msg("Transfer fee mints are not supported here")]
+    TransferFeeNotSupported,
+    #[msg("Vault balance does not cover outstanding claims")]
+    ClaimsUnderfunded,
+    #[msg("Invalid claimable balance account")]
+    InvalidClaimAccount,
+    #[msg("Claim exceeds claimable balance")]
+    InsufficientClaimBalance,
+    #[msg("Invalid fee schedule")]
+    InvalidFeeSchedule,
+    #[msg("Invalid vesting duration")]
+    InvalidVestingDuration,
+    #[msg("Vesting escrow required for this split")]
+    VestingEscrowRequired,
+    #[msg("Vesting splits must be settled with distribute_settlement")]
+    VestingNotSupported,
+    #[msg("Agent is suspended")]
+    AgentSuspended,
+    #[msg("Agent is not suspended")]
+    AgentNotSuspended,
+    #[msg("Clawback exceeds escrowed balance")]
+    InsufficientEscrowBalance,
+    #[msg("Invalid reserve lockup period")]
+    InvalidReserveLockup,
+    #[msg("Invalid reserve scope")]
+    InvalidReserveScope,
+    #[msg("Reserve funds are still locked")]
+    ReserveLocked,
+    #[msg("Insufficient reserve balance")]
+    InsufficientReserveBalance,
+    #[msg("Invalid referral levels")]
+    InvalidReferralLevels,
+    #[msg("Invalid referral account")]
+    InvalidReferralAccount,
+    #[msg("Referral parent would create a cycle")]
+    ReferralCycle,
+    #[msg("Referral chain too long")]
+    ReferralChainTooLong,
+    #[msg("Vesting escrow has too many open tranches")]
+    VestingTrancheLimit,
+    #[msg("Invalid risk flag table")]
+    InvalidRiskFlagTable,
+    #[msg("Invalid risk flag weight")]
+    InvalidRiskFlagWeight,
+    #[msg("Invalid epoch snapshot account")]
+    InvalidEpochSnapshot,
+    #[msg("Referral levels are not supported on this settlement path")]
+    ReferralLevelsNotSupported,
+    #[msg("A program-owned reserve cannot receive this settlement")]
+    ReserveNotSupported,
+    #[msg("Trade already scored for this agent")]
+    TradeAlreadyScored,
+    #[msg("Invalid scored trade index")]
+    InvalidScoredTrades,
 }
@@ -1,12 +1,34 @@
+use std::cmp::{Ordering, Reverse};
+
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount};
+use anchor_lang::Discriminator;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_interface::{self, TokenInterface, TransferChecked, TransferCheckedWithFee};
 
 use crate::constants::{
-    MAX_BPS, ROLE_ORACLE, ROLE_REVENUE_OPERATOR, ROLE_SIGNALER, ROLE_VERIFICATION_OPERATOR,
-    STATUS_VERIFIED,
+    AGGREGATION_BETA, AGGREGATION_WINDOW, CHALLENGE_NONE, EPOCH_SNAPSHOT_SEED, MAX_BPS,
+    MAX_SCORE_WINDOW, MAX_SPLIT_RECIPIENTS, MAX_VESTING_TRANCHES, REFERRAL_SEED, RISK_FLAG_BITS,
+    ROLE_ORACLE, ROLE_REVENUE_OPERATOR, ROLE_SIGNALER, ROLE_VERIFICATION_OPERATOR,
+    SCORED_TRADES_SEED, SECONDS_PER_DAY, STATUS_SUSPENDED, STATUS_VERIFIED, VOLUME_WINDOW_DAYS,
 };
 use crate::errors::ErrorCode;
-use crate::state::{ProtocolConfig, RoleAssignment, VerificationRecord};
+use crate::math::{
+    beta_mean, decay_score, effective_score, ema_retention_bps, ema_update, score_bucket,
+    welford_add, welford_remove, window_mean, EMPTY_WINDOW_SLOT,
+};
+use crate::state::{
+    ClaimLedger, FeeSchedule, Leaderboard, LeaderboardEntry, MemberStake, PnlStats, ProtocolConfig,
+    ReferralAccount, ReferralProgram, ReputationCategory, ReputationSnapshot, ReputationState,
+    ReserveTranche, ReserveVault, RevenueSplitConfig, RiskFlagTable, RoleAssignment, ScoredTrades,
+    SettlementMintConfig, SettlementVolume, SplitRecipient, TradeSignal, VerificationRecord,
+    VestingEscrow, VestingTranche,
+};
 
 pub fn transfer_from_vault<'info>(
     token_program: &Program<'info, Token>,
@@ -38,6 +60,690 @@ pub fn transfer_from_vault<'info>(
     Ok(())
 }
 
+pub fn transfer_to_vault<'info>(
+    token_program: &Program<'info, Token>,
+    from: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    authority: &Signer<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let cpi_accounts = Transfer {
+        from: from.to_account_info(),
+        to: to.to_account_info(),
+        authority: authority.to_account_info(),
+    };
+
+    token::transfer(
+        CpiContext::new(token_program.to_account_info(), cpi_accounts),
+        amount,
+    )?;
+
+    Ok(())
+}
+
+/// Checks the vault and treasury for a token distribution and returns the protocol fee.
+/// With a registry entry the mint's own vault, treasury and fee override apply; without one
+/// the legacy `ProtocolConfig` vault and treasury are used, so funds left in an old vault stay
+/// reachable after the settlement mint changes.
+pub fn settlement_route(
+    protocol: &ProtocolConfig,
+    mint_config: Option<&SettlementMintConfig>,
+    vault: &InterfaceAccount<token_interface::TokenAccount>,
+    treasury: &InterfaceAccount<token_interface::TokenAccount>,
+) -> Result<u16> {
+    let Some(entry) = mint_config else {
+        require!(
+            vault.key() == protocol.settlement_vault,
+            ErrorCode::InvalidSettlementVault
+        );
+        require!(
+            treasury.key() == protocol.protocol_treasury_token_account,
+            ErrorCode::InvalidTreasuryAccount
+        );
+        if protocol.enforce_settlement_token {
+            require!(
+                vault.mint == protocol.settlement_mint,
+                ErrorCode::SettlementTokenMismatch
+            );
+        }
+        return Ok(protocol.protocol_fee_bps);
+    };
+
+    require!(entry.enabled, ErrorCode::SettlementMintDisabled);
+    require!(
+        vault.key() == entry.vault,
+        ErrorCode::InvalidSettlementVault
+    );
+    require!(
+        treasury.key() == entry.treasury_token_account,
+        ErrorCode::InvalidTreasuryAccount
+    );
+
+    Ok(if entry.fee_override {
+        entry.protocol_fee_bps
+    } else {
+        protocol.protocol_fee_bps
+    })
+}
+
+/// Checks the treasury that receives bonds or slashed stake held in `mint`. The mint's registry
+/// entry names its treasury even when the mint is disabled, so funds posted before a settlement
+/// mint change can still be paid out; without an entry the `ProtocolConfig` treasury applies.
+pub fn require_mint_treasury(
+    protocol: &ProtocolConfig,
+    mint_config: Option<&SettlementMintConfig>,
+    mint: Pubkey,
+    treasury: &Account<TokenAccount>,
+) -> Result<()> {
+    require!(treasury.mint == mint, ErrorCode::InvalidTokenMint);
+    let expected = match mint_config {
+        Some(entry) => {
+            require!(entry.mint == mint, ErrorCode::InvalidTokenMint);
+            entry.treasury_token_account
+        }
+        None => protocol.protocol_treasury_token_account,
+    };
+    require!(
+        treasury.key() == expected,
+        ErrorCode::InvalidTreasuryAccount
+    );
+    Ok(())
+}
+
+/// Effective protocol fee for one settlement: the lowest fee among the schedule tiers the
+/// agent qualifies for, or `base_fee_bps` when there is no schedule or no tier matches. The
+/// trailing volume used excludes `amount`, which is recorded afterwards.
+#[allow(clippy::too_many_arguments)]
+pub fn scheduled_fee_bps(
+    schedule: &AccountInfo,
+    base_fee_bps: u16,
+    config: &ProtocolConfig,
+    verification: Option<&VerificationRecord>,
+    reputation: Option<&ReputationState>,
+    volume: &mut SettlementVolume,
+    amount: u64,
+    now: i64,
+) -> Result<u16> {
+    let day = now / SECONDS_PER_DAY;
+    roll_volume(volume, day);
+    let trailing = volume
+        .daily_volume
+        .iter()
+        .fold(0u64, |total, day_volume| total.saturating_add(*day_volume));
+    let slot = (day as usize) % VOLUME_WINDOW_DAYS;
+    volume.daily_volume[slot] = volume.daily_volume[slot].saturating_add(amount);
+
+    if schedule.data_is_empty() {
+        return Ok(base_fee_bps);
+    }
+    require!(schedule.owner == &crate::ID, ErrorCode::InvalidFeeSchedule);
+    let data = schedule.try_borrow_data()?;
+    let schedule = FeeSchedule::try_deserialize(&mut &data[..])?;
+
+    let verified = verification.is_some_and(|record| is_verified(record, now));
+    let rolling_score = reputation.map_or(0, |track| {
+        current_rolling_score(track, config.score_half_life, now)
+    });
+
+    Ok(schedule
+        .tiers
+        .iter()
+        .filter(|tier| {
+            (verified || !tier.require_verified)
+                && rolling_score >= tier.min_rolling_score
+                && trailing >= tier.min_volume
+        })
+        .map(|tier| tier.fee_bps)
+        .min()
+        .unwrap_or(base_fee_bps))
+}
+
+/// Clears the daily buckets that fell out of the trailing window since the last update.
+fn roll_volume(volume: &mut SettlementVolume, day: i64) {
+    if day <= volume.last_day {
+        return;
+    }
+
+    let elapsed = (day - volume.last_day).min(VOLUME_WINDOW_DAYS as i64);
+    for offset in 1..=elapsed {
+        let slot = ((volume.last_day + offset) as usize) % VOLUME_WINDOW_DAYS;
+        volume.daily_volume[slot] = 0;
+    }
+    volume.last_day = day;
+}
+
+/// Moves the share of each tranche that has vested since the last update into `vested`, linearly
+/// towards the tranche's end. Every tranche is checkpointed together, so the last update is
+/// never before a tranche's start and the remaining amount vests evenly over what is left.
+pub fn checkpoint_vesting(escrow: &mut VestingEscrow, now: i64) -> Result<()> {
+    if now <= escrow.last_update {
+        return Ok(());
+    }
+
+    let mut released = 0u64;
+    for tranche in escrow.tranches.iter_mut() {
+        let amount = if now >= tranche.end {
+            tranche.remaining
+        } else {
+            let elapsed = (now - escrow.last_update) as u128;
+            let remaining = (tranche.end - escrow.last_update) as u128;
+            ((tranche.remaining as u128 * elapsed) / remaining) as u64
+        };
+        tranche.remaining -= amount;
+        released += amount;
+    }
+    escrow.tranches.retain(|tranche| tranche.remaining > 0);
+
+    escrow.locked -= released;
+    escrow.vested = escrow
+        .vested
+        .checked_add(released)
+        .ok_or(ErrorCode::MathOverflow)?;
+    escrow.last_update = now;
+    Ok(())
+}
+
+/// Adds a deposit that vests linearly from now over at least `duration`. The end is rounded up
+/// to a day boundary so deposits made on the same day share a tranche; merging is exact because
+/// a tranche's remaining amount always vests evenly from the last update to its end.
+pub fn deposit_vesting(
+    escrow: &mut VestingEscrow,
+    amount: u64,
+    duration: i64,
+    now: i64,
+) -> Result<()> {
+    checkpoint_vesting(escrow, now)?;
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let end = now
+        .checked_add(duration)
+        .and_then(|end| end.checked_add(SECONDS_PER_DAY - 1))
+        .ok_or(ErrorCode::MathOverflow)?
+        .div_euclid(SECONDS_PER_DAY)
+        * SECONDS_PER_DAY;
+    match escrow
+        .tranches
+        .iter_mut()
+        .find(|tranche| tranche.end == end)
+    {
+        Some(tranche) => {
+            tranche.remaining = tranche
+                .remaining
+                .checked_add(amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        None => {
+            require!(
+                escrow.tranches.len() < MAX_VESTING_TRANCHES,
+                ErrorCode::VestingTrancheLimit
+            );
+            escrow.tranches.push(VestingTranche {
+                remaining: amount,
+                end,
+            });
+        }
+    }
+
+    escrow.locked = escrow
+        .locked
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    escrow.total_deposited = escrow
+        .total_deposited
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Upline payouts carved out of a referrer share of `pool`, read only if the referral program
+/// exists. Walks from `referrer` one level per configured level after the first, taking the
+/// referral account of the current referrer and, when it has a parent, the parent's token
+/// account from `accounts`. The walk stops at an unregistered referrer or a root and must consume
+/// every account. The direct referrer keeps whatever the upline is not paid, which covers level 1
+/// and any levels the chain does not reach.
+pub fn referral_payouts<'info>(
+    referral_program: &AccountInfo,
+    referrer: Pubkey,
+    pool: u64,
+    mint: Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+) -> Result<Vec<(InterfaceAccount<'info, token_interface::TokenAccount>, u64)>> {
+    let mut payouts = Vec::new();
+    if referral_program.data_is_empty() {
+        require!(accounts.is_empty(), ErrorCode::InvalidReferralAccount);
+        return Ok(payouts);
+    }
+    require!(
+        referral_program.owner == &crate::ID,
+        ErrorCode::InvalidReferralAccount
+    );
+    let data = referral_program.try_borrow_data()?;
+    let program = ReferralProgram::try_deserialize(&mut &data[..])?;
+
+    let mut accounts = accounts.iter();
+    let mut current = referrer;
+    for level_bps in program.level_bps.iter().skip(1) {
+        let info = accounts.next().ok_or(ErrorCode::InvalidReferralAccount)?;
+        let (expected, _) =
+            Pubkey::find_program_address(&[REFERRAL_SEED, current.as_ref()], &crate::ID);
+        require!(info.key() == expected, ErrorCode::InvalidReferralAccount);
+        if info.data_is_empty() {
+            break;
+        }
+        let node = Account::<ReferralAccount>::try_from(info)?;
+        if node.parent == Pubkey::default() {
+            break;
+        }
+
+        let token_info = accounts.next().ok_or(ErrorCode::InvalidReferralAccount)?;
+        require!(token_info.is_writable, ErrorCode::InvalidReferralAccount);
+        let token_account =
+            InterfaceAccount::<token_interface::TokenAccount>::try_from(token_info)?;
+        require!(
+            token_account.owner == node.parent,
+            ErrorCode::InvalidTokenAccountOwner
+        );
+        require!(token_account.mint == mint, ErrorCode::InvalidTokenMint);
+
+        let share = (pool as u128 * *level_bps as u128 / MAX_BPS as u128) as u64;
+        payouts.push((token_account, share));
+        current = node.parent;
+    }
+    require!(accounts.next().is_none(), ErrorCode::InvalidReferralAccount);
+
+    Ok(payouts)
+}
+
+/// Rejects a referrer share while the referral program has upline levels, for settlement paths
+/// that can only pay the direct referrer.
+pub fn require_direct_referral(referral_program: &AccountInfo) -> Result<()> {
+    if referral_program.data_is_empty() {
+        return Ok(());
+    }
+    require!(
+        referral_program.owner == &crate::ID,
+        ErrorCode::InvalidReferralAccount
+    );
+    let program = ReferralProgram::try_deserialize(&mut &referral_program.try_borrow_data()?[..])?;
+    require!(
+        program.level_bps.len() <= 1,
+        ErrorCode::ReferralLevelsNotSupported
+    );
+    Ok(())
+}
+
+/// Rejects a program-owned reserve, such as a `ReserveVault`, as the recipient of a settlement
+/// path it could never withdraw from: it cannot sign claims or move lamports out.
+pub fn require_external_reserve(reserve: &AccountInfo) -> Result<()> {
+    require!(reserve.owner != &crate::ID, ErrorCode::ReserveNotSupported);
+    Ok(())
+}
+
+/// Unlocks reserve tranches whose lockup has passed.
+fn roll_reserve(reserve: &mut ReserveVault, now: i64) -> Result<()> {
+    for tranche in [&mut reserve.previous, &mut reserve.current] {
+        if tranche.amount > 0 && tranche.unlock_at <= now {
+            reserve.unlocked = reserve
+                .unlocked
+                .checked_add(tranche.amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+            *tranche = ReserveTranche::default();
+        }
+    }
+    Ok(())
+}
+
+/// Unlocks matured tranches and books any tokens that reached the reserve account since the
+/// last sync as an inflow locked for at least `lockup_period` from now.
+///
+/// New inflows join the current tranche, which extends its unlock time. Once the current
+/// tranche has been open for a full lockup period and the previous one has unlocked, it becomes
+/// the previous tranche and a new one opens, so steady inflows still unlock on schedule.
+pub fn sync_reserve(reserve: &mut ReserveVault, balance: u64, now: i64) -> Result<()> {
+    roll_reserve(reserve, now)?;
+    reserve.updated_at = now;
+    if balance <= reserve.tracked_balance {
+        return Ok(());
+    }
+
+    let inflow = balance - reserve.tracked_balance;
+    let window_end = reserve
+        .current
+        .opened_at
+        .saturating_add(reserve.lockup_period);
+    if reserve.current.amount > 0 && now >= window_end && reserve.previous.amount == 0 {
+        reserve.previous = reserve.current;
+        reserve.current = ReserveTranche::default();
+    }
+    if reserve.current.amount == 0 {
+        reserve.current.opened_at = now;
+    }
+
+    let unlock_at = now
+        .checked_add(reserve.lockup_period)
+        .ok_or(ErrorCode::MathOverflow)?;
+    reserve.current.amount = reserve
+        .current
+        .amount
+        .checked_add(inflow)
+        .ok_or(ErrorCode::MathOverflow)?;
+    reserve.current.unlock_at = reserve.current.unlock_at.max(unlock_at);
+    reserve.total_inflow = reserve
+        .total_inflow
+        .checked_add(inflow)
+        .ok_or(ErrorCode::MathOverflow)?;
+    reserve.tracked_balance = balance;
+    Ok(())
+}
+
+/// True when the agent's verification record exists and is suspended.
+pub fn is_suspended(verification: &AccountInfo) -> Result<bool> {
+    if verification.data_is_empty() {
+        return Ok(false);
+    }
+    require!(verification.owner == &crate::ID, ErrorCode::InvalidAddress);
+    let data = verification.try_borrow_data()?;
+    let record = VerificationRecord::try_deserialize(&mut &data[..])?;
+    Ok(record.status == STATUS_SUSPENDED)
+}
+
+/// True when the agent's verification record exists and is verified.
+pub fn is_verified_account(verification: &AccountInfo, now: i64) -> Result<bool> {
+    if verification.data_is_empty() {
+        return Ok(false);
+    }
+    require!(verification.owner == &crate::ID, ErrorCode::InvalidAddress);
+    let data = verification.try_borrow_data()?;
+    let record = VerificationRecord::try_deserialize(&mut &data[..])?;
+    Ok(is_verified(&record, now))
+}
+
+/// Tokens in a settlement vault owed to claimable balances; zero until the vault's ledger exists.
+pub fn claims_outstanding(ledger: &AccountInfo) -> Result<u64> {
+    if ledger.data_is_empty() {
+        return Ok(0);
+    }
+    require!(ledger.owner == &crate::ID, ErrorCode::InvalidClaimAccount);
+    let data = ledger.try_borrow_data()?;
+    Ok(ClaimLedger::try_deserialize(&mut &data[..])?.outstanding)
+}
+
+/// Requires the vault to hold `amount` on top of what is already owed to claimants.
+pub fn require_unallocated(
+    vault: &InterfaceAccount<token_interface::TokenAccount>,
+    outstanding: u64,
+    amount: u64,
+) -> Result<()> {
+    let required = outstanding
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(vault.amount >= required, ErrorCode::ClaimsUnderfunded);
+    Ok(())
+}
+
+/// Moves lamports out of the system-owned SOL vault PDA, leaving its rent-exempt minimum.
+pub fn transfer_sol_from_vault<'info>(
+    system_program: &Program<'info, System>,
+    vault: &SystemAccount<'info>,
+    to: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let reserved = Rent::get()?.minimum_balance(0);
+    let available = vault.lamports().saturating_sub(reserved);
+    require!(amount <= available, ErrorCode::InsufficientVaultBalance);
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: vault.to_account_info(),
+                to: to.clone(),
+            },
+            &[signer_seeds],
+        ),
+        amount,
+    )?;
+
+    Ok(())
+}
+
+/// Creates a program-owned PDA account, tolerating lamports pre-sent to the address.
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    target: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let current = target.lamports();
+    let program = system_program.to_account_info();
+
+    if current == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                program,
+                CreateAccount {
+                    from: payer.clone(),
+                    to: target.clone(),
+                },
+                &[signer_seeds],
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    if rent > current {
+        system_program::transfer(
+            CpiContext::new(
+                program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: target.clone(),
+                },
+            ),
+            rent - current,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            program.clone(),
+            Allocate {
+                account_to_allocate: target.clone(),
+            },
+            &[signer_seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            program,
+            Assign {
+                account_to_assign: target.clone(),
+            },
+            &[signer_seeds],
+        ),
+        &crate::ID,
+    )
+}
+
+/// Grows a program account written under an older, shorter layout of `T` to `space` bytes,
+/// topping up rent from `payer`. Appended fields read as zero. Returns false when the account
+/// is already at the current size.
+pub fn grow_account<'info, T: Discriminator>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+) -> Result<bool> {
+    require!(
+        account.owner == &crate::ID,
+        ErrorCode::InvalidMigrationAccount
+    );
+    require!(
+        account.try_borrow_data()?.starts_with(&T::DISCRIMINATOR),
+        ErrorCode::InvalidMigrationAccount
+    );
+    if account.data_len() >= space {
+        return Ok(false);
+    }
+
+    let rent = Rent::get()?.minimum_balance(space);
+    let shortfall = rent.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    account.realloc(space, true)?;
+    Ok(true)
+}
+
+/// Mint extensions the settlement path can move tokens through. Anything else, such as
+/// transfer hooks, confidential transfers or permanent delegates, is rejected.
+const SUPPORTED_MINT_EXTENSIONS: [ExtensionType; 9] = [
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::MintCloseAuthority,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::GroupPointer,
+    ExtensionType::TokenGroup,
+    ExtensionType::GroupMemberPointer,
+    ExtensionType::TokenGroupMember,
+];
+
+/// Rejects mints with unsupported extensions and returns the transfer fee config, if any.
+pub fn settlement_mint_fee_config(mint: &AccountInfo) -> Result<Option<TransferFeeConfig>> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    let extensions = state.get_extension_types()?;
+    require!(
+        extensions
+            .iter()
+            .all(|extension| SUPPORTED_MINT_EXTENSIONS.contains(extension)),
+        ErrorCode::UnsupportedMintExtension
+    );
+
+    Ok(state.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+/// Signs token transfers out of a settlement vault with `transfer_checked`, passing the
+/// expected transfer fee for mints with the transfer-fee extension. Shares are computed on the
+/// gross amount, so each destination bears the fee withheld on its own transfer.
+pub struct VaultTransfer<'a, 'info> {
+    pub token_program: &'a Interface<'info, TokenInterface>,
+    pub vault: &'a InterfaceAccount<'info, token_interface::TokenAccount>,
+    pub mint: &'a InterfaceAccount<'info, token_interface::Mint>,
+    pub authority: &'a AccountInfo<'info>,
+    pub signer_seeds: &'a [&'a [u8]],
+    pub fee_config: Option<TransferFeeConfig>,
+    pub epoch: u64,
+}
+
+impl<'info> VaultTransfer<'_, 'info> {
+    /// Fee the mint withholds from a transfer of `amount` in the current epoch.
+    pub fn fee(&self, amount: u64) -> Result<u64> {
+        match &self.fee_config {
+            Some(config) => config
+                .calculate_epoch_fee(self.epoch, amount)
+                .ok_or(error!(ErrorCode::MathOverflow)),
+            None => Ok(0),
+        }
+    }
+
+    /// Sends `amount` to `to` and returns the fee withheld at the destination.
+    pub fn send(
+        &self,
+        to: &InterfaceAccount<'info, token_interface::TokenAccount>,
+        amount: u64,
+    ) -> Result<u64> {
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        let decimals = self.mint.decimals;
+        let signer_seeds = &[self.signer_seeds];
+        if self.fee_config.is_none() {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    TransferChecked {
+                        from: self.vault.to_account_info(),
+                        mint: self.mint.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: self.authority.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                decimals,
+            )?;
+            return Ok(0);
+        }
+
+        let fee = self.fee(amount)?;
+        token_interface::transfer_checked_with_fee(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferCheckedWithFee {
+                    token_program_id: self.token_program.to_account_info(),
+                    source: self.vault.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                    destination: to.to_account_info(),
+                    authority: self.authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            decimals,
+            fee,
+        )?;
+
+        Ok(fee)
+    }
+}
+
+pub fn close_vault<'info>(
+    token_program: &Program<'info, Token>,
+    vault: &Account<'info, TokenAccount>,
+    destination: &AccountInfo<'info>,
+    authority: &UncheckedAccount<'info>,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let cpi_accounts = CloseAccount {
+        account: vault.to_account_info(),
+        destination: destination.clone(),
+        authority: authority.to_account_info(),
+    };
+
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        &[signer_seeds],
+    ))
+}
+
 pub fn is_valid_role(role: u8) -> bool {
     role == ROLE_VERIFICATION_OPERATOR
         || role == ROLE_ORACLE
@@ -61,18 +767,1249 @@ pub fn is_verified(record: &VerificationRecord, now: i64) -> bool {
     record.expires_at >= now
 }
 
+pub fn current_rolling_score(reputation: &ReputationState, half_life: i64, now: i64) -> u16 {
+    let elapsed = now.saturating_sub(reputation.last_updated);
+    decay_score(reputation.rolling_score, elapsed, half_life)
+}
+
+pub fn epoch_due(track: &ReputationState, epoch_length: i64, now: i64) -> bool {
+    epoch_length > 0
+        && track.epoch_started_at > 0
+        && now >= track.epoch_started_at.saturating_add(epoch_length)
+}
+
+pub fn start_epoch(track: &mut ReputationState, epoch_length: i64, now: i64) {
+    if epoch_length > 0 && track.epoch_started_at == 0 {
+        track.epoch_started_at = now - now.rem_euclid(epoch_length);
+    }
+}
+
+/// Closes the track's elapsed epoch into `snapshot`, using the state as of the
+/// epoch boundary, and opens a new epoch aligned to `now`.
+pub fn close_epoch(
+    track: &mut ReputationState,
+    track_key: Pubkey,
+    snapshot: &mut ReputationSnapshot,
+    snapshot_bump: u8,
+    config: &ProtocolConfig,
+    now: i64,
+) -> Result<()> {
+    let epoch_length = config.reputation_epoch_length;
+    require!(epoch_length > 0, ErrorCode::EpochsDisabled);
+    require!(epoch_due(track, epoch_length, now), ErrorCode::EpochNotElapsed);
+
+    let ended_at = track.epoch_started_at.saturating_add(epoch_length);
+    let weighted_average = if track.total_weight == 0 {
+        0
+    } else {
+        (track.total_weighted_score / track.total_weight as u128) as u16
+    };
+
+    snapshot.reputation_state = track_key;
+    snapshot.agent = track.agent;
+    snapshot.category = track.category;
+    snapshot.epoch = track.epoch;
+    snapshot.started_at = track.epoch_started_at;
+    snapshot.ended_at = ended_at;
+    snapshot.rolling_score = current_rolling_score(track, config.score_half_life, ended_at);
+    snapshot.score_count = track.score_count;
+    snapshot.weighted_average = weighted_average;
+    snapshot.flag_counts = track.epoch_flag_counts;
+    snapshot.closed_at = now;
+    snapshot.bump = snapshot_bump;
+
+    track.epoch = track.epoch.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    track.epoch_started_at = 0;
+    track.epoch_flag_counts = [0; RISK_FLAG_BITS];
+    start_epoch(track, epoch_length, now);
+
+    Ok(())
+}
+
+/// Lazily rolls the track's epoch before a score update. The snapshot account is
+/// required exactly when the current epoch has elapsed.
+pub fn roll_epoch(
+    track: &mut ReputationState,
+    track_key: Pubkey,
+    snapshot: Option<&mut ReputationSnapshot>,
+    snapshot_bump: Option<u8>,
+    config: &ProtocolConfig,
+    now: i64,
+) -> Result<()> {
+    if epoch_due(track, config.reputation_epoch_length, now) {
+        let snapshot = snapshot.ok_or(ErrorCode::EpochSnapshotRequired)?;
+        let snapshot_bump = snapshot_bump.ok_or(ErrorCode::EpochSnapshotRequired)?;
+        return close_epoch(track, track_key, snapshot, snapshot_bump, config, now);
+    }
+
+    require!(snapshot.is_none(), ErrorCode::EpochNotElapsed);
+    start_epoch(track, config.reputation_epoch_length, now);
+
+    Ok(())
+}
+
+/// `roll_epoch` for a category track, whose snapshot PDA is passed unchecked and created
+/// here at `payer`'s expense.
+pub fn roll_category_epoch<'info>(
+    track: Option<&mut Account<'info, ReputationState>>,
+    snapshot: Option<&AccountInfo<'info>>,
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    config: &ProtocolConfig,
+    now: i64,
+) -> Result<()> {
+    let Some(track) = track else {
+        require!(snapshot.is_none(), ErrorCode::EpochNotElapsed);
+        return Ok(());
+    };
+    if !epoch_due(track, config.reputation_epoch_length, now) {
+        require!(snapshot.is_none(), ErrorCode::EpochNotElapsed);
+        start_epoch(track, config.reputation_epoch_length, now);
+        return Ok(());
+    }
+
+    let track_key = track.key();
+    let info = snapshot.ok_or(ErrorCode::EpochSnapshotRequired)?;
+    let epoch = track.epoch.to_le_bytes();
+    let (address, bump) = Pubkey::find_program_address(
+        &[EPOCH_SNAPSHOT_SEED, track_key.as_ref(), &epoch],
+        &crate::ID,
+    );
+    require_keys_eq!(info.key(), address, ErrorCode::InvalidEpochSnapshot);
+    create_pda_account(
+        payer,
+        info,
+        system_program,
+        ReputationSnapshot::SPACE,
+        &[EPOCH_SNAPSHOT_SEED, track_key.as_ref(), &epoch, &[bump]],
+    )?;
+
+    let mut snapshot =
+        ReputationSnapshot::try_deserialize_unchecked(&mut &info.try_borrow_data()?[..])?;
+    close_epoch(track, track_key, &mut snapshot, bump, config, now)?;
+    snapshot.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+pub fn validate_score_input(
+    config: &ProtocolConfig,
+    score: u16,
+    confidence_bps: u16,
+    score_hash: &[u8; 32],
+) -> Result<()> {
+    require!(score <= MAX_BPS, ErrorCode::InvalidScore);
+    require!(confidence_bps <= MAX_BPS, ErrorCode::InvalidConfidence);
+    require!(
+        confidence_bps >= config.min_confidence_bps,
+        ErrorCode::InvalidConfidence
+    );
+    require!(!is_zero_hash(score_hash), ErrorCode::InvalidHash);
+    Ok(())
+}
+
+pub fn validate_signal_category(
+    category: u16,
+    registered: Option<&ReputationCategory>,
+) -> Result<()> {
+    if category != 0 {
+        let registered = registered.ok_or(ErrorCode::InvalidCategory)?;
+        require!(
+            registered.id == category && registered.active,
+            ErrorCode::InvalidCategory
+        );
+    }
+    Ok(())
+}
+
+pub fn reset_signal_score(signal: &mut TradeSignal) {
+    signal.score_submitted = false;
+    signal.score_hash = [0u8; 32];
+    signal.score = 0;
+    signal.confidence_bps = 0;
+    signal.oracle = Pubkey::default();
+    signal.score_submitted_at = 0;
+    signal.rolling_before = 0;
+    signal.score_sequence = 0;
+    signal.category_rolling_before = 0;
+    signal.category_score_sequence = 0;
+    signal.correction_count = 0;
+    signal.corrected_by = Pubkey::default();
+    signal.corrected_at = 0;
+    signal.correction_reason_hash = [0u8; 32];
+    signal.previous_score = 0;
+    signal.previous_confidence_bps = 0;
+    signal.challenge_status = CHALLENGE_NONE;
+}
+
+pub fn require_signal_fresh(config: &ProtocolConfig, submitted_at: i64, now: i64) -> Result<()> {
+    if config.max_signal_age > 0 {
+        let elapsed = now
+            .checked_sub(submitted_at)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(elapsed <= config.max_signal_age, ErrorCode::SignalTooOld);
+    }
+    Ok(())
+}
+
+/// Books a newly scored signal into the agent's PnL stats. Every scored signal is a trade, so one
+/// scored without a PnL report counts as a trade that did not win; drawdown, notional and holding
+/// time only move on reported trades.
+pub fn record_trade_pnl(stats: &mut PnlStats, signal: &TradeSignal, now: i64) -> Result<()> {
+    stats.trade_count = stats
+        .trade_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    if signal.pnl_reported {
+        stats.reported_count = stats
+            .reported_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if signal.realized_pnl > 0 {
+            stats.win_count = stats
+                .win_count
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        // Drawdown is measured on the cumulative realized PnL curve, starting from zero.
+        stats.cumulative_pnl = stats
+            .cumulative_pnl
+            .checked_add(signal.realized_pnl)
+            .ok_or(ErrorCode::MathOverflow)?;
+        stats.peak_pnl = stats.peak_pnl.max(stats.cumulative_pnl);
+        let drawdown = (stats.peak_pnl as i128 - stats.cumulative_pnl as i128) as u64;
+        stats.max_drawdown = stats.max_drawdown.max(drawdown);
+
+        stats.total_notional = stats
+            .total_notional
+            .checked_add(signal.notional as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        stats.total_holding_time = stats
+            .total_holding_time
+            .checked_add(signal.holding_time as u64)
+            .ok_or(ErrorCode::MathOverflow)?;
+        stats.average_holding_time =
+            (stats.total_holding_time / stats.reported_count as u64) as i64;
+    }
+
+    stats.win_rate_bps =
+        ((stats.win_count as u64 * MAX_BPS as u64) / stats.trade_count as u64) as u16;
+    stats.last_updated = now;
+    Ok(())
+}
+
+/// Takes an upheld signal back out of the agent's PnL stats. Peak and max drawdown depend on the
+/// order trades were booked in, so they keep the removed trade's path; the peak is only raised
+/// back to the cumulative PnL when removing a loss leaves it below.
+pub fn unrecord_trade_pnl(stats: &mut PnlStats, signal: &TradeSignal, now: i64) -> Result<()> {
+    stats.trade_count = stats
+        .trade_count
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    if signal.pnl_reported {
+        stats.reported_count = stats
+            .reported_count
+            .checked_sub(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if signal.realized_pnl > 0 {
+            stats.win_count = stats
+                .win_count
+                .checked_sub(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        stats.cumulative_pnl = stats
+            .cumulative_pnl
+            .checked_sub(signal.realized_pnl)
+            .ok_or(ErrorCode::MathOverflow)?;
+        stats.peak_pnl = stats.peak_pnl.max(stats.cumulative_pnl);
+
+        stats.total_notional = stats
+            .total_notional
+            .checked_sub(signal.notional as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        stats.total_holding_time = stats
+            .total_holding_time
+            .checked_sub(signal.holding_time as u64)
+            .ok_or(ErrorCode::MathOverflow)?;
+        stats.average_holding_time = if stats.reported_count == 0 {
+            0
+        } else {
+            (stats.total_holding_time / stats.reported_count as u64) as i64
+        };
+    }
+
+    stats.win_rate_bps = if stats.trade_count == 0 {
+        0
+    } else {
+        ((stats.win_count as u64 * MAX_BPS as u64) / stats.trade_count as u64) as u16
+    };
+    stats.last_updated = now;
+    Ok(())
+}
+
+/// Adds a trade id to the agent's `ScoredTrades` index, creating the index on first use, and
+/// rejects a trade already in it. Each entry grows the account by 8 bytes at `payer`'s expense.
+pub fn record_scored_trade<'info>(
+    index: &AccountInfo<'info>,
+    agent: Pubkey,
+    bump: u8,
+    trade_id_hash: &[u8; 32],
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let mut header = if index.data_is_empty() {
+        create_pda_account(
+            payer,
+            index,
+            system_program,
+            ScoredTrades::SPACE,
+            &[SCORED_TRADES_SEED, agent.as_ref(), &[bump]],
+        )?;
+        ScoredTrades {
+            agent,
+            count: 0,
+            bump,
+        }
+    } else {
+        require!(index.owner == &crate::ID, ErrorCode::InvalidScoredTrades);
+        let header = ScoredTrades::try_deserialize(&mut &index.try_borrow_data()?[..])?;
+        require!(header.agent == agent, ErrorCode::InvalidScoredTrades);
+        header
+    };
+
+    let count = header.count as usize;
+    let used = ScoredTrades::SPACE + count * ScoredTrades::ENTRY_SPACE;
+    require!(index.data_len() == used, ErrorCode::InvalidScoredTrades);
+
+    let entry = &trade_id_hash[..ScoredTrades::ENTRY_SPACE];
+    let position = {
+        let data = index.try_borrow_data()?;
+        let (mut low, mut high) = (0, count);
+        while low < high {
+            let mid = (low + high) / 2;
+            let offset = ScoredTrades::SPACE + mid * ScoredTrades::ENTRY_SPACE;
+            match data[offset..offset + ScoredTrades::ENTRY_SPACE].cmp(entry) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return err!(ErrorCode::TradeAlreadyScored),
+            }
+        }
+        low
+    };
+
+    let space = used + ScoredTrades::ENTRY_SPACE;
+    let shortfall = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(index.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: index.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    index.realloc(space, false)?;
+
+    header.count = header.count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    let mut data = index.try_borrow_mut_data()?;
+    let offset = ScoredTrades::SPACE + position * ScoredTrades::ENTRY_SPACE;
+    data.copy_within(offset..used, offset + ScoredTrades::ENTRY_SPACE);
+    data[offset..offset + ScoredTrades::ENTRY_SPACE].copy_from_slice(entry);
+    header.try_serialize(&mut &mut data[..])
+}
+
+/// Accounts a newly scored signal is booked into; shared by every scoring instruction.
+pub struct ScoredSignalAccounts<'a, 'info> {
+    pub config: &'a ProtocolConfig,
+    pub agent: Pubkey,
+    pub verification_record: &'a mut VerificationRecord,
+    pub verification_bump: u8,
+    pub reputation_state: &'a mut Account<'info, ReputationState>,
+    pub reputation_bump: u8,
+    pub epoch_snapshot: Option<&'a mut ReputationSnapshot>,
+    pub epoch_snapshot_bump: Option<u8>,
+    pub category: Option<&'a ReputationCategory>,
+    pub category_reputation_state: Option<&'a mut Account<'info, ReputationState>>,
+    pub category_epoch_snapshot: Option<&'a AccountInfo<'info>>,
+    pub pnl_stats: &'a mut PnlStats,
+    pub pnl_stats_bump: u8,
+    pub scored_trades: &'a AccountInfo<'info>,
+    pub scored_trades_bump: u8,
+    pub risk_flag_table: &'a AccountInfo<'info>,
+    pub leaderboard: &'a AccountInfo<'info>,
+    pub category_leaderboard: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub system_program: &'a Program<'info, System>,
+}
+
+/// Applies a signal whose score fields are already set: rolls due epochs, updates the global and
+/// category tracks, records risk flags, books PnL and re-ranks the leaderboards.
+pub fn apply_scored_signal(
+    accounts: ScoredSignalAccounts,
+    signal: &mut TradeSignal,
+    now: i64,
+) -> Result<()> {
+    let ScoredSignalAccounts {
+        config,
+        agent,
+        verification_record,
+        verification_bump,
+        reputation_state,
+        reputation_bump,
+        epoch_snapshot,
+        epoch_snapshot_bump,
+        category,
+        mut category_reputation_state,
+        category_epoch_snapshot,
+        pnl_stats,
+        pnl_stats_bump,
+        scored_trades,
+        scored_trades_bump,
+        risk_flag_table,
+        leaderboard,
+        category_leaderboard,
+        payer,
+        system_program,
+    } = accounts;
+
+    record_scored_trade(
+        scored_trades,
+        agent,
+        scored_trades_bump,
+        &signal.trade_id_hash,
+        payer,
+        system_program,
+    )?;
+
+    if verification_record.agent == Pubkey::default() {
+        verification_record.agent = agent;
+        verification_record.bump = verification_bump;
+    }
+    if config.require_verified_for_score {
+        require!(
+            is_verified(verification_record, now),
+            ErrorCode::VerificationRequired
+        );
+    }
+
+    if reputation_state.agent == Pubkey::default() {
+        reputation_state.agent = agent;
+        reputation_state.bump = reputation_bump;
+    }
+    let reputation_key = reputation_state.key();
+    roll_epoch(
+        reputation_state,
+        reputation_key,
+        epoch_snapshot,
+        epoch_snapshot_bump,
+        config,
+        now,
+    )?;
+    roll_category_epoch(
+        category_reputation_state.as_deref_mut(),
+        category_epoch_snapshot,
+        payer,
+        system_program,
+        config,
+        now,
+    )?;
+
+    let table = load_risk_flag_table(risk_flag_table)?;
+    apply_score(reputation_state, signal, config, &table, now)?;
+    apply_category_score(
+        category_reputation_state
+            .as_deref_mut()
+            .map(|track| &mut **track),
+        signal,
+        category,
+        config,
+        now,
+    )?;
+
+    if signal.category != 0 {
+        if let Some(track) = category_reputation_state.as_deref_mut() {
+            record_category_risk_flags(&table, track, signal.risk_flags);
+        }
+    }
+    if let Some(reason_hash) = record_risk_flags(&table, reputation_state, signal.risk_flags, now) {
+        suspend_verification(verification_record, reason_hash, now);
+    }
+
+    if pnl_stats.agent == Pubkey::default() {
+        pnl_stats.agent = agent;
+        pnl_stats.bump = pnl_stats_bump;
+    }
+    record_trade_pnl(pnl_stats, signal, now)?;
+
+    update_leaderboards(
+        leaderboard,
+        category_leaderboard,
+        reputation_state,
+        category_reputation_state.as_deref().map(|track| &**track),
+        is_verified(verification_record, now),
+        config.score_half_life,
+        now,
+    )
+}
+
+pub fn apply_score(
+    reputation: &mut ReputationState,
+    signal: &mut TradeSignal,
+    config: &ProtocolConfig,
+    risk_flag_table: &RiskFlagTable,
+    now: i64,
+) -> Result<()> {
+    let (score, confidence_bps) = risk_adjusted_score(
+        risk_flag_table,
+        signal.risk_flags,
+        signal.score,
+        signal.confidence_bps,
+    );
+    signal.applied_score = score;
+    signal.applied_confidence_bps = confidence_bps;
+
+    let (rolling_before, sequence) = apply_to_track(
+        reputation,
+        score,
+        confidence_bps,
+        config.score_alpha_bps,
+        config,
+        now,
+    )?;
+    signal.rolling_before = rolling_before;
+    signal.score_sequence = sequence;
+
+    reputation.last_score = signal.score;
+    reputation.last_confidence_bps = signal.confidence_bps;
+
+    Ok(())
+}
+
+pub fn apply_category_score(
+    track: Option<&mut ReputationState>,
+    signal: &mut TradeSignal,
+    category: Option<&ReputationCategory>,
+    config: &ProtocolConfig,
+    now: i64,
+) -> Result<()> {
+    if signal.category == 0 {
+        return Ok(());
+    }
+
+    let track = track.ok_or(ErrorCode::InvalidCategory)?;
+    let category = category.ok_or(ErrorCode::InvalidCategory)?;
+    require!(
+        category.active && category.id == signal.category,
+        ErrorCode::InvalidCategory
+    );
+    require!(
+        track.agent == signal.agent && track.category == signal.category,
+        ErrorCode::InvalidCategory
+    );
+    require!(
+        signal.confidence_bps >= category.min_confidence_bps,
+        ErrorCode::InvalidConfidence
+    );
+    require!(
+        !epoch_due(track, config.reputation_epoch_length, now),
+        ErrorCode::EpochNotClosed
+    );
+    start_epoch(track, config.reputation_epoch_length, now);
+
+    let (rolling_before, sequence) = apply_to_track(
+        track,
+        signal.applied_score,
+        signal.applied_confidence_bps,
+        category.score_alpha_bps,
+        config,
+        now,
+    )?;
+    signal.category_rolling_before = rolling_before;
+    signal.category_score_sequence = sequence;
+
+    track.last_score = signal.score;
+    track.last_confidence_bps = signal.confidence_bps;
+
+    Ok(())
+}
+
+/// Blends one score into a reputation track and returns the decayed rolling score
+/// it was blended into together with the track's sequence before the update.
+fn apply_to_track(
+    track: &mut ReputationState,
+    score: u16,
+    confidence_bps: u16,
+    alpha_bps: u16,
+    config: &ProtocolConfig,
+    now: i64,
+) -> Result<(u16, u64)> {
+    migrate_track(track, config);
+
+    let rolling_before = current_rolling_score(track, config.score_half_life, now);
+    let sequence = track.score_sequence;
+    let first = track.score_count == 0;
+
+    let weighted = (score as u128)
+        .checked_mul(confidence_bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    track.total_weighted_score = track
+        .total_weighted_score
+        .checked_add(weighted)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    track.total_weight = track
+        .total_weight
+        .checked_add(confidence_bps as u64)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    add_score_stats(track, score);
+    track.score_count = track
+        .score_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    track.score_sequence = track
+        .score_sequence
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    track.last_updated = now;
+
+    let effective = effective_score(score, confidence_bps);
+    track.rolling_score = match config.aggregation_model {
+        AGGREGATION_WINDOW => {
+            if let Some(slot) = window_slot(track, config, sequence) {
+                track.score_window[slot] = effective + 1;
+            }
+            window_mean(window(track, config)).unwrap_or(effective)
+        }
+        AGGREGATION_BETA => beta_mean(
+            track.total_weighted_score,
+            track.total_weight,
+            config.beta_prior_alpha,
+            config.beta_prior_beta,
+        ),
+        _ if first => effective,
+        _ => ema_update(rolling_before, effective, alpha_bps),
+    };
+
+    Ok((rolling_before, sequence))
+}
+
+/// Brings a track onto the configured aggregation model. The window restarts empty at the
+/// current sequence, Beta is recomputed from the lifetime sums, and EMA carries on from the
+/// current rolling score.
+pub fn migrate_track(track: &mut ReputationState, config: &ProtocolConfig) {
+    if track.aggregation_version == config.aggregation_version {
+        return;
+    }
+
+    track.aggregation_version = config.aggregation_version;
+    track.window_start_sequence = track.score_sequence;
+    track.score_window = [EMPTY_WINDOW_SLOT; MAX_SCORE_WINDOW];
+    if config.aggregation_model == AGGREGATION_BETA && track.score_count > 0 {
+        track.rolling_score = beta_mean(
+            track.total_weighted_score,
+            track.total_weight,
+            config.beta_prior_alpha,
+            config.beta_prior_beta,
+        );
+    }
+}
+
+fn window<'a>(track: &'a ReputationState, config: &ProtocolConfig) -> &'a [u16] {
+    &track.score_window[..config.score_window_size as usize]
+}
+
+/// Ring-buffer slot of the update made at `sequence`, if it is still inside the window.
+fn window_slot(track: &ReputationState, config: &ProtocolConfig, sequence: u64) -> Option<usize> {
+    let size = config.score_window_size as u64;
+    if size == 0
+        || sequence < track.window_start_sequence
+        || track.score_sequence.saturating_sub(sequence) > size
+    {
+        return None;
+    }
+    Some((sequence % size) as usize)
+}
+
+/// Reads the admin risk flag table. Until it is initialized an empty table is used, which
+/// applies no penalties and never suspends.
+pub fn load_risk_flag_table(info: &AccountInfo) -> Result<RiskFlagTable> {
+    if info.data_is_empty() {
+        return Ok(RiskFlagTable::default());
+    }
+    require!(info.owner == &crate::ID, ErrorCode::InvalidRiskFlagTable);
+    let data = info.try_borrow_data()?;
+    RiskFlagTable::try_deserialize(&mut &data[..])
+}
+
+pub fn risk_adjusted_score(
+    table: &RiskFlagTable,
+    risk_flags: u8,
+    score: u16,
+    confidence_bps: u16,
+) -> (u16, u16) {
+    let max = MAX_BPS as u32;
+    let mut score = score as u32;
+    let mut confidence = confidence_bps as u32;
+
+    for (bit, rule) in table.rules.iter().enumerate() {
+        if risk_flags & (1 << bit) == 0 || is_zero_hash(&rule.name_hash) {
+            continue;
+        }
+        score = score * (max - rule.penalty_bps as u32) / max;
+        confidence = confidence * rule.weight_bps as u32 / max;
+    }
+
+    (score as u16, confidence as u16)
+}
+
+/// Re-ranks the agent on the global board and, for a categorized signal, on its category
+/// board after a track changes.
+pub fn update_leaderboards(
+    leaderboard: &AccountInfo,
+    category_leaderboard: &AccountInfo,
+    reputation: &ReputationState,
+    category_track: Option<&ReputationState>,
+    verified: bool,
+    half_life: i64,
+    now: i64,
+) -> Result<()> {
+    update_leaderboard(leaderboard, reputation, verified, half_life, now)?;
+    match category_track {
+        Some(track) if track.category != 0 => {
+            update_leaderboard(category_leaderboard, track, verified, half_life, now)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Re-ranks the track's agent on the board for the track's category. Boards are passed as
+/// unchecked PDAs so that no path can skip an existing board; until the admin creates one
+/// this is a no-op. Agents that no longer meet the board's requirements are dropped; a
+/// newcomer only enters a full board if it outranks the last entry, which is then evicted.
+/// Entries rank by their score decayed to `now`, so agents that stop scoring sink.
+pub fn update_leaderboard(
+    board: &AccountInfo,
+    track: &ReputationState,
+    verified: bool,
+    half_life: i64,
+    now: i64,
+) -> Result<()> {
+    if board.data_is_empty() {
+        return Ok(());
+    }
+    require!(board.owner == &crate::ID, ErrorCode::InvalidLeaderboard);
+    let mut leaderboard = Leaderboard::try_deserialize(&mut &board.try_borrow_data()?[..])?;
+    require!(
+        leaderboard.category == track.category,
+        ErrorCode::InvalidLeaderboard
+    );
+
+    leaderboard
+        .entries
+        .retain(|entry| entry.agent != track.agent);
+
+    let eligible = track.score_count >= leaderboard.min_score_count
+        && (!leaderboard.require_verified || verified);
+    if eligible {
+        leaderboard.entries.push(LeaderboardEntry {
+            agent: track.agent,
+            rolling_score: current_rolling_score(track, half_life, now),
+            score_count: track.score_count,
+            updated_at: now,
+        });
+    }
+    leaderboard
+        .entries
+        .sort_by_cached_key(|entry| leaderboard_rank(entry, half_life, now));
+    leaderboard.entries.truncate(leaderboard.capacity as usize);
+
+    leaderboard.updated_at = now;
+    leaderboard.try_serialize(&mut &mut board.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+/// Higher rolling score decayed to `now` first, then more scores, then the lower agent key.
+fn leaderboard_rank(
+    entry: &LeaderboardEntry,
+    half_life: i64,
+    now: i64,
+) -> (Reverse<u16>, Reverse<u32>, Pubkey) {
+    let elapsed = now.saturating_sub(entry.updated_at);
+    (
+        Reverse(decay_score(entry.rolling_score, elapsed, half_life)),
+        Reverse(entry.score_count),
+        entry.agent,
+    )
+}
+
+/// Counts the signal's active risk flags against the agent's current window and epoch, and
+/// returns the name hash of the first rule whose suspension threshold is reached.
+pub fn record_risk_flags(
+    table: &RiskFlagTable,
+    reputation: &mut ReputationState,
+    risk_flags: u8,
+    now: i64,
+) -> Option<[u8; 32]> {
+    if table.suspension_window > 0
+        && now.saturating_sub(reputation.flag_window_start) >= table.suspension_window
+    {
+        reputation.flag_window_start = now;
+        reputation.flag_counts = [0; RISK_FLAG_BITS];
+    }
+
+    let mut tripped = None;
+    for (bit, rule) in table.rules.iter().enumerate() {
+        if risk_flags & (1 << bit) == 0 || is_zero_hash(&rule.name_hash) {
+            continue;
+        }
+        reputation.epoch_flag_counts[bit] = reputation.epoch_flag_counts[bit].saturating_add(1);
+        let count = reputation.flag_counts[bit].saturating_add(1);
+        reputation.flag_counts[bit] = count;
+        if tripped.is_none() && rule.suspend_threshold > 0 && count >= rule.suspend_threshold {
+            tripped = Some(rule.name_hash);
+        }
+    }
+
+    tripped
+}
+
+/// Removes an upheld signal's flags from the agent's suspension window and epoch counts,
+/// skipping whichever has rolled since the signal was scored.
+pub fn unrecord_risk_flags(
+    table: &RiskFlagTable,
+    reputation: &mut ReputationState,
+    signal: &TradeSignal,
+) {
+    let in_window = signal.score_submitted_at >= reputation.flag_window_start;
+    let in_epoch = signal.score_submitted_at >= reputation.epoch_started_at;
+    for (bit, rule) in table.rules.iter().enumerate() {
+        if signal.risk_flags & (1 << bit) == 0 || is_zero_hash(&rule.name_hash) {
+            continue;
+        }
+        if in_window {
+            reputation.flag_counts[bit] = reputation.flag_counts[bit].saturating_sub(1);
+        }
+        if in_epoch {
+            reputation.epoch_flag_counts[bit] = reputation.epoch_flag_counts[bit].saturating_sub(1);
+        }
+    }
+}
+
+/// Counts a scored signal's flags into its category track's epoch. Suspension windows are
+/// only kept on the global track.
+pub fn record_category_risk_flags(
+    table: &RiskFlagTable,
+    track: &mut ReputationState,
+    risk_flags: u8,
+) {
+    for (bit, rule) in table.rules.iter().enumerate() {
+        if risk_flags & (1 << bit) == 0 || is_zero_hash(&rule.name_hash) {
+            continue;
+        }
+        track.epoch_flag_counts[bit] = track.epoch_flag_counts[bit].saturating_add(1);
+    }
+}
+
+/// Removes an upheld signal's flags from its category track's epoch counts, unless that epoch
+/// has rolled since the signal was scored.
+pub fn unrecord_category_risk_flags(
+    table: &RiskFlagTable,
+    track: &mut ReputationState,
+    signal: &TradeSignal,
+) {
+    if signal.score_submitted_at < track.epoch_started_at {
+        return;
+    }
+    for (bit, rule) in table.rules.iter().enumerate() {
+        if signal.risk_flags & (1 << bit) == 0 || is_zero_hash(&rule.name_hash) {
+            continue;
+        }
+        track.epoch_flag_counts[bit] = track.epoch_flag_counts[bit].saturating_sub(1);
+    }
+}
+
+/// The operator's evidence hash is kept; the tripped rule is recorded separately.
+pub fn suspend_verification(record: &mut VerificationRecord, reason_hash: [u8; 32], now: i64) {
+    record.status = STATUS_SUSPENDED;
+    record.operator = Pubkey::default();
+    record.updated_at = now;
+    record.suspension_reason_hash = reason_hash;
+}
+
+pub fn revert_score(
+    reputation: &mut ReputationState,
+    signal: &TradeSignal,
+    config: &ProtocolConfig,
+) -> Result<()> {
+    revert_from_track(
+        reputation,
+        signal.applied_score,
+        signal.applied_confidence_bps,
+        signal.rolling_before,
+        signal.score_sequence,
+        config.score_alpha_bps,
+        config,
+    )
+}
+
+pub fn revert_category_score(
+    track: Option<&mut ReputationState>,
+    signal: &TradeSignal,
+    category: Option<&ReputationCategory>,
+    config: &ProtocolConfig,
+) -> Result<()> {
+    if signal.category == 0 {
+        return Ok(());
+    }
+
+    let track = track.ok_or(ErrorCode::InvalidCategory)?;
+    let category = category.ok_or(ErrorCode::InvalidCategory)?;
+    require!(category.id == signal.category, ErrorCode::InvalidCategory);
+    require!(
+        track.agent == signal.agent && track.category == signal.category,
+        ErrorCode::InvalidCategory
+    );
+
+    revert_from_track(
+        track,
+        signal.applied_score,
+        signal.applied_confidence_bps,
+        signal.category_rolling_before,
+        signal.category_score_sequence,
+        category.score_alpha_bps,
+        config,
+    )
+}
+
+pub fn apply_correction(
+    reputation: &mut ReputationState,
+    signal: &TradeSignal,
+    raw: (u16, u16),
+    corrected: (u16, u16),
+    config: &ProtocolConfig,
+    now: i64,
+) -> Result<()> {
+    require!(
+        !epoch_due(reputation, config.reputation_epoch_length, now),
+        ErrorCode::EpochNotClosed
+    );
+
+    let latest = replace_in_track(
+        reputation,
+        (signal.applied_score, signal.applied_confidence_bps),
+        corrected,
+        signal.rolling_before,
+        signal.score_sequence,
+        config.score_alpha_bps,
+        config,
+    )?;
+    if latest {
+        reputation.last_score = raw.0;
+        reputation.last_confidence_bps = raw.1;
+    }
+
+    Ok(())
+}
+
+pub fn apply_category_correction(
+    track: Option<&mut ReputationState>,
+    signal: &TradeSignal,
+    category: Option<&ReputationCategory>,
+    raw: (u16, u16),
+    corrected: (u16, u16),
+    config: &ProtocolConfig,
+    now: i64,
+) -> Result<()> {
+    if signal.category == 0 {
+        return Ok(());
+    }
+
+    let track = track.ok_or(ErrorCode::InvalidCategory)?;
+    let category = category.ok_or(ErrorCode::InvalidCategory)?;
+    require!(category.id == signal.category, ErrorCode::InvalidCategory);
+    require!(
+        track.agent == signal.agent && track.category == signal.category,
+        ErrorCode::InvalidCategory
+    );
+    require!(
+        !epoch_due(track, config.reputation_epoch_length, now),
+        ErrorCode::EpochNotClosed
+    );
+
+    let latest = replace_in_track(
+        track,
+        (signal.applied_score, signal.applied_confidence_bps),
+        corrected,
+        signal.category_rolling_before,
+        signal.category_score_sequence,
+        category.score_alpha_bps,
+        config,
+    )?;
+    if latest {
+        track.last_score = raw.0;
+        track.last_confidence_bps = raw.1;
+    }
+
+    Ok(())
+}
+
+/// Swaps one applied contribution for another. The sums are exact. If the signal
+/// was the track's latest update its EMA step is recomputed from `rolling_before`;
+/// otherwise the EMA term is replaced at the weight it carries now,
+/// `alpha * (1 - alpha)^n` after `n` later updates. Returns whether the signal
+/// was the latest update.
+fn replace_in_track(
+    track: &mut ReputationState,
+    previous: (u16, u16),
+    corrected: (u16, u16),
+    rolling_before: u16,
+    sequence: u64,
+    alpha_bps: u16,
+    config: &ProtocolConfig,
+) -> Result<bool> {
+    migrate_track(track, config);
+
+    let previous_weighted = previous.0 as u128 * previous.1 as u128;
+    let corrected_weighted = corrected.0 as u128 * corrected.1 as u128;
+
+    track.total_weighted_score = track
+        .total_weighted_score
+        .checked_sub(previous_weighted)
+        .and_then(|total| total.checked_add(corrected_weighted))
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    track.total_weight = track
+        .total_weight
+        .checked_sub(previous.1 as u64)
+        .and_then(|total| total.checked_add(corrected.1 as u64))
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    replace_score_stats(track, previous.0, corrected.0);
+
+    let later_updates = track
+        .score_sequence
+        .checked_sub(sequence)
+        .and_then(|delta| delta.checked_sub(1))
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let previous_effective = effective_score(previous.0, previous.1);
+    let corrected_effective = effective_score(corrected.0, corrected.1);
+
+    match config.aggregation_model {
+        AGGREGATION_WINDOW => {
+            if let Some(slot) = window_slot(track, config, sequence) {
+                track.score_window[slot] = corrected_effective + 1;
+            }
+            if let Some(mean) = window_mean(window(track, config)) {
+                track.rolling_score = mean;
+            }
+        }
+        AGGREGATION_BETA => {
+            track.rolling_score = beta_mean(
+                track.total_weighted_score,
+                track.total_weight,
+                config.beta_prior_alpha,
+                config.beta_prior_beta,
+            );
+        }
+        _ if later_updates == 0 => {
+            track.rolling_score = if track.score_count <= 1 {
+                corrected_effective
+            } else {
+                ema_update(rolling_before, corrected_effective, alpha_bps)
+            };
+        }
+        _ => {
+            let weight = alpha_bps as u128 * ema_retention_bps(alpha_bps, later_updates) as u128;
+            let scale = MAX_BPS as u128 * MAX_BPS as u128;
+            let rolling = (track.rolling_score as u128 * scale)
+                .saturating_sub(previous_effective as u128 * weight)
+                .saturating_add(corrected_effective as u128 * weight)
+                / scale;
+            track.rolling_score = rolling.min(MAX_BPS as u128) as u16;
+        }
+    }
+
+    Ok(later_updates == 0)
+}
+
+// Removing the most recent score restores the rolling value it was blended into.
+// An older score is removed by subtracting its remaining EMA contribution,
+// `alpha * (effective - rolling_before) * (1 - alpha)^n` for `n` later updates,
+// which ignores decay applied since.
+fn revert_from_track(
+    track: &mut ReputationState,
+    score: u16,
+    confidence_bps: u16,
+    rolling_before: u16,
+    sequence: u64,
+    alpha_bps: u16,
+    config: &ProtocolConfig,
+) -> Result<()> {
+    migrate_track(track, config);
+
+    let weighted = (score as u128)
+        .checked_mul(confidence_bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    track.total_weighted_score = track
+        .total_weighted_score
+        .checked_sub(weighted)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    track.total_weight = track
+        .total_weight
+        .checked_sub(confidence_bps as u64)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    remove_score_stats(track, score);
+    track.score_count = track
+        .score_count
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let later_updates = track
+        .score_sequence
+        .checked_sub(sequence)
+        .and_then(|delta| delta.checked_sub(1))
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    if track.score_count == 0 {
+        track.rolling_score = 0;
+        return Ok(());
+    }
+
+    match config.aggregation_model {
+        AGGREGATION_WINDOW => {
+            if let Some(slot) = window_slot(track, config, sequence) {
+                track.score_window[slot] = EMPTY_WINDOW_SLOT;
+            }
+            if let Some(mean) = window_mean(window(track, config)) {
+                track.rolling_score = mean;
+            }
+        }
+        AGGREGATION_BETA => {
+            track.rolling_score = beta_mean(
+                track.total_weighted_score,
+                track.total_weight,
+                config.beta_prior_alpha,
+                config.beta_prior_beta,
+            );
+        }
+        _ if later_updates == 0 => track.rolling_score = rolling_before,
+        _ => {
+            let delta = effective_score(score, confidence_bps) as i128 - rolling_before as i128;
+            let retention = ema_retention_bps(alpha_bps, later_updates) as i128;
+            let term = delta * alpha_bps as i128 * retention / (MAX_BPS as i128 * MAX_BPS as i128);
+            let rolling = (track.rolling_score as i128 - term).clamp(0, MAX_BPS as i128);
+            track.rolling_score = rolling as u16;
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds an applied score to the track's Welford moments and histogram. Call before
+/// `score_count` is incremented.
+fn add_score_stats(track: &mut ReputationState, score: u16) {
+    (track.score_mean, track.score_m2) =
+        welford_add(track.score_mean, track.score_m2, track.score_count, score);
+    let bucket = &mut track.score_histogram[score_bucket(score)];
+    *bucket = bucket.saturating_add(1);
+}
+
+/// Removes an applied score from the track's Welford moments and histogram. Call before
+/// `score_count` is decremented.
+fn remove_score_stats(track: &mut ReputationState, score: u16) {
+    (track.score_mean, track.score_m2) =
+        welford_remove(track.score_mean, track.score_m2, track.score_count, score);
+    let bucket = &mut track.score_histogram[score_bucket(score)];
+    *bucket = bucket.saturating_sub(1);
+}
+
+/// Swaps one applied score for another without changing `score_count`.
+fn replace_score_stats(track: &mut ReputationState, previous: u16, corrected: u16) {
+    let count = track.score_count;
+    if count == 0 {
+        return;
+    }
+
+    let (mean, m2) = welford_remove(track.score_mean, track.score_m2, count, previous);
+    (track.score_mean, track.score_m2) = welford_add(mean, m2, count - 1, corrected);
+
+    let bucket = &mut track.score_histogram[score_bucket(previous)];
+    *bucket = bucket.saturating_sub(1);
+    let bucket = &mut track.score_histogram[score_bucket(corrected)];
+    *bucket = bucket.saturating_add(1);
+}
+
+pub fn next_batch_account<'info>(
+    accounts: &mut impl Iterator<Item = &'info AccountInfo<'info>>,
+) -> Result<&'info AccountInfo<'info>> {
+    accounts
+        .next()
+        .ok_or_else(|| error!(ErrorCode::InvalidBatch))
+}
+
+/// Prefixes the failing entry's index to the returned error message.
+pub fn batch_entry_error(index: usize, err: Error) -> Error {
+    match err {
+        Error::AnchorError(mut error) => {
+            error.error_msg = format!("Batch entry {}: {}", index, error.error_msg);
+            Error::AnchorError(error)
+        }
+        Error::ProgramError(error) => {
+            msg!("Batch entry {} rejected", index);
+            Error::ProgramError(error)
+        }
+    }
+}
+
 pub fn require_not_paused(config: &ProtocolConfig) -> Result<()> {
     require!(!config.paused, ErrorCode::ProtocolPaused);
     Ok(())
 }
 
-pub fn assert_role(assignment: &RoleAssignment, signer: Pubkey, role: u8) -> Result<()> {
+pub fn min_stake_for_role(config: &ProtocolConfig, role: u8) -> u64 {
+    match role {
+        ROLE_ORACLE => config.min_oracle_stake,
+        ROLE_SIGNALER => config.min_signaler_stake,
+        _ => 0,
+    }
+}
+
+pub fn assert_role(
+    config: &ProtocolConfig,
+    assignment: &RoleAssignment,
+    stake: Option<&MemberStake>,
+    signer: Pubkey,
+    role: u8,
+) -> Result<()> {
     require!(assignment.active, ErrorCode::Unauthorized);
     require!(assignment.role == role, ErrorCode::Unauthorized);
     require!(assignment.member == signer, ErrorCode::Unauthorized);
+
+    let min_stake = min_stake_for_role(config, role);
+    if min_stake > 0 {
+        let stake = stake.ok_or(ErrorCode::InsufficientStake)?;
+        require!(stake.member == signer, ErrorCode::Unauthorized);
+        require!(stake.amount >= min_stake, ErrorCode::InsufficientStake);
+    }
+
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn validate_split(
     platform: Pubkey,
     platform_bps: u16,
@@ -80,13 +2017,35 @@ pub fn validate_split(
     referrer_bps: u16,
     reserve_vault: Pubkey,
     reserve_bps: u16,
+    recipients: &[SplitRecipient],
 ) -> Result<()> {
-    let total = (platform_bps as u32)
+    require!(
+        recipients.len() <= MAX_SPLIT_RECIPIENTS,
+        ErrorCode::InvalidSplitRecipients
+    );
+
+    let mut total = (platform_bps as u32)
         .checked_add(referrer_bps as u32)
         .ok_or(ErrorCode::MathOverflow)?
         .checked_add(reserve_bps as u32)
         .ok_or(ErrorCode::MathOverflow)?;
 
+    for (index, entry) in recipients.iter().enumerate() {
+        require!(
+            entry.bps > 0 && entry.recipient != Pubkey::default(),
+            ErrorCode::InvalidSplitRecipients
+        );
+        require!(
+            recipients[..index]
+                .iter()
+                .all(|other| other.recipient != entry.recipient),
+            ErrorCode::InvalidSplitRecipients
+        );
+        total = total
+            .checked_add(entry.bps as u32)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
     require!(total <= MAX_BPS as u32, ErrorCode::InvalidBps);
 
     if platform_bps > 0 {
@@ -103,3 +2062,51 @@ pub fn validate_split(
 
     Ok(())
 }
+
+pub struct SplitShares {
+    pub platform: u64,
+    pub referrer: u64,
+    pub reserve: u64,
+    pub recipients: Vec<u64>,
+    pub protocol: u64,
+    pub agent: u64,
+}
+
+/// Splits a settlement amount by the agent's split and the protocol fee. Each share rounds
+/// down and the agent receives the remainder.
+pub fn split_settlement(
+    amount: u64,
+    split: &RevenueSplitConfig,
+    protocol_fee_bps: u16,
+) -> Result<SplitShares> {
+    let total_bps = split
+        .recipients
+        .iter()
+        .try_fold(
+            split.platform_bps as u32 + split.referrer_bps as u32 + split.reserve_bps as u32,
+            |total, entry| total.checked_add(entry.bps as u32),
+        )
+        .and_then(|total| total.checked_add(protocol_fee_bps as u32))
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(total_bps <= MAX_BPS as u32, ErrorCode::InvalidBps);
+
+    let share = |bps: u16| (amount as u128 * bps as u128 / MAX_BPS as u128) as u64;
+    let platform = share(split.platform_bps);
+    let referrer = share(split.referrer_bps);
+    let reserve = share(split.reserve_bps);
+    let protocol = share(protocol_fee_bps);
+    let recipients: Vec<u64> = split.recipients.iter().map(|entry| share(entry.bps)).collect();
+
+    // Shares never exceed `amount` because the bps total is capped above.
+    let paid = recipients.iter().sum::<u64>() + platform + referrer + reserve + protocol;
+    let agent = amount.checked_sub(paid).ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(SplitShares {
+        platform,
+        referrer,
+        reserve,
+        recipients,
+        protocol,
+        agent,
+    })
+}
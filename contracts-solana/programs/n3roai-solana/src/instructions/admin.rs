@@ -1,9 +1,17 @@
 use anchor_lang::prelude::*;
 
-use crate::constants::MAX_BPS;
-use crate::contexts::{AdminUpdate, InitializeProtocol, SetRole};
+use crate::constants::{
+    AGGREGATION_BETA, AGGREGATION_EMA, AGGREGATION_WINDOW, MAX_BALLOT_VOTES, MAX_BPS,
+    MAX_FEE_TIERS, MAX_LEADERBOARD_CAPACITY, MAX_REFERRAL_LEVELS, MAX_SCORE_WINDOW, RISK_FLAG_BITS,
+};
+use crate::contexts::{
+    AddSettlementMint, AdminUpdate, InitializeLeaderboard, InitializeProtocol,
+    InitializeRiskFlagTable, SetCategory, SetFeeSchedule, SetLeaderboardConfig, SetReferralLevels,
+    SetRole, UpdateRiskFlagTable, UpdateSettlementMint,
+};
 use crate::errors::ErrorCode;
-use crate::helpers::is_valid_role;
+use crate::helpers::{is_valid_role, is_zero_hash, settlement_mint_fee_config};
+use crate::state::{FeeTier, RiskFlagRule};
 
 #[allow(clippy::too_many_arguments)]
 pub fn initialize_protocol(
@@ -38,6 +46,25 @@ pub fn initialize_protocol(
     config.min_confidence_bps = min_confidence_bps;
     config.score_alpha_bps = score_alpha_bps;
     config.max_signal_age = max_signal_age;
+    config.score_half_life = 0;
+    config.score_quorum = 0;
+    config.score_ballot_window = 0;
+    config.arbiter = Pubkey::default();
+    config.challenge_window = 0;
+    config.challenge_bond = 0;
+    config.min_oracle_stake = 0;
+    config.min_signaler_stake = 0;
+    config.unstake_cooldown = 0;
+    config.reputation_epoch_length = 0;
+    config.signal_reveal_window = 0;
+    config.require_signal_commit = false;
+    config.aggregation_model = AGGREGATION_EMA;
+    config.score_window_size = 0;
+    config.beta_prior_alpha = 0;
+    config.beta_prior_beta = 0;
+    config.aggregation_version = 0;
+    config.aggregation_updated_at = 0;
+    config.sol_treasury = Pubkey::default();
     config.require_verified_for_score = require_verified_for_score;
     config.enforce_settlement_token = enforce_settlement_token;
     config.paused = false;
@@ -101,6 +128,12 @@ pub fn set_protocol_treasury(
     Ok(())
 }
 
+pub fn set_sol_treasury(ctx: Context<AdminUpdate>, sol_treasury: Pubkey) -> Result<()> {
+    require!(sol_treasury != Pubkey::default(), ErrorCode::InvalidAddress);
+    ctx.accounts.protocol_config.sol_treasury = sol_treasury;
+    Ok(())
+}
+
 pub fn set_score_config(
     ctx: Context<AdminUpdate>,
     min_confidence_bps: u16,
@@ -121,6 +154,135 @@ pub fn set_score_config(
     Ok(())
 }
 
+pub fn set_score_half_life(ctx: Context<AdminUpdate>, score_half_life: i64) -> Result<()> {
+    require!(score_half_life >= 0, ErrorCode::InvalidHalfLife);
+    ctx.accounts.protocol_config.score_half_life = score_half_life;
+    Ok(())
+}
+
+pub fn set_score_aggregation(
+    ctx: Context<AdminUpdate>,
+    score_quorum: u8,
+    score_ballot_window: i64,
+) -> Result<()> {
+    require!(
+        score_quorum as usize <= MAX_BALLOT_VOTES,
+        ErrorCode::InvalidQuorum
+    );
+    require!(score_ballot_window >= 0, ErrorCode::InvalidQuorum);
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.score_quorum = score_quorum;
+    config.score_ballot_window = score_ballot_window;
+
+    Ok(())
+}
+
+pub fn set_arbiter(ctx: Context<AdminUpdate>, arbiter: Pubkey) -> Result<()> {
+    ctx.accounts.protocol_config.arbiter = arbiter;
+    Ok(())
+}
+
+pub fn set_challenge_config(
+    ctx: Context<AdminUpdate>,
+    challenge_window: i64,
+    challenge_bond: u64,
+) -> Result<()> {
+    require!(challenge_window >= 0, ErrorCode::InvalidAmount);
+    if challenge_window > 0 {
+        require!(challenge_bond > 0, ErrorCode::InvalidAmount);
+        require!(
+            ctx.accounts.protocol_config.arbiter != Pubkey::default(),
+            ErrorCode::InvalidAddress
+        );
+    }
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.challenge_window = challenge_window;
+    config.challenge_bond = challenge_bond;
+
+    Ok(())
+}
+
+pub fn set_stake_config(
+    ctx: Context<AdminUpdate>,
+    min_oracle_stake: u64,
+    min_signaler_stake: u64,
+    unstake_cooldown: i64,
+) -> Result<()> {
+    require!(unstake_cooldown >= 0, ErrorCode::InvalidAmount);
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.min_oracle_stake = min_oracle_stake;
+    config.min_signaler_stake = min_signaler_stake;
+    config.unstake_cooldown = unstake_cooldown;
+
+    Ok(())
+}
+
+pub fn set_reputation_epoch_length(
+    ctx: Context<AdminUpdate>,
+    reputation_epoch_length: i64,
+) -> Result<()> {
+    require!(reputation_epoch_length >= 0, ErrorCode::InvalidEpochLength);
+    ctx.accounts.protocol_config.reputation_epoch_length = reputation_epoch_length;
+    Ok(())
+}
+
+pub fn set_signal_commit_config(
+    ctx: Context<AdminUpdate>,
+    signal_reveal_window: i64,
+    require_signal_commit: bool,
+) -> Result<()> {
+    require!(signal_reveal_window >= 0, ErrorCode::CommitRevealDisabled);
+    require!(
+        !require_signal_commit || signal_reveal_window > 0,
+        ErrorCode::CommitRevealDisabled
+    );
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.signal_reveal_window = signal_reveal_window;
+    config.require_signal_commit = require_signal_commit;
+
+    Ok(())
+}
+
+/// Switches the reputation aggregation model. Bumping `aggregation_version` marks every
+/// track stale; tracks migrate on their next update or via `migrate_reputation_track`.
+pub fn set_aggregation_model(
+    ctx: Context<AdminUpdate>,
+    aggregation_model: u8,
+    score_window_size: u8,
+    beta_prior_alpha: u32,
+    beta_prior_beta: u32,
+) -> Result<()> {
+    match aggregation_model {
+        AGGREGATION_EMA => {}
+        AGGREGATION_WINDOW => require!(
+            score_window_size > 0 && score_window_size as usize <= MAX_SCORE_WINDOW,
+            ErrorCode::InvalidAggregationModel
+        ),
+        AGGREGATION_BETA => require!(
+            beta_prior_alpha as u64 + beta_prior_beta as u64 > 0,
+            ErrorCode::InvalidAggregationModel
+        ),
+        _ => return err!(ErrorCode::InvalidAggregationModel),
+    }
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.aggregation_model = aggregation_model;
+    config.score_window_size = score_window_size;
+    config.beta_prior_alpha = beta_prior_alpha;
+    config.beta_prior_beta = beta_prior_beta;
+    config.aggregation_version = config
+        .aggregation_version
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    config.aggregation_updated_at = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
 pub fn set_require_verified_for_score(
     ctx: Context<AdminUpdate>,
     require_verified_for_score: bool,
@@ -133,3 +295,208 @@ pub fn set_paused(ctx: Context<AdminUpdate>, paused: bool) -> Result<()> {
     ctx.accounts.protocol_config.paused = paused;
     Ok(())
 }
+
+pub fn initialize_risk_flag_table(
+    ctx: Context<InitializeRiskFlagTable>,
+    suspension_window: i64,
+) -> Result<()> {
+    require!(suspension_window >= 0, ErrorCode::InvalidRiskFlag);
+
+    let table = &mut ctx.accounts.risk_flag_table;
+    table.rules = [RiskFlagRule::default(); RISK_FLAG_BITS];
+    table.suspension_window = suspension_window;
+    table.updated_at = Clock::get()?.unix_timestamp;
+    table.bump = ctx.bumps.risk_flag_table;
+
+    Ok(())
+}
+
+pub fn set_risk_flag_rule(
+    ctx: Context<UpdateRiskFlagTable>,
+    bit: u8,
+    name_hash: [u8; 32],
+    penalty_bps: u16,
+    weight_bps: u16,
+    suspend_threshold: u16,
+) -> Result<()> {
+    require!((bit as usize) < RISK_FLAG_BITS, ErrorCode::InvalidRiskFlag);
+    require!(penalty_bps <= MAX_BPS, ErrorCode::InvalidBps);
+    require!(weight_bps <= MAX_BPS, ErrorCode::InvalidBps);
+    // A zero weight would zero the confidence of every flagged signal.
+    require!(
+        is_zero_hash(&name_hash) || weight_bps > 0,
+        ErrorCode::InvalidRiskFlagWeight
+    );
+
+    let table = &mut ctx.accounts.risk_flag_table;
+    table.rules[bit as usize] = RiskFlagRule {
+        name_hash,
+        penalty_bps,
+        weight_bps,
+        suspend_threshold,
+    };
+    table.updated_at = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+pub fn set_risk_flag_window(
+    ctx: Context<UpdateRiskFlagTable>,
+    suspension_window: i64,
+) -> Result<()> {
+    require!(suspension_window >= 0, ErrorCode::InvalidRiskFlag);
+
+    let table = &mut ctx.accounts.risk_flag_table;
+    table.suspension_window = suspension_window;
+    table.updated_at = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+pub fn set_category(
+    ctx: Context<SetCategory>,
+    category_id: u16,
+    name_hash: [u8; 32],
+    score_alpha_bps: u16,
+    min_confidence_bps: u16,
+    active: bool,
+) -> Result<()> {
+    require!(category_id != 0, ErrorCode::InvalidCategory);
+    require!(
+        score_alpha_bps > 0 && score_alpha_bps <= MAX_BPS,
+        ErrorCode::InvalidBps
+    );
+    require!(min_confidence_bps <= MAX_BPS, ErrorCode::InvalidBps);
+
+    let category = &mut ctx.accounts.category;
+    category.id = category_id;
+    category.name_hash = name_hash;
+    category.score_alpha_bps = score_alpha_bps;
+    category.min_confidence_bps = min_confidence_bps;
+    category.active = active;
+    category.updated_at = Clock::get()?.unix_timestamp;
+    category.bump = ctx.bumps.category;
+
+    Ok(())
+}
+
+pub fn initialize_leaderboard(
+    ctx: Context<InitializeLeaderboard>,
+    category_id: u16,
+    capacity: u16,
+    min_score_count: u32,
+    require_verified: bool,
+) -> Result<()> {
+    require!(
+        capacity > 0 && capacity <= MAX_LEADERBOARD_CAPACITY,
+        ErrorCode::InvalidLeaderboard
+    );
+
+    let leaderboard = &mut ctx.accounts.leaderboard;
+    leaderboard.category = category_id;
+    leaderboard.capacity = capacity;
+    leaderboard.min_score_count = min_score_count;
+    leaderboard.require_verified = require_verified;
+    leaderboard.updated_at = Clock::get()?.unix_timestamp;
+    leaderboard.entries = Vec::with_capacity(capacity as usize);
+    leaderboard.bump = ctx.bumps.leaderboard;
+
+    Ok(())
+}
+
+pub fn set_leaderboard_config(
+    ctx: Context<SetLeaderboardConfig>,
+    min_score_count: u32,
+    require_verified: bool,
+) -> Result<()> {
+    let leaderboard = &mut ctx.accounts.leaderboard;
+    leaderboard.min_score_count = min_score_count;
+    leaderboard.require_verified = require_verified;
+
+    Ok(())
+}
+
+pub fn add_settlement_mint(
+    ctx: Context<AddSettlementMint>,
+    fee_override: bool,
+    protocol_fee_bps: u16,
+) -> Result<()> {
+    require!(protocol_fee_bps <= MAX_BPS, ErrorCode::InvalidBps);
+    require!(
+        ctx.accounts.treasury_token_account.mint == ctx.accounts.mint.key(),
+        ErrorCode::InvalidTokenMint
+    );
+    settlement_mint_fee_config(&ctx.accounts.mint.to_account_info())?;
+
+    let entry = &mut ctx.accounts.settlement_mint_config;
+    entry.mint = ctx.accounts.mint.key();
+    entry.vault = ctx.accounts.settlement_vault.key();
+    entry.treasury_token_account = ctx.accounts.treasury_token_account.key();
+    entry.fee_override = fee_override;
+    entry.protocol_fee_bps = protocol_fee_bps;
+    entry.enabled = true;
+    entry.updated_at = Clock::get()?.unix_timestamp;
+    entry.bump = ctx.bumps.settlement_mint_config;
+
+    Ok(())
+}
+
+pub fn update_settlement_mint(
+    ctx: Context<UpdateSettlementMint>,
+    fee_override: bool,
+    protocol_fee_bps: u16,
+    enabled: bool,
+) -> Result<()> {
+    require!(protocol_fee_bps <= MAX_BPS, ErrorCode::InvalidBps);
+
+    let entry = &mut ctx.accounts.settlement_mint_config;
+    require!(
+        ctx.accounts.treasury_token_account.mint == entry.mint,
+        ErrorCode::InvalidTokenMint
+    );
+
+    entry.treasury_token_account = ctx.accounts.treasury_token_account.key();
+    entry.fee_override = fee_override;
+    entry.protocol_fee_bps = protocol_fee_bps;
+    entry.enabled = enabled;
+    entry.updated_at = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+pub fn set_referral_levels(ctx: Context<SetReferralLevels>, level_bps: Vec<u16>) -> Result<()> {
+    require!(
+        level_bps.len() <= MAX_REFERRAL_LEVELS,
+        ErrorCode::InvalidReferralLevels
+    );
+    let total = level_bps
+        .iter()
+        .fold(0u32, |total, bps| total + u32::from(*bps));
+    // Level 1 is the direct referrer; the levels split the whole referrer share between them.
+    require!(
+        level_bps.is_empty() || total == MAX_BPS as u32,
+        ErrorCode::InvalidReferralLevels
+    );
+
+    let program = &mut ctx.accounts.referral_program;
+    program.level_bps = level_bps;
+    program.updated_at = Clock::get()?.unix_timestamp;
+    program.bump = ctx.bumps.referral_program;
+
+    Ok(())
+}
+
+pub fn set_fee_schedule(ctx: Context<SetFeeSchedule>, tiers: Vec<FeeTier>) -> Result<()> {
+    require!(tiers.len() <= MAX_FEE_TIERS, ErrorCode::InvalidFeeSchedule);
+    require!(
+        tiers.iter().all(|tier| tier.fee_bps <= MAX_BPS),
+        ErrorCode::InvalidFeeSchedule
+    );
+
+    let schedule = &mut ctx.accounts.fee_schedule;
+    schedule.tiers = tiers;
+    schedule.updated_at = Clock::get()?.unix_timestamp;
+    schedule.bump = ctx.bumps.fee_schedule;
+
+    Ok(())
+}
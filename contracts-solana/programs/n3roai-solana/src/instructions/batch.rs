@@ -0,0 +1,527 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{
+    CHALLENGE_NONE, LEADERBOARD_SEED, MAX_BATCH_ENTRIES, PNL_STATS_SEED, REPUTATION_SEED,
+    ROLE_ORACLE, ROLE_SIGNALER, SCORED_TRADES_SEED, SIGNAL_SEED, VERIFICATION_SEED,
+};
+use crate::contexts::{SubmitScoresBatch, SubmitSignalsBatch};
+use crate::errors::ErrorCode;
+use crate::helpers::{
+    apply_category_score, apply_score, assert_role, batch_entry_error, create_pda_account,
+    epoch_due, is_verified, is_zero_hash, load_risk_flag_table, next_batch_account,
+    record_category_risk_flags, record_risk_flags, record_scored_trade, record_trade_pnl,
+    require_not_paused, require_signal_fresh, reset_signal_score, roll_epoch, suspend_verification,
+    update_leaderboard, validate_score_input, validate_signal_category,
+};
+use crate::state::{
+    AgentIdentity, PnlStats, ReputationCategory, ReputationState, RiskFlagTable, ScoreBatchEntry,
+    SignalBatchEntry, TradeSignal, VerificationRecord,
+};
+
+struct SignalPlan<'info> {
+    agent: Pubkey,
+    signal: &'info AccountInfo<'info>,
+    bump: u8,
+}
+
+struct ScorePlan<'info> {
+    signal: usize,
+    record: usize,
+    track: usize,
+    pnl_stats: &'info AccountInfo<'info>,
+    pnl_stats_bump: u8,
+    scored_trades: &'info AccountInfo<'info>,
+    scored_trades_bump: u8,
+    category: Option<(
+        Account<'info, ReputationCategory>,
+        usize,
+        &'info AccountInfo<'info>,
+    )>,
+}
+
+/// Accounts loaded once per batch, so entries sharing an agent see each other's updates.
+#[derive(Default)]
+struct ScoreBatch<'info> {
+    signals: Vec<Account<'info, TradeSignal>>,
+    records: Vec<Account<'info, VerificationRecord>>,
+    tracks: Vec<Account<'info, ReputationState>>,
+    pnl_stats: Vec<Account<'info, PnlStats>>,
+}
+
+pub fn submit_signals_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SubmitSignalsBatch<'info>>,
+    entries: Vec<SignalBatchEntry>,
+) -> Result<()> {
+    let config = &ctx.accounts.protocol_config;
+    require_not_paused(config)?;
+    require!(
+        !config.require_signal_commit,
+        ErrorCode::SignalCommitRequired
+    );
+    assert_role(
+        config,
+        &ctx.accounts.role_assignment,
+        ctx.accounts.member_stake.as_deref(),
+        ctx.accounts.signaler.key(),
+        ROLE_SIGNALER,
+    )?;
+    require!(
+        !entries.is_empty() && entries.len() <= MAX_BATCH_ENTRIES,
+        ErrorCode::InvalidBatch
+    );
+
+    let mut accounts = ctx.remaining_accounts.iter();
+    let mut plans: Vec<SignalPlan> = Vec::with_capacity(entries.len());
+    for (index, entry) in entries.iter().enumerate() {
+        let plan = plan_signal(ctx.program_id, entry, &mut accounts, &plans)
+            .map_err(|err| batch_entry_error(index, err))?;
+        plans.push(plan);
+    }
+    require!(accounts.next().is_none(), ErrorCode::InvalidBatch);
+
+    let now = Clock::get()?.unix_timestamp;
+    let signaler = ctx.accounts.signaler.to_account_info();
+    for (entry, plan) in entries.iter().zip(plans.iter()) {
+        create_pda_account(
+            &signaler,
+            plan.signal,
+            &ctx.accounts.system_program,
+            TradeSignal::SPACE,
+            &[
+                SIGNAL_SEED,
+                plan.agent.as_ref(),
+                &entry.trade_id_hash,
+                &[plan.bump],
+            ],
+        )?;
+
+        let mut signal = Account::<TradeSignal>::try_from_unchecked(plan.signal)?;
+        signal.agent = plan.agent;
+        signal.trade_id_hash = entry.trade_id_hash;
+        signal.result_hash = entry.result_hash;
+        signal.context_hash = entry.context_hash;
+        signal.reporter = ctx.accounts.signaler.key();
+        signal.submitted_at = now;
+        signal.revealed_at = 0;
+        signal.risk_flags = entry.risk_flags;
+        signal.category = entry.category;
+        reset_signal_score(&mut signal);
+        signal.bump = plan.bump;
+        signal.exit(ctx.program_id)?;
+    }
+
+    Ok(())
+}
+
+/// Scores up to `MAX_BATCH_ENTRIES` signals. Missing verification records, reputation tracks,
+/// PnL stats and trade indexes are created at the oracle's expense; category tracks must already
+/// exist. Epochs are not rolled here, so an entry whose global or category epoch is due is
+/// rejected with `EpochNotClosed` until `close_reputation_epoch` or a single score rolls it.
+pub fn submit_scores_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SubmitScoresBatch<'info>>,
+    entries: Vec<ScoreBatchEntry>,
+) -> Result<()> {
+    let config = &ctx.accounts.protocol_config;
+    require_not_paused(config)?;
+    assert_role(
+        config,
+        &ctx.accounts.role_assignment,
+        ctx.accounts.member_stake.as_deref(),
+        ctx.accounts.oracle.key(),
+        ROLE_ORACLE,
+    )?;
+    require!(config.score_quorum <= 1, ErrorCode::ScoreAggregationEnabled);
+    require!(
+        !entries.is_empty() && entries.len() <= MAX_BATCH_ENTRIES,
+        ErrorCode::InvalidBatch
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let mut accounts = ctx.remaining_accounts.iter();
+    let mut batch = ScoreBatch::default();
+    let mut plans = Vec::with_capacity(entries.len());
+    for (index, entry) in entries.iter().enumerate() {
+        let plan = batch
+            .plan(ctx.program_id, ctx.accounts, entry, &mut accounts, now)
+            .map_err(|err| batch_entry_error(index, err))?;
+        plans.push(plan);
+    }
+    require!(accounts.next().is_none(), ErrorCode::InvalidBatch);
+
+    let table = load_risk_flag_table(&ctx.accounts.risk_flag_table)?;
+    for (index, (entry, plan)) in entries.iter().zip(plans.iter()).enumerate() {
+        batch
+            .score(ctx.accounts, entry, plan, &table, now)
+            .map_err(|err| batch_entry_error(index, err))?;
+    }
+
+    batch.exit(ctx.program_id)
+}
+
+fn plan_signal<'info>(
+    program_id: &Pubkey,
+    entry: &SignalBatchEntry,
+    accounts: &mut impl Iterator<Item = &'info AccountInfo<'info>>,
+    planned: &[SignalPlan<'info>],
+) -> Result<SignalPlan<'info>> {
+    require!(!is_zero_hash(&entry.trade_id_hash), ErrorCode::InvalidHash);
+    require!(!is_zero_hash(&entry.result_hash), ErrorCode::InvalidHash);
+
+    let agent_identity = Account::<AgentIdentity>::try_from(next_batch_account(accounts)?)?;
+    let agent = agent_identity.key();
+    let signal = next_batch_account(accounts)?;
+
+    let (expected, bump) = Pubkey::find_program_address(
+        &[SIGNAL_SEED, agent.as_ref(), &entry.trade_id_hash],
+        program_id,
+    );
+    require!(
+        signal.key() == expected && signal.is_writable,
+        ErrorCode::InvalidBatchAccount
+    );
+    require!(signal.data_is_empty(), ErrorCode::SignalAlreadyExists);
+    require!(
+        planned.iter().all(|plan| plan.signal.key() != expected),
+        ErrorCode::DuplicateBatchEntry
+    );
+
+    if entry.category != 0 {
+        let category = Account::<ReputationCategory>::try_from(next_batch_account(accounts)?)?;
+        validate_signal_category(entry.category, Some(&category))?;
+    }
+
+    Ok(SignalPlan {
+        agent,
+        signal,
+        bump,
+    })
+}
+
+impl<'info> ScoreBatch<'info> {
+    fn plan(
+        &mut self,
+        program_id: &Pubkey,
+        context: &SubmitScoresBatch<'info>,
+        entry: &ScoreBatchEntry,
+        accounts: &mut impl Iterator<Item = &'info AccountInfo<'info>>,
+        now: i64,
+    ) -> Result<ScorePlan<'info>> {
+        let config = &context.protocol_config;
+        validate_score_input(config, entry.score, entry.confidence_bps, &entry.score_hash)?;
+
+        let agent_identity = Account::<AgentIdentity>::try_from(next_batch_account(accounts)?)?;
+        let agent = agent_identity.key();
+
+        let signal_info = next_batch_account(accounts)?;
+        require!(
+            self.signals
+                .iter()
+                .all(|signal| signal.key() != signal_info.key()),
+            ErrorCode::DuplicateBatchEntry
+        );
+        let signal = Account::<TradeSignal>::try_from(signal_info)?;
+        require!(
+            signal_info.is_writable
+                && signal.agent == agent
+                && signal.trade_id_hash == entry.trade_id_hash,
+            ErrorCode::InvalidBatchAccount
+        );
+        require!(!signal.score_submitted, ErrorCode::ScoreAlreadySubmitted);
+        require_signal_fresh(config, signal.submitted_at, now)?;
+
+        let record_info = next_batch_account(accounts)?;
+        let record = match create_agent_account(
+            context,
+            record_info,
+            VERIFICATION_SEED,
+            agent,
+            VerificationRecord::SPACE,
+            program_id,
+        )? {
+            Some(bump) => {
+                let mut record = Account::<VerificationRecord>::try_from_unchecked(record_info)?;
+                record.agent = agent;
+                record.bump = bump;
+                self.records.push(record);
+                self.records.len() - 1
+            }
+            None => cached(&mut self.records, record_info)?,
+        };
+        require!(
+            self.records[record].agent == agent,
+            ErrorCode::InvalidBatchAccount
+        );
+        if config.require_verified_for_score {
+            require!(
+                is_verified(&self.records[record], now),
+                ErrorCode::VerificationRequired
+            );
+        }
+
+        let track_info = next_batch_account(accounts)?;
+        let track = match create_agent_account(
+            context,
+            track_info,
+            REPUTATION_SEED,
+            agent,
+            ReputationState::SPACE,
+            program_id,
+        )? {
+            Some(bump) => {
+                let mut track = Account::<ReputationState>::try_from_unchecked(track_info)?;
+                track.agent = agent;
+                track.bump = bump;
+                self.tracks.push(track);
+                self.tracks.len() - 1
+            }
+            None => cached(&mut self.tracks, track_info)?,
+        };
+        require!(
+            self.tracks[track].agent == agent && self.tracks[track].category == 0,
+            ErrorCode::InvalidBatchAccount
+        );
+        require!(
+            !epoch_due(&self.tracks[track], config.reputation_epoch_length, now),
+            ErrorCode::EpochNotClosed
+        );
+
+        let pnl_stats = next_batch_account(accounts)?;
+        let (expected, pnl_stats_bump) =
+            Pubkey::find_program_address(&[PNL_STATS_SEED, agent.as_ref()], program_id);
+        require!(
+            pnl_stats.key() == expected && pnl_stats.is_writable,
+            ErrorCode::InvalidBatchAccount
+        );
+
+        let scored_trades = next_batch_account(accounts)?;
+        let (expected, scored_trades_bump) =
+            Pubkey::find_program_address(&[SCORED_TRADES_SEED, agent.as_ref()], program_id);
+        require!(
+            scored_trades.key() == expected && scored_trades.is_writable,
+            ErrorCode::InvalidBatchAccount
+        );
+
+        let category = if signal.category != 0 {
+            let category = Account::<ReputationCategory>::try_from(next_batch_account(accounts)?)?;
+            let category_track = cached(&mut self.tracks, next_batch_account(accounts)?)?;
+            let state = &self.tracks[category_track];
+            require!(
+                category.active && category.id == signal.category,
+                ErrorCode::InvalidCategory
+            );
+            require!(
+                state.agent == agent && state.category == signal.category,
+                ErrorCode::InvalidCategory
+            );
+            require!(
+                entry.confidence_bps >= category.min_confidence_bps,
+                ErrorCode::InvalidConfidence
+            );
+            require!(
+                !epoch_due(state, config.reputation_epoch_length, now),
+                ErrorCode::EpochNotClosed
+            );
+
+            let board = next_batch_account(accounts)?;
+            let (expected, _) = Pubkey::find_program_address(
+                &[LEADERBOARD_SEED, &signal.category.to_le_bytes()],
+                program_id,
+            );
+            require!(
+                board.key() == expected && board.is_writable,
+                ErrorCode::InvalidBatchAccount
+            );
+            Some((category, category_track, board))
+        } else {
+            None
+        };
+
+        self.signals.push(signal);
+        Ok(ScorePlan {
+            signal: self.signals.len() - 1,
+            record,
+            track,
+            pnl_stats,
+            pnl_stats_bump,
+            scored_trades,
+            scored_trades_bump,
+            category,
+        })
+    }
+
+    /// Scores one planned entry against the batch's loaded accounts.
+    fn score(
+        &mut self,
+        context: &SubmitScoresBatch<'info>,
+        entry: &ScoreBatchEntry,
+        plan: &ScorePlan<'info>,
+        table: &RiskFlagTable,
+        now: i64,
+    ) -> Result<()> {
+        let config = &context.protocol_config;
+        let payer = &context.oracle;
+        let signal = &mut self.signals[plan.signal];
+        record_scored_trade(
+            plan.scored_trades,
+            signal.agent,
+            plan.scored_trades_bump,
+            &signal.trade_id_hash,
+            payer,
+            &context.system_program,
+        )?;
+        signal.score_submitted = true;
+        signal.score_hash = entry.score_hash;
+        signal.score = entry.score;
+        signal.confidence_bps = entry.confidence_bps;
+        signal.oracle = context.oracle.key();
+        signal.score_submitted_at = now;
+        signal.challenge_status = CHALLENGE_NONE;
+
+        let track = &mut self.tracks[plan.track];
+        let track_key = track.key();
+        roll_epoch(track, track_key, None, None, config, now)?;
+        apply_score(track, signal, config, table, now)?;
+
+        if let Some(reason_hash) = record_risk_flags(table, track, signal.risk_flags, now) {
+            suspend_verification(&mut self.records[plan.record], reason_hash, now);
+        }
+
+        if let Some((category, category_track, _)) = &plan.category {
+            apply_category_score(
+                Some(&mut self.tracks[*category_track]),
+                signal,
+                Some(category),
+                config,
+                now,
+            )?;
+            record_category_risk_flags(table, &mut self.tracks[*category_track], signal.risk_flags);
+        }
+
+        let agent = self.signals[plan.signal].agent;
+        let stats = self.load_pnl_stats(
+            plan.pnl_stats,
+            agent,
+            plan.pnl_stats_bump,
+            payer,
+            &context.system_program,
+        )?;
+        record_trade_pnl(&mut self.pnl_stats[stats], &self.signals[plan.signal], now)?;
+
+        let verified = is_verified(&self.records[plan.record], now);
+        update_leaderboard(
+            &context.leaderboard,
+            &self.tracks[plan.track],
+            verified,
+            config.score_half_life,
+            now,
+        )?;
+        if let Some((_, category_track, board)) = &plan.category {
+            update_leaderboard(
+                board,
+                &self.tracks[*category_track],
+                verified,
+                config.score_half_life,
+                now,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Loads an agent's PnL stats on first use, creating the account at the oracle's expense.
+    fn load_pnl_stats(
+        &mut self,
+        info: &'info AccountInfo<'info>,
+        agent: Pubkey,
+        bump: u8,
+        payer: &AccountInfo<'info>,
+        system_program: &Program<'info, System>,
+    ) -> Result<usize> {
+        if let Some(index) = self
+            .pnl_stats
+            .iter()
+            .position(|stats| stats.key() == info.key())
+        {
+            return Ok(index);
+        }
+
+        let stats = if info.data_is_empty() {
+            create_pda_account(
+                payer,
+                info,
+                system_program,
+                PnlStats::SPACE,
+                &[PNL_STATS_SEED, agent.as_ref(), &[bump]],
+            )?;
+            let mut stats = Account::<PnlStats>::try_from_unchecked(info)?;
+            stats.agent = agent;
+            stats.bump = bump;
+            stats
+        } else {
+            Account::<PnlStats>::try_from(info)?
+        };
+        self.pnl_stats.push(stats);
+        Ok(self.pnl_stats.len() - 1)
+    }
+
+    fn exit(&self, program_id: &Pubkey) -> Result<()> {
+        for signal in &self.signals {
+            signal.exit(program_id)?;
+        }
+        for record in &self.records {
+            record.exit(program_id)?;
+        }
+        for track in &self.tracks {
+            track.exit(program_id)?;
+        }
+        for stats in &self.pnl_stats {
+            stats.exit(program_id)?;
+        }
+        Ok(())
+    }
+}
+
+fn cached<'info, T>(
+    cache: &mut Vec<Account<'info, T>>,
+    info: &'info AccountInfo<'info>,
+) -> Result<usize>
+where
+    T: AccountSerialize + AccountDeserialize + Owner + Clone,
+{
+    if let Some(index) = cache.iter().position(|account| account.key() == info.key()) {
+        return Ok(index);
+    }
+
+    require!(info.is_writable, ErrorCode::InvalidBatchAccount);
+    cache.push(Account::try_from(info)?);
+    Ok(cache.len() - 1)
+}
+
+/// Creates an agent's verification record or reputation track at the oracle's expense when the
+/// entry's account does not exist yet, returning its bump.
+fn create_agent_account<'info>(
+    context: &SubmitScoresBatch<'info>,
+    info: &'info AccountInfo<'info>,
+    seed: &[u8],
+    agent: Pubkey,
+    space: usize,
+    program_id: &Pubkey,
+) -> Result<Option<u8>> {
+    if !info.data_is_empty() {
+        return Ok(None);
+    }
+
+    let (expected, bump) = Pubkey::find_program_address(&[seed, agent.as_ref()], program_id);
+    require!(
+        info.key() == expected && info.is_writable,
+        ErrorCode::InvalidBatchAccount
+    );
+    create_pda_account(
+        &context.oracle,
+        info,
+        &context.system_program,
+        space,
+        &[seed, agent.as_ref(), &[bump]],
+    )?;
+    Ok(Some(bump))
+}
@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{
+    CHALLENGE_NONE, CHALLENGE_OPEN, CHALLENGE_REJECTED, CHALLENGE_UPHELD, ROLE_ORACLE,
+    VAULT_AUTHORITY_SEED,
+};
+use crate::contexts::{OpenChallenge, ResolveChallenge};
+use crate::errors::ErrorCode;
+use crate::helpers::{
+    assert_role, close_vault, is_verified_account, is_zero_hash, load_risk_flag_table,
+    require_mint_treasury, require_not_paused, revert_category_score, revert_score,
+    transfer_from_vault, transfer_to_vault, unrecord_category_risk_flags, unrecord_risk_flags,
+    unrecord_trade_pnl, update_leaderboards,
+};
+
+pub fn open_challenge(
+    ctx: Context<OpenChallenge>,
+    trade_id_hash: [u8; 32],
+    reason_hash: [u8; 32],
+) -> Result<()> {
+    let _ = trade_id_hash;
+
+    let config = &ctx.accounts.protocol_config;
+    require_not_paused(config)?;
+    require!(config.challenge_window > 0, ErrorCode::ChallengesDisabled);
+    require!(!is_zero_hash(&reason_hash), ErrorCode::InvalidHash);
+
+    let signal = &ctx.accounts.trade_signal;
+    let challenger = ctx.accounts.challenger.key();
+    require!(signal.score_submitted, ErrorCode::ChallengeNotOpen);
+    require!(
+        signal.challenge_status == CHALLENGE_NONE,
+        ErrorCode::ScoreChallenged
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let window_end = signal
+        .score_submitted_at
+        .checked_add(config.challenge_window)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(now < window_end, ErrorCode::ChallengeWindowClosed);
+
+    if challenger != ctx.accounts.agent_identity.owner {
+        let assignment = ctx
+            .accounts
+            .role_assignment
+            .as_ref()
+            .ok_or(ErrorCode::Unauthorized)?;
+        assert_role(
+            config,
+            assignment,
+            ctx.accounts.member_stake.as_deref(),
+            challenger,
+            ROLE_ORACLE,
+        )?;
+        require!(challenger != signal.oracle, ErrorCode::Unauthorized);
+    }
+
+    require!(
+        ctx.accounts.settlement_mint.key() == config.settlement_mint,
+        ErrorCode::SettlementTokenMismatch
+    );
+    require!(
+        ctx.accounts.challenger_token_account.owner == challenger,
+        ErrorCode::InvalidTokenAccountOwner
+    );
+    require!(
+        ctx.accounts.challenger_token_account.mint == config.settlement_mint,
+        ErrorCode::InvalidTokenMint
+    );
+
+    let bond = config.challenge_bond;
+    transfer_to_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.challenger_token_account,
+        &ctx.accounts.challenge_vault,
+        &ctx.accounts.challenger,
+        bond,
+    )?;
+
+    let challenge = &mut ctx.accounts.score_challenge;
+    challenge.trade_signal = ctx.accounts.trade_signal.key();
+    challenge.agent = ctx.accounts.agent_identity.key();
+    challenge.challenger = challenger;
+    challenge.bond = bond;
+    challenge.reason_hash = reason_hash;
+    challenge.opened_at = now;
+    challenge.status = CHALLENGE_OPEN;
+    challenge.resolution_hash = [0u8; 32];
+    challenge.resolved_at = 0;
+    challenge.bump = ctx.bumps.score_challenge;
+
+    ctx.accounts.trade_signal.challenge_status = CHALLENGE_OPEN;
+
+    Ok(())
+}
+
+pub fn resolve_challenge(
+    ctx: Context<ResolveChallenge>,
+    trade_id_hash: [u8; 32],
+    upheld: bool,
+    resolution_hash: [u8; 32],
+) -> Result<()> {
+    let _ = trade_id_hash;
+
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    require!(!is_zero_hash(&resolution_hash), ErrorCode::InvalidHash);
+    require!(
+        ctx.accounts.score_challenge.status == CHALLENGE_OPEN,
+        ErrorCode::ChallengeNotOpen
+    );
+
+    require_mint_treasury(
+        &ctx.accounts.protocol_config,
+        ctx.accounts.settlement_mint_config.as_deref(),
+        ctx.accounts.challenge_vault.mint,
+        &ctx.accounts.protocol_treasury_token_account,
+    )?;
+    require!(
+        ctx.accounts.challenger_token_account.owner == ctx.accounts.score_challenge.challenger,
+        ErrorCode::InvalidTokenAccountOwner
+    );
+    require!(
+        ctx.accounts.challenger_token_account.mint == ctx.accounts.challenge_vault.mint,
+        ErrorCode::InvalidTokenMint
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let status = if upheld {
+        revert_score(
+            &mut ctx.accounts.reputation_state,
+            &ctx.accounts.trade_signal,
+            &ctx.accounts.protocol_config,
+        )?;
+        revert_category_score(
+            ctx.accounts.category_reputation_state.as_deref_mut(),
+            &ctx.accounts.trade_signal,
+            ctx.accounts.category.as_deref(),
+            &ctx.accounts.protocol_config,
+        )?;
+        let risk_flag_table = load_risk_flag_table(&ctx.accounts.risk_flag_table)?;
+        unrecord_risk_flags(
+            &risk_flag_table,
+            &mut ctx.accounts.reputation_state,
+            &ctx.accounts.trade_signal,
+        );
+        if ctx.accounts.trade_signal.category != 0 {
+            if let Some(track) = ctx.accounts.category_reputation_state.as_deref_mut() {
+                unrecord_category_risk_flags(&risk_flag_table, track, &ctx.accounts.trade_signal);
+            }
+        }
+        unrecord_trade_pnl(&mut ctx.accounts.pnl_stats, &ctx.accounts.trade_signal, now)?;
+        update_leaderboards(
+            &ctx.accounts.leaderboard,
+            &ctx.accounts.category_leaderboard,
+            &ctx.accounts.reputation_state,
+            ctx.accounts.category_reputation_state.as_deref(),
+            is_verified_account(&ctx.accounts.verification_record, now)?,
+            ctx.accounts.protocol_config.score_half_life,
+            now,
+        )?;
+        CHALLENGE_UPHELD
+    } else {
+        CHALLENGE_REJECTED
+    };
+
+    let payout_account = if upheld {
+        &ctx.accounts.challenger_token_account
+    } else {
+        &ctx.accounts.protocol_treasury_token_account
+    };
+
+    let signer_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        &[ctx.accounts.protocol_config.vault_authority_bump],
+    ];
+
+    transfer_from_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.challenge_vault,
+        payout_account,
+        &ctx.accounts.vault_authority,
+        signer_seeds,
+        ctx.accounts.challenge_vault.amount,
+    )?;
+
+    close_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.challenge_vault,
+        &ctx.accounts.challenger.to_account_info(),
+        &ctx.accounts.vault_authority,
+        signer_seeds,
+    )?;
+
+    let challenge = &mut ctx.accounts.score_challenge;
+    challenge.status = status;
+    challenge.resolution_hash = resolution_hash;
+    challenge.resolved_at = now;
+
+    ctx.accounts.trade_signal.challenge_status = status;
+
+    Ok(())
+}
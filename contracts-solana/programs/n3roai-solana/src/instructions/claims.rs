@@ -0,0 +1,278 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{CLAIMABLE_SEED, ROLE_REVENUE_OPERATOR, VAULT_AUTHORITY_SEED};
+use crate::contexts::{AccrueSettlement, Claim};
+use crate::errors::ErrorCode;
+use crate::helpers::{
+    assert_role, create_pda_account, is_zero_hash, require_direct_referral,
+    require_external_reserve, require_not_paused, require_unallocated, scheduled_fee_bps,
+    settlement_mint_fee_config, settlement_route, split_settlement, VaultTransfer,
+};
+use crate::state::ClaimableBalance;
+
+/// Claimable balance credits collected for one accrual, merged per balance account so an
+/// owner that fills several split slots is written once.
+#[derive(Default)]
+struct Credits<'info> {
+    accounts: Vec<&'info AccountInfo<'info>>,
+    owners: Vec<Pubkey>,
+    amounts: Vec<u64>,
+}
+
+impl<'info> Credits<'info> {
+    fn add(&mut self, info: &'info AccountInfo<'info>, owner: Pubkey, amount: u64) -> Result<()> {
+        match self.owners.iter().position(|existing| *existing == owner) {
+            Some(index) => {
+                require!(
+                    self.accounts[index].key() == info.key(),
+                    ErrorCode::InvalidClaimAccount
+                );
+                self.amounts[index] = self.amounts[index]
+                    .checked_add(amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+            None => {
+                self.accounts.push(info);
+                self.owners.push(owner);
+                self.amounts.push(amount);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Credits each split party's claimable balance instead of transferring. Accruals share the
+/// `receipt` PDA with push distributions, so a reference settles once either way, and the vault
+/// must hold `amount` on top of the ledger's `outstanding`.
+pub fn accrue_settlement<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AccrueSettlement<'info>>,
+    reference: [u8; 32],
+    amount: u64,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    assert_role(
+        &ctx.accounts.protocol_config,
+        &ctx.accounts.role_assignment,
+        None,
+        ctx.accounts.operator.key(),
+        ROLE_REVENUE_OPERATOR,
+    )?;
+
+    require!(!is_zero_hash(&reference), ErrorCode::InvalidHash);
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let protocol = &ctx.accounts.protocol_config;
+    let split = &ctx.accounts.split_config;
+    require!(split.vesting_duration == 0, ErrorCode::VestingNotSupported);
+    if split.referrer_bps > 0 {
+        require_direct_referral(&ctx.accounts.referral_program)?;
+    }
+    if split.reserve_bps > 0 {
+        require!(
+            ctx.accounts.reserve_wallet.key() == split.reserve_vault,
+            ErrorCode::InvalidAddress
+        );
+        require_external_reserve(&ctx.accounts.reserve_wallet)?;
+    }
+    let vault = &ctx.accounts.settlement_vault;
+    let treasury = &ctx.accounts.protocol_treasury_token_account;
+
+    let base_fee_bps = settlement_route(
+        protocol,
+        ctx.accounts.settlement_mint_config.as_deref(),
+        vault,
+        treasury,
+    )?;
+    require!(
+        vault.owner == ctx.accounts.vault_authority.key(),
+        ErrorCode::InvalidTokenAccountOwner
+    );
+    require!(treasury.mint == vault.mint, ErrorCode::InvalidTokenMint);
+
+    let ledger = &ctx.accounts.claim_ledger;
+    require_unallocated(vault, ledger.outstanding, amount)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let protocol_fee_bps = scheduled_fee_bps(
+        &ctx.accounts.fee_schedule,
+        base_fee_bps,
+        protocol,
+        ctx.accounts.verification_record.as_deref(),
+        ctx.accounts.reputation_state.as_deref(),
+        &mut ctx.accounts.settlement_volume,
+        amount,
+        now,
+    )?;
+    ctx.accounts.settlement_volume.agent = ctx.accounts.agent_identity.key();
+    ctx.accounts.settlement_volume.mint = vault.mint;
+    ctx.accounts.settlement_volume.bump = ctx.bumps.settlement_volume;
+
+    let shares = split_settlement(amount, split, protocol_fee_bps)?;
+
+    // Remaining accounts: claimable balances of the agent wallet, the platform, referrer and
+    // reserve for non-zero slots, each extra recipient, then the treasury owner when the
+    // protocol fee is non-zero.
+    let mut accounts = ctx.remaining_accounts.iter();
+    let mut next = || {
+        accounts
+            .next()
+            .ok_or(error!(ErrorCode::InvalidClaimAccount))
+    };
+    let mut credits = Credits::default();
+    credits.add(
+        next()?,
+        ctx.accounts.agent_identity.agent_wallet,
+        shares.agent,
+    )?;
+    for (owner, bps, share) in [
+        (split.platform, split.platform_bps, shares.platform),
+        (split.referrer, split.referrer_bps, shares.referrer),
+        (split.reserve_vault, split.reserve_bps, shares.reserve),
+    ] {
+        if bps > 0 {
+            credits.add(next()?, owner, share)?;
+        }
+    }
+    for (entry, share) in split.recipients.iter().zip(shares.recipients.iter()) {
+        credits.add(next()?, entry.recipient, *share)?;
+    }
+    if protocol_fee_bps > 0 {
+        credits.add(next()?, treasury.owner, shares.protocol)?;
+    }
+    require!(next().is_err(), ErrorCode::InvalidClaimAccount);
+
+    let vault_key = vault.key();
+    let operator = ctx.accounts.operator.to_account_info();
+    for ((info, owner), credit) in credits
+        .accounts
+        .iter()
+        .zip(credits.owners.iter())
+        .zip(credits.amounts.iter())
+    {
+        let (expected, bump) = Pubkey::find_program_address(
+            &[CLAIMABLE_SEED, vault_key.as_ref(), owner.as_ref()],
+            ctx.program_id,
+        );
+        require!(
+            info.key() == expected && info.is_writable,
+            ErrorCode::InvalidClaimAccount
+        );
+
+        let mut balance = if info.data_is_empty() {
+            create_pda_account(
+                &operator,
+                info,
+                &ctx.accounts.system_program,
+                ClaimableBalance::SPACE,
+                &[CLAIMABLE_SEED, vault_key.as_ref(), owner.as_ref(), &[bump]],
+            )?;
+            let mut balance = Account::<ClaimableBalance>::try_from_unchecked(info)?;
+            balance.owner = *owner;
+            balance.vault = vault_key;
+            balance.bump = bump;
+            balance
+        } else {
+            Account::<ClaimableBalance>::try_from(info)?
+        };
+
+        balance.amount = balance
+            .amount
+            .checked_add(*credit)
+            .ok_or(ErrorCode::MathOverflow)?;
+        balance.total_accrued = balance
+            .total_accrued
+            .checked_add(*credit)
+            .ok_or(ErrorCode::MathOverflow)?;
+        balance.updated_at = now;
+        balance.exit(ctx.program_id)?;
+    }
+
+    let ledger = &mut ctx.accounts.claim_ledger;
+    ledger.vault = vault_key;
+    ledger.mint = ctx.accounts.settlement_vault.mint;
+    ledger.outstanding = ledger
+        .outstanding
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    ledger.total_accrued = ledger
+        .total_accrued
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    ledger.updated_at = now;
+    ledger.bump = ctx.bumps.claim_ledger;
+
+    let receipt = &mut ctx.accounts.distribution_receipt;
+    receipt.agent = ctx.accounts.agent_identity.key();
+    receipt.reference = reference;
+    receipt.amount = amount;
+    receipt.mint = ctx.accounts.settlement_vault.mint;
+    receipt.transfer_fee = 0;
+    receipt.protocol_fee_bps = protocol_fee_bps;
+    receipt.operator = ctx.accounts.operator.key();
+    receipt.distributed_at = now;
+    receipt.bump = ctx.bumps.distribution_receipt;
+
+    Ok(())
+}
+
+/// Pays out of a claimable balance to any token account of the vault mint. Transfer fees are
+/// withheld here rather than at accrual, and the vault must still cover `outstanding` afterwards.
+pub fn claim(ctx: Context<Claim>, amount: u64) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        amount <= ctx.accounts.claimable_balance.amount,
+        ErrorCode::InsufficientClaimBalance
+    );
+    require!(
+        ctx.accounts.settlement_vault.owner == ctx.accounts.vault_authority.key(),
+        ErrorCode::InvalidTokenAccountOwner
+    );
+    require!(
+        ctx.accounts.destination_token_account.mint == ctx.accounts.settlement_vault.mint,
+        ErrorCode::InvalidTokenMint
+    );
+
+    let signer_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        &[ctx.accounts.protocol_config.vault_authority_bump],
+    ];
+    let transfer = VaultTransfer {
+        token_program: &ctx.accounts.token_program,
+        vault: &ctx.accounts.settlement_vault,
+        mint: &ctx.accounts.settlement_mint,
+        authority: &ctx.accounts.vault_authority,
+        signer_seeds,
+        fee_config: settlement_mint_fee_config(&ctx.accounts.settlement_mint.to_account_info())?,
+        epoch: Clock::get()?.epoch,
+    };
+    transfer.send(&ctx.accounts.destination_token_account, amount)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let balance = &mut ctx.accounts.claimable_balance;
+    balance.amount -= amount;
+    balance.total_claimed = balance
+        .total_claimed
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    balance.updated_at = now;
+
+    let ledger = &mut ctx.accounts.claim_ledger;
+    ledger.outstanding = ledger
+        .outstanding
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    ledger.total_claimed = ledger
+        .total_claimed
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    ledger.updated_at = now;
+
+    ctx.accounts.settlement_vault.reload()?;
+    require!(
+        ctx.accounts.settlement_vault.amount >= ctx.accounts.claim_ledger.outstanding,
+        ErrorCode::ClaimsUnderfunded
+    );
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+use crate::constants::ROLE_SIGNALER;
+use crate::contexts::{CommitSignal, ExpireSignalCommitment, RevealSignal};
+use crate::errors::ErrorCode;
+use crate::helpers::{
+    assert_role, is_zero_hash, require_not_paused, reset_signal_score, validate_signal_category,
+};
+
+pub fn commit_signal(ctx: Context<CommitSignal>, commitment: [u8; 32]) -> Result<()> {
+    let config = &ctx.accounts.protocol_config;
+    require_not_paused(config)?;
+    require!(
+        config.signal_reveal_window > 0,
+        ErrorCode::CommitRevealDisabled
+    );
+    assert_role(
+        config,
+        &ctx.accounts.role_assignment,
+        ctx.accounts.member_stake.as_deref(),
+        ctx.accounts.signaler.key(),
+        ROLE_SIGNALER,
+    )?;
+    require!(!is_zero_hash(&commitment), ErrorCode::InvalidHash);
+
+    let now = Clock::get()?.unix_timestamp;
+    let entry = &mut ctx.accounts.signal_commitment;
+    entry.agent = ctx.accounts.agent_identity.key();
+    entry.signaler = ctx.accounts.signaler.key();
+    entry.commitment = commitment;
+    entry.committed_at = now;
+    entry.expires_at = now
+        .checked_add(config.signal_reveal_window)
+        .ok_or(ErrorCode::MathOverflow)?;
+    entry.bump = ctx.bumps.signal_commitment;
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn reveal_signal(
+    ctx: Context<RevealSignal>,
+    commitment: [u8; 32],
+    trade_id_hash: [u8; 32],
+    result_hash: [u8; 32],
+    context_hash: [u8; 32],
+    risk_flags: u8,
+    category: u16,
+    salt: [u8; 32],
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    assert_role(
+        &ctx.accounts.protocol_config,
+        &ctx.accounts.role_assignment,
+        ctx.accounts.member_stake.as_deref(),
+        ctx.accounts.signaler.key(),
+        ROLE_SIGNALER,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let entry = &ctx.accounts.signal_commitment;
+    require!(now <= entry.expires_at, ErrorCode::CommitmentExpired);
+
+    let agent = ctx.accounts.agent_identity.key();
+    let expected = keccak::hashv(&[
+        agent.as_ref(),
+        &trade_id_hash,
+        &result_hash,
+        &context_hash,
+        &[risk_flags],
+        &category.to_le_bytes(),
+        &salt,
+    ]);
+    require!(
+        expected.to_bytes() == commitment,
+        ErrorCode::CommitmentMismatch
+    );
+
+    require!(!is_zero_hash(&trade_id_hash), ErrorCode::InvalidHash);
+    require!(!is_zero_hash(&result_hash), ErrorCode::InvalidHash);
+    validate_signal_category(category, ctx.accounts.category.as_deref())?;
+
+    let committed_at = entry.committed_at;
+    let signal = &mut ctx.accounts.trade_signal;
+    signal.agent = agent;
+    signal.trade_id_hash = trade_id_hash;
+    signal.result_hash = result_hash;
+    signal.context_hash = context_hash;
+    signal.reporter = ctx.accounts.signaler.key();
+    signal.submitted_at = committed_at;
+    signal.revealed_at = now;
+    signal.risk_flags = risk_flags;
+    signal.category = category;
+    reset_signal_score(signal);
+    signal.bump = ctx.bumps.trade_signal;
+
+    Ok(())
+}
+
+pub fn expire_signal_commitment(
+    ctx: Context<ExpireSignalCommitment>,
+    commitment: [u8; 32],
+) -> Result<()> {
+    let _ = commitment;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now > ctx.accounts.signal_commitment.expires_at,
+        ErrorCode::CommitmentNotExpired
+    );
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+use crate::constants::{
+    CHALLENGE_NONE, MAX_MERKLE_DEPTH, MAX_SIGNAL_BATCH_LEAVES, ROLE_ORACLE, ROLE_SIGNALER,
+};
+use crate::contexts::{SubmitCompressedScore, SubmitSignalBatchRoot};
+use crate::errors::ErrorCode;
+use crate::helpers::{
+    apply_scored_signal, assert_role, is_zero_hash, require_not_paused, require_signal_fresh,
+    validate_score_input, validate_signal_category, ScoredSignalAccounts,
+};
+use crate::math::verify_proof;
+use crate::state::{CompressedSignalLeaf, TradeSignal};
+
+const LEAF_PREFIX: u8 = 0;
+
+pub fn submit_signal_batch_root(
+    ctx: Context<SubmitSignalBatchRoot>,
+    root: [u8; 32],
+    leaf_count: u32,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    assert_role(
+        &ctx.accounts.protocol_config,
+        &ctx.accounts.role_assignment,
+        ctx.accounts.member_stake.as_deref(),
+        ctx.accounts.signaler.key(),
+        ROLE_SIGNALER,
+    )?;
+    require!(!is_zero_hash(&root), ErrorCode::InvalidHash);
+    require!(
+        leaf_count > 0 && leaf_count <= MAX_SIGNAL_BATCH_LEAVES,
+        ErrorCode::InvalidLeafCount
+    );
+
+    let batch = &mut ctx.accounts.signal_batch;
+    batch.agent = ctx.accounts.agent_identity.key();
+    batch.reporter = ctx.accounts.signaler.key();
+    batch.root = root;
+    batch.leaf_count = leaf_count;
+    batch.scored_count = 0;
+    batch.submitted_at = Clock::get()?.unix_timestamp;
+    batch.scored = vec![0u8; (leaf_count as usize).div_ceil(8)];
+    batch.bump = ctx.bumps.signal_batch;
+
+    Ok(())
+}
+
+pub fn submit_compressed_score(
+    ctx: Context<SubmitCompressedScore>,
+    root: [u8; 32],
+    leaf: CompressedSignalLeaf,
+    proof: Vec<[u8; 32]>,
+    score: u16,
+    confidence_bps: u16,
+    score_hash: [u8; 32],
+) -> Result<()> {
+    let _ = root;
+
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    assert_role(
+        &ctx.accounts.protocol_config,
+        &ctx.accounts.role_assignment,
+        ctx.accounts.member_stake.as_deref(),
+        ctx.accounts.oracle.key(),
+        ROLE_ORACLE,
+    )?;
+    require!(
+        ctx.accounts.protocol_config.score_quorum <= 1,
+        ErrorCode::ScoreAggregationEnabled
+    );
+
+    validate_score_input(
+        &ctx.accounts.protocol_config,
+        score,
+        confidence_bps,
+        &score_hash,
+    )?;
+
+    let agent = ctx.accounts.agent_identity.key();
+    let batch = &mut ctx.accounts.signal_batch;
+    require!(
+        leaf.leaf_index < batch.leaf_count,
+        ErrorCode::InvalidMerkleProof
+    );
+    require!(
+        proof.len() <= MAX_MERKLE_DEPTH,
+        ErrorCode::InvalidMerkleProof
+    );
+    require!(
+        verify_proof(signal_leaf_hash(&agent, &leaf), &proof, &batch.root),
+        ErrorCode::InvalidMerkleProof
+    );
+    require!(!is_zero_hash(&leaf.trade_id_hash), ErrorCode::InvalidHash);
+    // Signals scored before the trade index existed are only known by their account.
+    require!(
+        ctx.accounts.trade_signal.data_is_empty(),
+        ErrorCode::SignalAlreadyExists
+    );
+    require!(!is_zero_hash(&leaf.result_hash), ErrorCode::InvalidHash);
+    validate_signal_category(leaf.category, ctx.accounts.category.as_deref())?;
+    require!(
+        !leaf.pnl_reported || (leaf.notional > 0 && leaf.holding_time >= 0),
+        ErrorCode::InvalidPnl
+    );
+
+    let byte = (leaf.leaf_index / 8) as usize;
+    let mask = 1u8 << (leaf.leaf_index % 8);
+    require!(batch.scored[byte] & mask == 0, ErrorCode::LeafAlreadyScored);
+    batch.scored[byte] |= mask;
+    batch.scored_count = batch
+        .scored_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require_signal_fresh(&ctx.accounts.protocol_config, batch.submitted_at, now)?;
+
+    // Compressed leaves have no account of their own; the scoring helpers run against
+    // a transient signal so the reputation math matches `submit_score`.
+    let mut signal = TradeSignal {
+        agent,
+        trade_id_hash: leaf.trade_id_hash,
+        result_hash: leaf.result_hash,
+        context_hash: leaf.context_hash,
+        reporter: batch.reporter,
+        submitted_at: batch.submitted_at,
+        risk_flags: leaf.risk_flags,
+        category: leaf.category,
+        score_submitted: true,
+        score_hash,
+        score,
+        confidence_bps,
+        oracle: ctx.accounts.oracle.key(),
+        score_submitted_at: now,
+        challenge_status: CHALLENGE_NONE,
+        pnl_reported: leaf.pnl_reported,
+        notional: leaf.notional,
+        realized_pnl: leaf.realized_pnl,
+        holding_time: leaf.holding_time,
+        ..TradeSignal::default()
+    };
+
+    apply_scored_signal(
+        ScoredSignalAccounts {
+            config: &ctx.accounts.protocol_config,
+            agent,
+            verification_record: &mut ctx.accounts.verification_record,
+            verification_bump: ctx.bumps.verification_record,
+            reputation_state: &mut ctx.accounts.reputation_state,
+            reputation_bump: ctx.bumps.reputation_state,
+            epoch_snapshot: ctx.accounts.epoch_snapshot.as_deref_mut(),
+            epoch_snapshot_bump: ctx.bumps.epoch_snapshot,
+            category: ctx.accounts.category.as_deref(),
+            category_reputation_state: ctx.accounts.category_reputation_state.as_mut(),
+            category_epoch_snapshot: ctx.accounts.category_epoch_snapshot.as_deref(),
+            pnl_stats: &mut ctx.accounts.pnl_stats,
+            pnl_stats_bump: ctx.bumps.pnl_stats,
+            scored_trades: &ctx.accounts.scored_trades,
+            scored_trades_bump: ctx.bumps.scored_trades,
+            risk_flag_table: &ctx.accounts.risk_flag_table,
+            leaderboard: &ctx.accounts.leaderboard,
+            category_leaderboard: &ctx.accounts.category_leaderboard,
+            payer: &ctx.accounts.oracle,
+            system_program: &ctx.accounts.system_program,
+        },
+        &mut signal,
+        now,
+    )
+}
+
+/// keccak256(0x00 || agent_identity || leaf_index u32 LE || trade_id_hash || result_hash
+/// || context_hash || risk_flags u8 || category u16 LE || pnl_reported u8 || notional u64 LE
+/// || realized_pnl i64 LE || holding_time i64 LE)
+fn signal_leaf_hash(agent: &Pubkey, leaf: &CompressedSignalLeaf) -> [u8; 32] {
+    keccak::hashv(&[
+        &[LEAF_PREFIX],
+        agent.as_ref(),
+        &leaf.leaf_index.to_le_bytes(),
+        &leaf.trade_id_hash,
+        &leaf.result_hash,
+        &leaf.context_hash,
+        &[leaf.risk_flags],
+        &leaf.category.to_le_bytes(),
+        &[leaf.pnl_reported as u8],
+        &leaf.notional.to_le_bytes(),
+        &leaf.realized_pnl.to_le_bytes(),
+        &leaf.holding_time.to_le_bytes(),
+    ])
+    .to_bytes()
+}
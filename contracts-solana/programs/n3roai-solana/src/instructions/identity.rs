@@ -21,7 +21,7 @@ pub fn register_agent(
     metadata_hash: [u8; 32],
 ) -> Result<()> {
     require!(agent_wallet != Pubkey::default(), ErrorCode::InvalidAddress);
-    require!(uri.as_bytes().len() <= MAX_URI_LEN, ErrorCode::UriTooLong);
+    require!(uri.len() <= MAX_URI_LEN, ErrorCode::UriTooLong);
 
     let now = Clock::get()?.unix_timestamp;
     let registry = &mut ctx.accounts.identity_registry;
@@ -56,7 +56,7 @@ pub fn set_agent_wallet(ctx: Context<SetAgentWallet>, new_wallet: Pubkey) -> Res
 }
 
 pub fn set_agent_uri(ctx: Context<SetAgentUri>, uri: String) -> Result<()> {
-    require!(uri.as_bytes().len() <= MAX_URI_LEN, ErrorCode::UriTooLong);
+    require!(uri.len() <= MAX_URI_LEN, ErrorCode::UriTooLong);
 
     let agent = &mut ctx.accounts.agent_identity;
     agent.uri = uri;
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::CHALLENGE_LEGACY;
+use crate::contexts::{MigrateAccount, MigrateDistributionReceipt, MigrateProtocolConfig};
+use crate::errors::ErrorCode;
+use crate::helpers::grow_account;
+use crate::math::SCORE_STAT_SCALE;
+use crate::state::{
+    DistributionReceipt, ProtocolConfig, ReputationState, RevenueSplitConfig, TradeSignal,
+    VerificationRecord,
+};
+
+// Each migration grows one account from its `LEGACY_SPACE` layout to `SPACE`. Fields
+// declared after the legacy ones were appended since the initial release and start at zero
+// unless set below; migrating a current account is a no-op.
+
+pub fn migrate_protocol_config(ctx: Context<MigrateProtocolConfig>) -> Result<()> {
+    let info = ctx.accounts.protocol_config.to_account_info();
+    // The admin key is the first field in every layout.
+    let admin = {
+        let data = info.try_borrow_data()?;
+        require!(data.len() >= 40, ErrorCode::InvalidMigrationAccount);
+        Pubkey::try_from(&data[8..40]).map_err(|_| ErrorCode::InvalidMigrationAccount)?
+    };
+    require!(admin == ctx.accounts.admin.key(), ErrorCode::Unauthorized);
+
+    grow_account::<ProtocolConfig>(
+        &info,
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.system_program,
+        ProtocolConfig::SPACE,
+    )?;
+    Ok(())
+}
+
+/// Pre-upgrade tracks are global tracks. The sequence restarts at the score count, and the
+/// score mean is seeded from the lifetime confidence-weighted mean with zero variance; the
+/// histogram only counts scores applied after the upgrade.
+pub fn migrate_reputation_state(ctx: Context<MigrateAccount>) -> Result<()> {
+    let info = ctx.accounts.account.to_account_info();
+    if !grow_account::<ReputationState>(
+        &info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program,
+        ReputationState::SPACE,
+    )? {
+        return Ok(());
+    }
+
+    let mut track = ReputationState::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    track.score_sequence = track.score_count as u64;
+    track.window_start_sequence = track.score_sequence;
+    if track.total_weight > 0 {
+        track.score_mean = (track.total_weighted_score * SCORE_STAT_SCALE as u128
+            / track.total_weight as u128) as u64;
+    }
+    track.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+/// A signal scored before the upgrade has no record of what was applied to the track, so it
+/// is marked legacy and can no longer be challenged or corrected.
+pub fn migrate_trade_signal(ctx: Context<MigrateAccount>) -> Result<()> {
+    let info = ctx.accounts.account.to_account_info();
+    if !grow_account::<TradeSignal>(
+        &info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program,
+        TradeSignal::SPACE,
+    )? {
+        return Ok(());
+    }
+
+    let mut signal = TradeSignal::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    if signal.score_submitted {
+        signal.applied_score = signal.score;
+        signal.applied_confidence_bps = signal.confidence_bps;
+        signal.challenge_status = CHALLENGE_LEGACY;
+    }
+    signal.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+pub fn migrate_revenue_split(ctx: Context<MigrateAccount>) -> Result<()> {
+    grow_account::<RevenueSplitConfig>(
+        &ctx.accounts.account.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program,
+        RevenueSplitConfig::SPACE,
+    )?;
+    Ok(())
+}
+
+pub fn migrate_verification_record(ctx: Context<MigrateAccount>) -> Result<()> {
+    grow_account::<VerificationRecord>(
+        &ctx.accounts.account.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program,
+        VerificationRecord::SPACE,
+    )?;
+    Ok(())
+}
+
+/// Pre-upgrade receipts all settled in the configured settlement mint.
+pub fn migrate_distribution_receipt(ctx: Context<MigrateDistributionReceipt>) -> Result<()> {
+    let info = ctx.accounts.distribution_receipt.to_account_info();
+    if !grow_account::<DistributionReceipt>(
+        &info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program,
+        DistributionReceipt::SPACE,
+    )? {
+        return Ok(());
+    }
+
+    let mut receipt = DistributionReceipt::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    receipt.mint = ctx.accounts.protocol_config.settlement_mint;
+    receipt.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
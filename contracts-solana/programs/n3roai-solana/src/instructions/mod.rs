@@ -1,5 +1,16 @@
 pub mod admin;
+pub mod batch;
+pub mod challenge;
+pub mod claims;
+pub mod commitment;
+pub mod compressed;
 pub mod identity;
+pub mod migration;
+pub mod pnl;
+pub mod referral;
 pub mod reputation;
+pub mod reserve;
 pub mod revenue;
+pub mod staking;
 pub mod verification;
+pub mod vesting;
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::ROLE_SIGNALER;
+use crate::contexts::ReportTradePnl;
+use crate::errors::ErrorCode;
+use crate::helpers::{assert_role, require_not_paused};
+
+/// Attaches realized PnL to a signal. It must land before the signal is scored, because scoring
+/// is what books the trade into `PnlStats`; a signal scored without a report still counts as a
+/// trade, so leaving losing trades unreported lowers the win rate instead of hiding them.
+pub fn report_trade_pnl(
+    ctx: Context<ReportTradePnl>,
+    trade_id_hash: [u8; 32],
+    notional: u64,
+    realized_pnl: i64,
+    holding_time: i64,
+) -> Result<()> {
+    let _ = trade_id_hash;
+
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    assert_role(
+        &ctx.accounts.protocol_config,
+        &ctx.accounts.role_assignment,
+        ctx.accounts.member_stake.as_deref(),
+        ctx.accounts.signaler.key(),
+        ROLE_SIGNALER,
+    )?;
+    require!(notional > 0 && holding_time >= 0, ErrorCode::InvalidPnl);
+
+    let signal = &mut ctx.accounts.trade_signal;
+    require!(!signal.pnl_reported, ErrorCode::PnlAlreadyReported);
+    require!(!signal.score_submitted, ErrorCode::ScoreAlreadySubmitted);
+    signal.pnl_reported = true;
+    signal.notional = notional;
+    signal.realized_pnl = realized_pnl;
+    signal.holding_time = holding_time;
+
+    Ok(())
+}
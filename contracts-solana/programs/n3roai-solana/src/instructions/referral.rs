@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_REFERRAL_CHAIN, REFERRAL_SEED};
+use crate::contexts::{RegisterReferral, SetReferralParent};
+use crate::errors::ErrorCode;
+use crate::helpers::require_not_paused;
+use crate::state::ReferralAccount;
+
+/// Registers the signer in the referral graph under `parent`, or as a root when `parent` is the
+/// default key. The parent must already be registered, so a new node cannot close a cycle.
+/// Remaining accounts carry each ancestor's referral account above the parent, up to a root.
+pub fn register_referral<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RegisterReferral<'info>>,
+    parent: Pubkey,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+
+    let referrer = ctx.accounts.referrer.key();
+    let mut accounts = ctx.remaining_accounts.iter();
+    if parent != Pubkey::default() {
+        require!(parent != referrer, ErrorCode::ReferralCycle);
+        let parent_referral = ctx
+            .accounts
+            .parent_referral
+            .as_ref()
+            .ok_or(ErrorCode::InvalidReferralAccount)?;
+        walk_upline(
+            &mut accounts,
+            parent_referral.parent,
+            1,
+            referrer,
+            ctx.program_id,
+        )?;
+    }
+    require!(accounts.next().is_none(), ErrorCode::InvalidReferralAccount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let node = &mut ctx.accounts.referral_account;
+    node.referrer = referrer;
+    node.parent = parent;
+    node.registered_at = now;
+    node.updated_at = now;
+    node.bump = ctx.bumps.referral_account;
+
+    Ok(())
+}
+
+/// Moves a referrer under a new parent. Remaining accounts carry the new parent's referral
+/// account and then each ancestor's, up to a root; none of them may be the moved referrer.
+pub fn set_referral_parent<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetReferralParent<'info>>,
+    parent: Pubkey,
+) -> Result<()> {
+    let referrer = ctx.accounts.referral_account.referrer;
+    let mut accounts = ctx.remaining_accounts.iter();
+    walk_upline(&mut accounts, parent, 0, referrer, ctx.program_id)?;
+    require!(accounts.next().is_none(), ErrorCode::InvalidReferralAccount);
+
+    let node = &mut ctx.accounts.referral_account;
+    node.parent = parent;
+    node.updated_at = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+/// Follows the upline from `current` to a root, taking one referral account per ancestor.
+/// `depth` counts the ancestors already walked; the chain may not pass `MAX_REFERRAL_CHAIN`
+/// ancestors or reach `referrer`.
+fn walk_upline<'info>(
+    accounts: &mut impl Iterator<Item = &'info AccountInfo<'info>>,
+    mut current: Pubkey,
+    mut depth: usize,
+    referrer: Pubkey,
+    program_id: &Pubkey,
+) -> Result<()> {
+    while current != Pubkey::default() {
+        require!(current != referrer, ErrorCode::ReferralCycle);
+        require!(depth < MAX_REFERRAL_CHAIN, ErrorCode::ReferralChainTooLong);
+
+        let info = accounts.next().ok_or(ErrorCode::InvalidReferralAccount)?;
+        let (expected, _) =
+            Pubkey::find_program_address(&[REFERRAL_SEED, current.as_ref()], program_id);
+        require!(info.key() == expected, ErrorCode::InvalidReferralAccount);
+        current = Account::<ReferralAccount>::try_from(info)?.parent;
+        depth += 1;
+    }
+    Ok(())
+}
@@ -1,9 +1,24 @@
 use anchor_lang::prelude::*;
 
-use crate::constants::{MAX_BPS, ROLE_ORACLE, ROLE_SIGNALER};
-use crate::contexts::{SubmitScore, SubmitSignal};
+use crate::constants::{
+    CHALLENGE_LEGACY, CHALLENGE_NONE, CHALLENGE_OPEN, CHALLENGE_UPHELD, MAX_BALLOT_VOTES,
+    MIN_VOTE_CONFIDENCE_BPS, ROLE_ORACLE, ROLE_SIGNALER,
+};
+use crate::contexts::{
+    CloseReputationEpoch, CorrectScore, FinalizeScore, GetReputationScore,
+    InitializeCategoryReputation, MigrateReputationTrack, SubmitScore, SubmitScoreVote,
+    SubmitSignal,
+};
 use crate::errors::ErrorCode;
-use crate::helpers::{assert_role, is_verified, is_zero_hash, require_not_paused};
+use crate::helpers::{
+    apply_category_correction, apply_correction, apply_scored_signal, assert_role, close_epoch,
+    current_rolling_score, is_verified_account, is_zero_hash, load_risk_flag_table, migrate_track,
+    require_not_paused, require_signal_fresh, reset_signal_score, risk_adjusted_score,
+    update_leaderboard, update_leaderboards, validate_score_input, validate_signal_category,
+    ScoredSignalAccounts,
+};
+use crate::math::weighted_median_index;
+use crate::state::BallotVote;
 
 pub fn submit_signal(
     ctx: Context<SubmitSignal>,
@@ -11,16 +26,25 @@ pub fn submit_signal(
     result_hash: [u8; 32],
     context_hash: [u8; 32],
     risk_flags: u8,
+    category: u16,
 ) -> Result<()> {
     require_not_paused(&ctx.accounts.protocol_config)?;
     assert_role(
+        &ctx.accounts.protocol_config,
         &ctx.accounts.role_assignment,
+        ctx.accounts.member_stake.as_deref(),
         ctx.accounts.signaler.key(),
         ROLE_SIGNALER,
     )?;
 
+    require!(
+        !ctx.accounts.protocol_config.require_signal_commit,
+        ErrorCode::SignalCommitRequired
+    );
+
     require!(!is_zero_hash(&trade_id_hash), ErrorCode::InvalidHash);
     require!(!is_zero_hash(&result_hash), ErrorCode::InvalidHash);
+    validate_signal_category(category, ctx.accounts.category.as_deref())?;
 
     let signal = &mut ctx.accounts.trade_signal;
     signal.agent = ctx.accounts.agent_identity.key();
@@ -29,13 +53,10 @@ pub fn submit_signal(
     signal.context_hash = context_hash;
     signal.reporter = ctx.accounts.signaler.key();
     signal.submitted_at = Clock::get()?.unix_timestamp;
+    signal.revealed_at = 0;
     signal.risk_flags = risk_flags;
-    signal.score_submitted = false;
-    signal.score_hash = [0u8; 32];
-    signal.score = 0;
-    signal.confidence_bps = 0;
-    signal.oracle = Pubkey::default();
-    signal.score_submitted_at = 0;
+    signal.category = category;
+    reset_signal_score(signal);
     signal.bump = ctx.bumps.trade_signal;
 
     Ok(())
@@ -51,105 +72,377 @@ pub fn submit_score(
     let _ = trade_id_hash;
 
     require_not_paused(&ctx.accounts.protocol_config)?;
-    assert_role(&ctx.accounts.role_assignment, ctx.accounts.oracle.key(), ROLE_ORACLE)?;
+    assert_role(
+        &ctx.accounts.protocol_config,
+        &ctx.accounts.role_assignment,
+        ctx.accounts.member_stake.as_deref(),
+        ctx.accounts.oracle.key(),
+        ROLE_ORACLE,
+    )?;
+    require!(
+        ctx.accounts.protocol_config.score_quorum <= 1,
+        ErrorCode::ScoreAggregationEnabled
+    );
 
-    require!(score <= MAX_BPS, ErrorCode::InvalidScore);
-    require!(confidence_bps <= MAX_BPS, ErrorCode::InvalidConfidence);
+    validate_score_input(
+        &ctx.accounts.protocol_config,
+        score,
+        confidence_bps,
+        &score_hash,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
     require!(
-        confidence_bps >= ctx.accounts.protocol_config.min_confidence_bps,
+        !ctx.accounts.trade_signal.score_submitted,
+        ErrorCode::ScoreAlreadySubmitted
+    );
+    require_signal_fresh(
+        &ctx.accounts.protocol_config,
+        ctx.accounts.trade_signal.submitted_at,
+        now,
+    )?;
+
+    let signal = &mut ctx.accounts.trade_signal;
+    signal.score_submitted = true;
+    signal.score_hash = score_hash;
+    signal.score = score;
+    signal.confidence_bps = confidence_bps;
+    signal.oracle = ctx.accounts.oracle.key();
+    signal.score_submitted_at = now;
+    signal.challenge_status = CHALLENGE_NONE;
+
+    apply_scored_signal(
+        ScoredSignalAccounts {
+            config: &ctx.accounts.protocol_config,
+            agent: ctx.accounts.agent_identity.key(),
+            verification_record: &mut ctx.accounts.verification_record,
+            verification_bump: ctx.bumps.verification_record,
+            reputation_state: &mut ctx.accounts.reputation_state,
+            reputation_bump: ctx.bumps.reputation_state,
+            epoch_snapshot: ctx.accounts.epoch_snapshot.as_deref_mut(),
+            epoch_snapshot_bump: ctx.bumps.epoch_snapshot,
+            category: ctx.accounts.category.as_deref(),
+            category_reputation_state: ctx.accounts.category_reputation_state.as_mut(),
+            category_epoch_snapshot: ctx.accounts.category_epoch_snapshot.as_deref(),
+            pnl_stats: &mut ctx.accounts.pnl_stats,
+            pnl_stats_bump: ctx.bumps.pnl_stats,
+            scored_trades: &ctx.accounts.scored_trades,
+            scored_trades_bump: ctx.bumps.scored_trades,
+            risk_flag_table: &ctx.accounts.risk_flag_table,
+            leaderboard: &ctx.accounts.leaderboard,
+            category_leaderboard: &ctx.accounts.category_leaderboard,
+            payer: &ctx.accounts.oracle,
+            system_program: &ctx.accounts.system_program,
+        },
+        signal,
+        now,
+    )
+}
+
+pub fn submit_score_vote(
+    ctx: Context<SubmitScoreVote>,
+    trade_id_hash: [u8; 32],
+    score: u16,
+    confidence_bps: u16,
+    score_hash: [u8; 32],
+) -> Result<()> {
+    let _ = trade_id_hash;
+
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    assert_role(
+        &ctx.accounts.protocol_config,
+        &ctx.accounts.role_assignment,
+        ctx.accounts.member_stake.as_deref(),
+        ctx.accounts.oracle.key(),
+        ROLE_ORACLE,
+    )?;
+    require!(
+        ctx.accounts.protocol_config.score_quorum > 1,
+        ErrorCode::ScoreAggregationDisabled
+    );
+
+    validate_score_input(
+        &ctx.accounts.protocol_config,
+        score,
+        confidence_bps,
+        &score_hash,
+    )?;
+    require!(
+        confidence_bps >= MIN_VOTE_CONFIDENCE_BPS,
         ErrorCode::InvalidConfidence
     );
-    require!(!is_zero_hash(&score_hash), ErrorCode::InvalidHash);
 
     let now = Clock::get()?.unix_timestamp;
-    let submitted_at = ctx.accounts.trade_signal.submitted_at;
     require!(
         !ctx.accounts.trade_signal.score_submitted,
         ErrorCode::ScoreAlreadySubmitted
     );
+    require_signal_fresh(
+        &ctx.accounts.protocol_config,
+        ctx.accounts.trade_signal.submitted_at,
+        now,
+    )?;
 
-    if ctx.accounts.protocol_config.max_signal_age > 0 {
-        let elapsed = now
-            .checked_sub(submitted_at)
-            .ok_or(ErrorCode::MathOverflow)?;
-        require!(
-            elapsed <= ctx.accounts.protocol_config.max_signal_age,
-            ErrorCode::SignalTooOld
-        );
+    let window = ctx.accounts.protocol_config.score_ballot_window;
+    let ballot = &mut ctx.accounts.score_ballot;
+    if ballot.trade_signal == Pubkey::default() {
+        ballot.trade_signal = ctx.accounts.trade_signal.key();
+        ballot.agent = ctx.accounts.agent_identity.key();
+        ballot.opened_by = ctx.accounts.oracle.key();
+        ballot.opened_at = now;
+        ballot.finalized = false;
+        ballot.bump = ctx.bumps.score_ballot;
     }
 
-    if ctx.accounts.verification_record.agent == Pubkey::default() {
-        ctx.accounts.verification_record.agent = ctx.accounts.agent_identity.key();
-        ctx.accounts.verification_record.bump = ctx.bumps.verification_record;
+    require!(!ballot.finalized, ErrorCode::ScoreAlreadySubmitted);
+    if window > 0 {
+        let deadline = ballot
+            .opened_at
+            .checked_add(window)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(now < deadline, ErrorCode::BallotClosed);
     }
 
-    if ctx.accounts.protocol_config.require_verified_for_score {
-        require!(
-            is_verified(&ctx.accounts.verification_record, now),
-            ErrorCode::VerificationRequired
-        );
-    }
+    let oracle = ctx.accounts.oracle.key();
+    require!(
+        ballot.votes.iter().all(|vote| vote.oracle != oracle),
+        ErrorCode::DuplicateScoreVote
+    );
+    require!(ballot.votes.len() < MAX_BALLOT_VOTES, ErrorCode::BallotFull);
+
+    ballot.votes.push(BallotVote {
+        oracle,
+        score,
+        confidence_bps,
+        score_hash,
+        submitted_at: now,
+    });
+
+    Ok(())
+}
+
+pub fn finalize_score(ctx: Context<FinalizeScore>, trade_id_hash: [u8; 32]) -> Result<()> {
+    let _ = trade_id_hash;
+
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    assert_role(
+        &ctx.accounts.protocol_config,
+        &ctx.accounts.role_assignment,
+        ctx.accounts.member_stake.as_deref(),
+        ctx.accounts.oracle.key(),
+        ROLE_ORACLE,
+    )?;
+    require!(
+        !ctx.accounts.trade_signal.score_submitted,
+        ErrorCode::ScoreAlreadySubmitted
+    );
+    require!(
+        !ctx.accounts.score_ballot.finalized,
+        ErrorCode::ScoreAlreadySubmitted
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let config = &ctx.accounts.protocol_config;
+    let ballot = &ctx.accounts.score_ballot;
+    let vote_count = ballot.votes.len();
+    let quorum = config.score_quorum as usize;
+
+    // Past the deadline a strict majority of the quorum is still required, so a
+    // single oracle can never settle a ballot on its own.
+    let deadline_passed = config.score_ballot_window > 0
+        && now
+            >= ballot
+                .opened_at
+                .checked_add(config.score_ballot_window)
+                .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        vote_count > 0 && (vote_count >= quorum || (deadline_passed && vote_count * 2 > quorum)),
+        ErrorCode::BallotNotReady
+    );
+
+    let mut votes = ballot.votes.clone();
+    votes.sort_by(|a, b| {
+        a.score
+            .cmp(&b.score)
+            .then_with(|| a.oracle.to_bytes().cmp(&b.oracle.to_bytes()))
+    });
+
+    let confidences: Vec<u16> = votes.iter().map(|vote| vote.confidence_bps).collect();
+    let median = votes[weighted_median_index(&confidences).ok_or(ErrorCode::BallotNotReady)?];
+    let confidence_bps = (votes
+        .iter()
+        .map(|vote| vote.confidence_bps as u32)
+        .sum::<u32>()
+        / vote_count as u32) as u16;
+
+    ctx.accounts.score_ballot.finalized = true;
 
     let signal = &mut ctx.accounts.trade_signal;
     signal.score_submitted = true;
-    signal.score_hash = score_hash;
-    signal.score = score;
+    signal.score_hash = median.score_hash;
+    signal.score = median.score;
     signal.confidence_bps = confidence_bps;
-    signal.oracle = ctx.accounts.oracle.key();
+    signal.oracle = median.oracle;
     signal.score_submitted_at = now;
+    signal.challenge_status = CHALLENGE_NONE;
+
+    apply_scored_signal(
+        ScoredSignalAccounts {
+            config: &ctx.accounts.protocol_config,
+            agent: ctx.accounts.agent_identity.key(),
+            verification_record: &mut ctx.accounts.verification_record,
+            verification_bump: ctx.bumps.verification_record,
+            reputation_state: &mut ctx.accounts.reputation_state,
+            reputation_bump: ctx.bumps.reputation_state,
+            epoch_snapshot: ctx.accounts.epoch_snapshot.as_deref_mut(),
+            epoch_snapshot_bump: ctx.bumps.epoch_snapshot,
+            category: ctx.accounts.category.as_deref(),
+            category_reputation_state: ctx.accounts.category_reputation_state.as_mut(),
+            category_epoch_snapshot: ctx.accounts.category_epoch_snapshot.as_deref(),
+            pnl_stats: &mut ctx.accounts.pnl_stats,
+            pnl_stats_bump: ctx.bumps.pnl_stats,
+            scored_trades: &ctx.accounts.scored_trades,
+            scored_trades_bump: ctx.bumps.scored_trades,
+            risk_flag_table: &ctx.accounts.risk_flag_table,
+            leaderboard: &ctx.accounts.leaderboard,
+            category_leaderboard: &ctx.accounts.category_leaderboard,
+            payer: &ctx.accounts.oracle,
+            system_program: &ctx.accounts.system_program,
+        },
+        signal,
+        now,
+    )
+}
+
+pub fn get_reputation_score(ctx: Context<GetReputationScore>) -> Result<u16> {
+    let now = Clock::get()?.unix_timestamp;
+    Ok(current_rolling_score(
+        &ctx.accounts.reputation_state,
+        ctx.accounts.protocol_config.score_half_life,
+        now,
+    ))
+}
+
+pub fn initialize_category_reputation(
+    ctx: Context<InitializeCategoryReputation>,
+    category_id: u16,
+) -> Result<()> {
+    require!(category_id != 0, ErrorCode::InvalidCategory);
 
     let reputation = &mut ctx.accounts.reputation_state;
-    if reputation.agent == Pubkey::default() {
-        reputation.agent = ctx.accounts.agent_identity.key();
-        reputation.bump = ctx.bumps.reputation_state;
-    }
+    reputation.agent = ctx.accounts.agent_identity.key();
+    reputation.category = category_id;
+    reputation.bump = ctx.bumps.reputation_state;
 
-    let weighted = (score as u128)
-        .checked_mul(confidence_bps as u128)
-        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
 
-    reputation.total_weighted_score = reputation
-        .total_weighted_score
-        .checked_add(weighted)
-        .ok_or(ErrorCode::MathOverflow)?;
+pub fn close_reputation_epoch(ctx: Context<CloseReputationEpoch>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let reputation_key = ctx.accounts.reputation_state.key();
 
-    reputation.total_weight = reputation
-        .total_weight
-        .checked_add(confidence_bps as u64)
-        .ok_or(ErrorCode::MathOverflow)?;
+    close_epoch(
+        &mut ctx.accounts.reputation_state,
+        reputation_key,
+        &mut ctx.accounts.epoch_snapshot,
+        ctx.bumps.epoch_snapshot,
+        &ctx.accounts.protocol_config,
+        now,
+    )
+}
 
-    let effective_score = ((score as u32)
-        .checked_mul(confidence_bps as u32)
-        .ok_or(ErrorCode::MathOverflow)?
-        / (MAX_BPS as u32)) as u16;
-
-    if reputation.rolling_score == 0 {
-        reputation.rolling_score = effective_score;
-    } else {
-        let alpha = ctx.accounts.protocol_config.score_alpha_bps as u32;
-        let inv_alpha = (MAX_BPS - ctx.accounts.protocol_config.score_alpha_bps) as u32;
-
-        let smoothed = (reputation.rolling_score as u32)
-            .checked_mul(inv_alpha)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_add(
-                (effective_score as u32)
-                    .checked_mul(alpha)
-                    .ok_or(ErrorCode::MathOverflow)?,
-            )
-            .ok_or(ErrorCode::MathOverflow)?
-            / (MAX_BPS as u32);
-
-        reputation.rolling_score = smoothed as u16;
-    }
+pub fn correct_score(
+    ctx: Context<CorrectScore>,
+    trade_id_hash: [u8; 32],
+    score: u16,
+    confidence_bps: u16,
+    score_hash: [u8; 32],
+    reason_hash: [u8; 32],
+) -> Result<()> {
+    let _ = trade_id_hash;
+
+    validate_score_input(
+        &ctx.accounts.protocol_config,
+        score,
+        confidence_bps,
+        &score_hash,
+    )?;
+    require!(!is_zero_hash(&reason_hash), ErrorCode::InvalidHash);
+
+    let signal = &ctx.accounts.trade_signal;
+    require!(signal.score_submitted, ErrorCode::ScoreNotCorrectable);
+    require!(
+        signal.challenge_status != CHALLENGE_OPEN,
+        ErrorCode::ScoreChallenged
+    );
+    require!(
+        signal.challenge_status != CHALLENGE_UPHELD && signal.challenge_status != CHALLENGE_LEGACY,
+        ErrorCode::ScoreNotCorrectable
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let risk_flag_table = load_risk_flag_table(&ctx.accounts.risk_flag_table)?;
+    let corrected = risk_adjusted_score(&risk_flag_table, signal.risk_flags, score, confidence_bps);
+
+    apply_correction(
+        &mut ctx.accounts.reputation_state,
+        signal,
+        (score, confidence_bps),
+        corrected,
+        &ctx.accounts.protocol_config,
+        now,
+    )?;
+    apply_category_correction(
+        ctx.accounts.category_reputation_state.as_deref_mut(),
+        signal,
+        ctx.accounts.category.as_deref(),
+        (score, confidence_bps),
+        corrected,
+        &ctx.accounts.protocol_config,
+        now,
+    )?;
 
-    reputation.last_score = score;
-    reputation.last_confidence_bps = confidence_bps;
-    reputation.score_count = reputation
-        .score_count
+    let signal = &mut ctx.accounts.trade_signal;
+    signal.previous_score = signal.score;
+    signal.previous_confidence_bps = signal.confidence_bps;
+    signal.score = score;
+    signal.confidence_bps = confidence_bps;
+    signal.score_hash = score_hash;
+    signal.applied_score = corrected.0;
+    signal.applied_confidence_bps = corrected.1;
+    signal.correction_count = signal
+        .correction_count
         .checked_add(1)
         .ok_or(ErrorCode::MathOverflow)?;
-    reputation.last_updated = now;
+    signal.corrected_by = ctx.accounts.authority.key();
+    signal.corrected_at = now;
+    signal.correction_reason_hash = reason_hash;
+
+    update_leaderboards(
+        &ctx.accounts.leaderboard,
+        &ctx.accounts.category_leaderboard,
+        &ctx.accounts.reputation_state,
+        ctx.accounts.category_reputation_state.as_deref(),
+        is_verified_account(&ctx.accounts.verification_record, now)?,
+        ctx.accounts.protocol_config.score_half_life,
+        now,
+    )?;
 
     Ok(())
 }
+
+pub fn migrate_reputation_track(ctx: Context<MigrateReputationTrack>) -> Result<()> {
+    migrate_track(
+        &mut ctx.accounts.reputation_state,
+        &ctx.accounts.protocol_config,
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    update_leaderboard(
+        &ctx.accounts.leaderboard,
+        &ctx.accounts.reputation_state,
+        is_verified_account(&ctx.accounts.verification_record, now)?,
+        ctx.accounts.protocol_config.score_half_life,
+        now,
+    )
+}
@@ -0,0 +1,195 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::{MAX_RESERVE_LOCKUP, RESERVE_SEED};
+use crate::contexts::{
+    CompensateFromReserve, OpenReserveVault, SetReserveLockup, SyncReserve, WithdrawReserve,
+};
+use crate::errors::ErrorCode;
+use crate::helpers::{
+    is_suspended, is_zero_hash, require_not_paused, settlement_mint_fee_config, sync_reserve,
+    VaultTransfer,
+};
+use crate::state::ReserveVault;
+
+/// Opens a reserve for an agent (scope is the agent identity, owned by the agent owner) or for a
+/// platform (scope is the owner's own key). Point a split's `reserve_vault` at the reserve PDA to
+/// route the reserve share into it.
+pub fn open_reserve_vault(
+    ctx: Context<OpenReserveVault>,
+    scope: Pubkey,
+    lockup_period: i64,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    require!(
+        lockup_period > 0 && lockup_period <= MAX_RESERVE_LOCKUP,
+        ErrorCode::InvalidReserveLockup
+    );
+    settlement_mint_fee_config(&ctx.accounts.mint.to_account_info())?;
+
+    let owner = ctx.accounts.owner.key();
+    match ctx.accounts.agent_identity.as_ref() {
+        Some(agent_identity) => {
+            require!(
+                scope == agent_identity.key(),
+                ErrorCode::InvalidReserveScope
+            );
+            require!(agent_identity.owner == owner, ErrorCode::Unauthorized);
+        }
+        None => require!(scope == owner, ErrorCode::InvalidReserveScope),
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let reserve = &mut ctx.accounts.reserve;
+    reserve.scope = scope;
+    reserve.owner = owner;
+    reserve.mint = ctx.accounts.mint.key();
+    reserve.token_account = ctx.accounts.reserve_token_account.key();
+    reserve.lockup_period = lockup_period;
+    reserve.tracked_balance = 0;
+    reserve.updated_at = now;
+    reserve.bump = ctx.bumps.reserve;
+
+    // Tokens sent to the reserve's token account before it was opened are locked like any inflow.
+    let balance = ctx.accounts.reserve_token_account.amount;
+    sync_reserve(reserve, balance, now)?;
+
+    Ok(())
+}
+
+/// Changes the lockup applied to future inflows. Tranches already booked keep their unlock time.
+pub fn set_reserve_lockup(ctx: Context<SetReserveLockup>, lockup_period: i64) -> Result<()> {
+    require!(
+        lockup_period > 0 && lockup_period <= MAX_RESERVE_LOCKUP,
+        ErrorCode::InvalidReserveLockup
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let balance = ctx.accounts.reserve_token_account.amount;
+    let reserve = &mut ctx.accounts.reserve;
+    sync_reserve(reserve, balance, now)?;
+    reserve.lockup_period = lockup_period;
+
+    Ok(())
+}
+
+/// Permissionless crank that books tokens reaching the reserve outside `distribute_settlement`,
+/// such as batch settlements and plain transfers.
+pub fn sync_reserve_balance(ctx: Context<SyncReserve>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let balance = ctx.accounts.reserve_token_account.amount;
+    sync_reserve(&mut ctx.accounts.reserve, balance, now)
+}
+
+pub fn withdraw_reserve(ctx: Context<WithdrawReserve>, amount: u64) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        !is_suspended(&ctx.accounts.verification_record)?,
+        ErrorCode::AgentSuspended
+    );
+    require!(
+        ctx.accounts.destination_token_account.mint == ctx.accounts.reserve.mint,
+        ErrorCode::InvalidTokenMint
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let balance = ctx.accounts.reserve_token_account.amount;
+    let reserve = &mut ctx.accounts.reserve;
+    sync_reserve(reserve, balance, now)?;
+    require!(amount <= reserve.unlocked, ErrorCode::ReserveLocked);
+
+    reserve.unlocked -= amount;
+    reserve.tracked_balance -= amount;
+    reserve.total_withdrawn = reserve
+        .total_withdrawn
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    send_from_reserve(
+        &ctx.accounts.reserve,
+        &ctx.accounts.reserve_token_account,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        &ctx.accounts.destination_token_account,
+        amount,
+    )
+}
+
+/// Pays users of the reserve's agent or platform for a loss. Locked tranches are drawn first,
+/// newest first, then unlocked funds.
+pub fn compensate_from_reserve(
+    ctx: Context<CompensateFromReserve>,
+    amount: u64,
+    reason_hash: [u8; 32],
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(!is_zero_hash(&reason_hash), ErrorCode::InvalidHash);
+    require!(
+        ctx.accounts.destination_token_account.mint == ctx.accounts.reserve.mint,
+        ErrorCode::InvalidTokenMint
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let balance = ctx.accounts.reserve_token_account.amount;
+    let reserve: &mut ReserveVault = &mut ctx.accounts.reserve;
+    sync_reserve(reserve, balance, now)?;
+    require!(
+        amount <= reserve.tracked_balance,
+        ErrorCode::InsufficientReserveBalance
+    );
+
+    let mut remaining = amount;
+    for bucket in [
+        &mut reserve.current.amount,
+        &mut reserve.previous.amount,
+        &mut reserve.unlocked,
+    ] {
+        let taken = remaining.min(*bucket);
+        *bucket -= taken;
+        remaining -= taken;
+    }
+    reserve.tracked_balance -= amount;
+    reserve.total_compensated = reserve
+        .total_compensated
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    reserve.last_compensation_hash = reason_hash;
+
+    send_from_reserve(
+        &ctx.accounts.reserve,
+        &ctx.accounts.reserve_token_account,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        &ctx.accounts.destination_token_account,
+        amount,
+    )
+}
+
+fn send_from_reserve<'info>(
+    reserve: &Account<'info, ReserveVault>,
+    reserve_token_account: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    amount: u64,
+) -> Result<()> {
+    let authority = reserve.to_account_info();
+    let signer_seeds: &[&[u8]] = &[
+        RESERVE_SEED,
+        reserve.scope.as_ref(),
+        reserve.mint.as_ref(),
+        &[reserve.bump],
+    ];
+    let transfer = VaultTransfer {
+        token_program,
+        vault: reserve_token_account,
+        mint,
+        authority: &authority,
+        signer_seeds,
+        fee_config: settlement_mint_fee_config(&mint.to_account_info())?,
+        epoch: Clock::get()?.epoch,
+    };
+    transfer.send(to, amount)?;
+    Ok(())
+}
@@ -1,9 +1,26 @@
 use anchor_lang::prelude::*;
-
-use crate::constants::{MAX_BPS, ROLE_REVENUE_OPERATOR, VAULT_AUTHORITY_SEED};
-use crate::contexts::{DistributeSettlement, SetSplit};
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::constants::{
+    MAX_BATCH_ENTRIES, MAX_VESTING_DURATION, RECEIPT_SEED, REPUTATION_SEED, ROLE_REVENUE_OPERATOR,
+    SETTLEMENT_VOLUME_SEED, SOL_VAULT_SEED, VAULT_AUTHORITY_SEED, VERIFICATION_SEED,
+};
+use crate::contexts::{
+    DistributeSettlement, DistributeSettlementBatch, DistributeSettlementSol, SetSplit,
+    SetSplitVesting,
+};
 use crate::errors::ErrorCode;
-use crate::helpers::{assert_role, is_zero_hash, require_not_paused, transfer_from_vault, validate_split};
+use crate::helpers::{
+    assert_role, batch_entry_error, claims_outstanding, create_pda_account, deposit_vesting,
+    is_zero_hash, next_batch_account, referral_payouts, require_direct_referral,
+    require_external_reserve, require_not_paused, require_unallocated, scheduled_fee_bps,
+    settlement_mint_fee_config, settlement_route, split_settlement, sync_reserve,
+    transfer_sol_from_vault, validate_split, VaultTransfer,
+};
+use crate::state::{
+    AgentIdentity, DistributionReceipt, ProtocolConfig, ReputationState, RevenueSplitConfig,
+    SettlementBatchEntry, SettlementVolume, SplitRecipient, VerificationRecord,
+};
 
 #[allow(clippy::too_many_arguments)]
 pub fn set_split(
@@ -23,6 +40,7 @@ pub fn set_split(
         referrer_bps,
         reserve_vault,
         reserve_bps,
+        &ctx.accounts.split_config.recipients,
     )?;
 
     let split = &mut ctx.accounts.split_config;
@@ -38,14 +56,46 @@ pub fn set_split(
     Ok(())
 }
 
-pub fn distribute_settlement(
-    ctx: Context<DistributeSettlement>,
+pub fn set_split_recipients(ctx: Context<SetSplit>, recipients: Vec<SplitRecipient>) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+
+    let split = &mut ctx.accounts.split_config;
+    validate_split(
+        split.platform,
+        split.platform_bps,
+        split.referrer,
+        split.referrer_bps,
+        split.reserve_vault,
+        split.reserve_bps,
+        &recipients,
+    )?;
+
+    split.agent = ctx.accounts.agent_identity.key();
+    split.recipients = recipients;
+    split.bump = ctx.bumps.split_config;
+
+    Ok(())
+}
+
+pub fn set_split_vesting(ctx: Context<SetSplitVesting>, vesting_duration: i64) -> Result<()> {
+    require!(
+        (0..=MAX_VESTING_DURATION).contains(&vesting_duration),
+        ErrorCode::InvalidVestingDuration
+    );
+    ctx.accounts.split_config.vesting_duration = vesting_duration;
+    Ok(())
+}
+
+pub fn distribute_settlement<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeSettlement<'info>>,
     reference: [u8; 32],
     amount: u64,
 ) -> Result<()> {
     require_not_paused(&ctx.accounts.protocol_config)?;
     assert_role(
+        &ctx.accounts.protocol_config,
         &ctx.accounts.role_assignment,
+        None,
         ctx.accounts.operator.key(),
         ROLE_REVENUE_OPERATOR,
     )?;
@@ -56,37 +106,67 @@ pub fn distribute_settlement(
     let protocol = &ctx.accounts.protocol_config;
     let split = &ctx.accounts.split_config;
 
-    require!(
-        ctx.accounts.settlement_vault.key() == protocol.settlement_vault,
-        ErrorCode::InvalidSettlementVault
-    );
-
-    require!(
-        ctx.accounts.protocol_treasury_token_account.key() == protocol.protocol_treasury_token_account,
-        ErrorCode::InvalidTreasuryAccount
-    );
+    let base_fee_bps = settlement_route(
+        protocol,
+        ctx.accounts.settlement_mint_config.as_deref(),
+        &ctx.accounts.settlement_vault,
+        &ctx.accounts.protocol_treasury_token_account,
+    )?;
+    require_unallocated(
+        &ctx.accounts.settlement_vault,
+        claims_outstanding(&ctx.accounts.claim_ledger)?,
+        amount,
+    )?;
 
-    if protocol.enforce_settlement_token {
-        require!(
-            ctx.accounts.settlement_vault.mint == protocol.settlement_mint,
-            ErrorCode::SettlementTokenMismatch
-        );
-    }
+    let now = Clock::get()?.unix_timestamp;
+    let protocol_fee_bps = scheduled_fee_bps(
+        &ctx.accounts.fee_schedule,
+        base_fee_bps,
+        protocol,
+        ctx.accounts.verification_record.as_deref(),
+        ctx.accounts.reputation_state.as_deref(),
+        &mut ctx.accounts.settlement_volume,
+        amount,
+        now,
+    )?;
+    ctx.accounts.settlement_volume.agent = ctx.accounts.agent_identity.key();
+    ctx.accounts.settlement_volume.mint = ctx.accounts.settlement_vault.mint;
+    ctx.accounts.settlement_volume.bump = ctx.bumps.settlement_volume;
 
     require!(
         ctx.accounts.settlement_vault.owner == ctx.accounts.vault_authority.key(),
         ErrorCode::InvalidTokenAccountOwner
     );
 
-    require!(
-        ctx.accounts.agent_token_account.owner == ctx.accounts.agent_identity.agent_wallet,
-        ErrorCode::InvalidTokenAccountOwner
-    );
-
-    require!(
-        ctx.accounts.agent_token_account.mint == ctx.accounts.settlement_vault.mint,
-        ErrorCode::InvalidTokenMint
-    );
+    // With a vesting policy the agent share is escrowed instead of paid to the agent's wallet.
+    let vesting_duration = split.vesting_duration;
+    let agent_destination = if vesting_duration > 0 {
+        let escrow = ctx
+            .accounts
+            .vesting_escrow
+            .as_ref()
+            .ok_or(ErrorCode::VestingEscrowRequired)?;
+        let vesting_vault = ctx
+            .accounts
+            .vesting_vault
+            .as_ref()
+            .ok_or(ErrorCode::VestingEscrowRequired)?;
+        require!(
+            vesting_vault.key() == escrow.vault,
+            ErrorCode::VestingEscrowRequired
+        );
+        vesting_vault
+    } else {
+        require!(
+            ctx.accounts.agent_token_account.owner == ctx.accounts.agent_identity.agent_wallet,
+            ErrorCode::InvalidTokenAccountOwner
+        );
+        require!(
+            ctx.accounts.agent_token_account.mint == ctx.accounts.settlement_vault.mint,
+            ErrorCode::InvalidTokenMint
+        );
+        &ctx.accounts.agent_token_account
+    };
 
     if split.platform_bps > 0 {
         require!(
@@ -121,105 +201,685 @@ pub fn distribute_settlement(
         );
     }
 
+    // A governed reserve books the reserve share as a locked inflow as soon as it lands.
+    if let Some(reserve) = ctx.accounts.reserve.as_ref() {
+        require!(
+            reserve.key() == split.reserve_vault
+                && reserve.token_account == ctx.accounts.reserve_token_account.key(),
+            ErrorCode::InvalidAddress
+        );
+    }
+
     require!(
         ctx.accounts.protocol_treasury_token_account.mint == ctx.accounts.settlement_vault.mint,
         ErrorCode::InvalidTokenMint
     );
 
-    let total_bps = (split.platform_bps as u32)
-        .checked_add(split.referrer_bps as u32)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_add(split.reserve_bps as u32)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_add(protocol.protocol_fee_bps as u32)
-        .ok_or(ErrorCode::MathOverflow)?;
+    // Extra recipients' token accounts follow in `remaining_accounts`, in split order, then the
+    // referral upline accounts.
+    require!(
+        ctx.remaining_accounts.len() >= split.recipients.len(),
+        ErrorCode::InvalidSplitRecipients
+    );
+    let (recipient_infos, referral_infos) = ctx.remaining_accounts.split_at(split.recipients.len());
+    let mut recipient_accounts = Vec::with_capacity(split.recipients.len());
+    for (entry, info) in split.recipients.iter().zip(recipient_infos.iter()) {
+        require!(info.is_writable, ErrorCode::InvalidSplitRecipients);
+        let token_account = InterfaceAccount::<TokenAccount>::try_from(info)?;
+        require!(
+            token_account.owner == entry.recipient,
+            ErrorCode::InvalidTokenAccountOwner
+        );
+        require!(
+            token_account.mint == ctx.accounts.settlement_vault.mint,
+            ErrorCode::InvalidTokenMint
+        );
+        recipient_accounts.push(token_account);
+    }
 
-    require!(total_bps <= MAX_BPS as u32, ErrorCode::InvalidBps);
-
-    let platform_amount = ((amount as u128)
-        .checked_mul(split.platform_bps as u128)
-        .ok_or(ErrorCode::MathOverflow)?
-        / (MAX_BPS as u128)) as u64;
-
-    let referrer_amount = ((amount as u128)
-        .checked_mul(split.referrer_bps as u128)
-        .ok_or(ErrorCode::MathOverflow)?
-        / (MAX_BPS as u128)) as u64;
-
-    let reserve_amount = ((amount as u128)
-        .checked_mul(split.reserve_bps as u128)
-        .ok_or(ErrorCode::MathOverflow)?
-        / (MAX_BPS as u128)) as u64;
-
-    let protocol_amount = ((amount as u128)
-        .checked_mul(protocol.protocol_fee_bps as u128)
-        .ok_or(ErrorCode::MathOverflow)?
-        / (MAX_BPS as u128)) as u64;
-
-    let agent_amount = amount
-        .checked_sub(platform_amount)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_sub(referrer_amount)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_sub(reserve_amount)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_sub(protocol_amount)
+    let shares = split_settlement(amount, split, protocol_fee_bps)?;
+
+    // The upline is paid out of the referrer share; the direct referrer keeps the rest.
+    let upline = if split.referrer_bps > 0 {
+        referral_payouts(
+            &ctx.accounts.referral_program,
+            split.referrer,
+            shares.referrer,
+            ctx.accounts.settlement_vault.mint,
+            referral_infos,
+        )?
+    } else {
+        require!(referral_infos.is_empty(), ErrorCode::InvalidReferralAccount);
+        Vec::new()
+    };
+    let upline_total = upline
+        .iter()
+        .try_fold(0u64, |total, (_, share)| total.checked_add(*share))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let direct_referrer_share = shares
+        .referrer
+        .checked_sub(upline_total)
         .ok_or(ErrorCode::MathOverflow)?;
 
     let signer_seeds: &[&[u8]] = &[VAULT_AUTHORITY_SEED, &[protocol.vault_authority_bump]];
+    let transfer = VaultTransfer {
+        token_program: &ctx.accounts.token_program,
+        vault: &ctx.accounts.settlement_vault,
+        mint: &ctx.accounts.settlement_mint,
+        authority: &ctx.accounts.vault_authority,
+        signer_seeds,
+        fee_config: settlement_mint_fee_config(&ctx.accounts.settlement_mint.to_account_info())?,
+        epoch: Clock::get()?.epoch,
+    };
+
+    // Each share is sent gross; the mint withholds its transfer fee from what the
+    // destination receives, and the total withheld is recorded on the receipt.
+    let mut withheld = 0u64;
+    let mut payouts = vec![
+        (&ctx.accounts.platform_token_account, shares.platform),
+        (&ctx.accounts.referrer_token_account, direct_referrer_share),
+        (&ctx.accounts.reserve_token_account, shares.reserve),
+    ];
+    payouts.extend(upline.iter().map(|(account, share)| (account, *share)));
+    payouts.extend(recipient_accounts.iter().zip(shares.recipients));
+    payouts.push((
+        &ctx.accounts.protocol_treasury_token_account,
+        shares.protocol,
+    ));
+    for (token_account, share) in payouts {
+        withheld = withheld
+            .checked_add(transfer.send(token_account, share)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    let agent_withheld = transfer.send(agent_destination, shares.agent)?;
+    withheld = withheld
+        .checked_add(agent_withheld)
+        .ok_or(ErrorCode::MathOverflow)?;
 
-    transfer_from_vault(
-        &ctx.accounts.token_program,
-        &ctx.accounts.settlement_vault,
-        &ctx.accounts.platform_token_account,
-        &ctx.accounts.vault_authority,
+    if let Some(escrow) = ctx.accounts.vesting_escrow.as_deref_mut() {
+        if vesting_duration > 0 {
+            deposit_vesting(escrow, shares.agent - agent_withheld, vesting_duration, now)?;
+        }
+    }
+    if let Some(reserve) = ctx.accounts.reserve.as_deref_mut() {
+        ctx.accounts.reserve_token_account.reload()?;
+        sync_reserve(reserve, ctx.accounts.reserve_token_account.amount, now)?;
+    }
+
+    let receipt = &mut ctx.accounts.distribution_receipt;
+    receipt.agent = ctx.accounts.agent_identity.key();
+    receipt.reference = reference;
+    receipt.amount = amount;
+    receipt.mint = ctx.accounts.settlement_vault.mint;
+    receipt.transfer_fee = withheld;
+    receipt.protocol_fee_bps = protocol_fee_bps;
+    receipt.operator = ctx.accounts.operator.key();
+    receipt.distributed_at = now;
+    receipt.bump = ctx.bumps.distribution_receipt;
+
+    Ok(())
+}
+
+/// Pays a settlement in lamports from the `sol-vault` PDA. The vault keeps its rent-exempt
+/// minimum, and the whole amount is checked before any lamports move. SOL and token settlements
+/// share the `receipt` PDA, so a reference settles once across both.
+pub fn distribute_settlement_sol<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeSettlementSol<'info>>,
+    reference: [u8; 32],
+    amount: u64,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    assert_role(
+        &ctx.accounts.protocol_config,
+        &ctx.accounts.role_assignment,
+        None,
+        ctx.accounts.operator.key(),
+        ROLE_REVENUE_OPERATOR,
+    )?;
+
+    require!(!is_zero_hash(&reference), ErrorCode::InvalidHash);
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let protocol = &ctx.accounts.protocol_config;
+    let split = &ctx.accounts.split_config;
+    require!(split.vesting_duration == 0, ErrorCode::VestingNotSupported);
+
+    require!(
+        protocol.sol_treasury != Pubkey::default()
+            && ctx.accounts.protocol_treasury.key() == protocol.sol_treasury,
+        ErrorCode::InvalidTreasuryAccount
+    );
+
+    if split.platform_bps > 0 {
+        require!(
+            ctx.accounts.platform_wallet.key() == split.platform,
+            ErrorCode::InvalidAddress
+        );
+    }
+
+    if split.referrer_bps > 0 {
+        require!(
+            ctx.accounts.referrer_wallet.key() == split.referrer,
+            ErrorCode::InvalidAddress
+        );
+        require_direct_referral(&ctx.accounts.referral_program)?;
+    }
+
+    if split.reserve_bps > 0 {
+        require!(
+            ctx.accounts.reserve_wallet.key() == split.reserve_vault,
+            ErrorCode::InvalidAddress
+        );
+        require_external_reserve(&ctx.accounts.reserve_wallet)?;
+    }
+
+    // Extra recipients' wallets follow in `remaining_accounts`, in split order.
+    require!(
+        ctx.remaining_accounts.len() == split.recipients.len(),
+        ErrorCode::InvalidSplitRecipients
+    );
+    for (entry, info) in split.recipients.iter().zip(ctx.remaining_accounts.iter()) {
+        require!(info.is_writable, ErrorCode::InvalidSplitRecipients);
+        require!(info.key() == entry.recipient, ErrorCode::InvalidAddress);
+    }
+
+    // The whole amount must be available above the rent-exempt minimum before any
+    // lamports move, so a short vault fails cleanly instead of part-way through.
+    let reserved = Rent::get()?.minimum_balance(0);
+    let available = ctx.accounts.sol_vault.lamports().saturating_sub(reserved);
+    require!(amount <= available, ErrorCode::InsufficientVaultBalance);
+
+    let now = Clock::get()?.unix_timestamp;
+    let protocol_fee_bps = scheduled_fee_bps(
+        &ctx.accounts.fee_schedule,
+        protocol.protocol_fee_bps,
+        protocol,
+        ctx.accounts.verification_record.as_deref(),
+        ctx.accounts.reputation_state.as_deref(),
+        &mut ctx.accounts.settlement_volume,
+        amount,
+        now,
+    )?;
+    ctx.accounts.settlement_volume.agent = ctx.accounts.agent_identity.key();
+    ctx.accounts.settlement_volume.mint = Pubkey::default();
+    ctx.accounts.settlement_volume.bump = ctx.bumps.settlement_volume;
+
+    let shares = split_settlement(amount, split, protocol_fee_bps)?;
+
+    let signer_seeds: &[&[u8]] = &[SOL_VAULT_SEED, &[ctx.bumps.sol_vault]];
+    let system_program = &ctx.accounts.system_program;
+    let vault = &ctx.accounts.sol_vault;
+
+    transfer_sol_from_vault(
+        system_program,
+        vault,
+        ctx.accounts.platform_wallet.as_ref(),
         signer_seeds,
-        platform_amount,
+        shares.platform,
     )?;
 
-    transfer_from_vault(
-        &ctx.accounts.token_program,
-        &ctx.accounts.settlement_vault,
-        &ctx.accounts.referrer_token_account,
-        &ctx.accounts.vault_authority,
+    transfer_sol_from_vault(
+        system_program,
+        vault,
+        ctx.accounts.referrer_wallet.as_ref(),
         signer_seeds,
-        referrer_amount,
+        shares.referrer,
     )?;
 
-    transfer_from_vault(
-        &ctx.accounts.token_program,
-        &ctx.accounts.settlement_vault,
-        &ctx.accounts.reserve_token_account,
-        &ctx.accounts.vault_authority,
+    transfer_sol_from_vault(
+        system_program,
+        vault,
+        ctx.accounts.reserve_wallet.as_ref(),
         signer_seeds,
-        reserve_amount,
+        shares.reserve,
     )?;
 
-    transfer_from_vault(
-        &ctx.accounts.token_program,
-        &ctx.accounts.settlement_vault,
-        &ctx.accounts.protocol_treasury_token_account,
-        &ctx.accounts.vault_authority,
+    for (info, share) in ctx.remaining_accounts.iter().zip(shares.recipients) {
+        transfer_sol_from_vault(system_program, vault, info, signer_seeds, share)?;
+    }
+
+    transfer_sol_from_vault(
+        system_program,
+        vault,
+        ctx.accounts.protocol_treasury.as_ref(),
         signer_seeds,
-        protocol_amount,
+        shares.protocol,
     )?;
 
-    transfer_from_vault(
-        &ctx.accounts.token_program,
-        &ctx.accounts.settlement_vault,
-        &ctx.accounts.agent_token_account,
-        &ctx.accounts.vault_authority,
+    transfer_sol_from_vault(
+        system_program,
+        vault,
+        ctx.accounts.agent_wallet.as_ref(),
         signer_seeds,
-        agent_amount,
+        shares.agent,
     )?;
 
     let receipt = &mut ctx.accounts.distribution_receipt;
     receipt.agent = ctx.accounts.agent_identity.key();
     receipt.reference = reference;
     receipt.amount = amount;
+    receipt.mint = Pubkey::default();
+    receipt.transfer_fee = 0;
+    receipt.protocol_fee_bps = protocol_fee_bps;
     receipt.operator = ctx.accounts.operator.key();
-    receipt.distributed_at = Clock::get()?.unix_timestamp;
+    receipt.distributed_at = now;
     receipt.bump = ctx.bumps.distribution_receipt;
 
     Ok(())
 }
+
+struct SettlementPlan<'info> {
+    agent: Pubkey,
+    receipt: &'info AccountInfo<'info>,
+    bump: u8,
+    protocol_fee_bps: u16,
+    protocol_amount: u64,
+}
+
+/// Inputs for each batch entry's scheduled protocol fee and referrer check.
+struct FeeInputs<'a, 'info> {
+    schedule: &'a AccountInfo<'info>,
+    referral_program: &'a AccountInfo<'info>,
+    base_fee_bps: u16,
+    protocol: &'a ProtocolConfig,
+    payer: &'a AccountInfo<'info>,
+    system_program: &'a Program<'info, System>,
+    now: i64,
+}
+
+/// Payout token accounts collected across a batch, so each receives a single transfer.
+/// `shares` keeps each entry's part of a transfer so its withheld fee can be split back.
+#[derive(Default)]
+struct Payouts<'info> {
+    accounts: Vec<InterfaceAccount<'info, TokenAccount>>,
+    amounts: Vec<u64>,
+    shares: Vec<Vec<(usize, u64)>>,
+}
+
+impl<'info> Payouts<'info> {
+    fn add(
+        &mut self,
+        info: &'info AccountInfo<'info>,
+        owner: Pubkey,
+        mint: Pubkey,
+        entry: usize,
+        amount: u64,
+    ) -> Result<()> {
+        let index = match self
+            .accounts
+            .iter()
+            .position(|account| account.key() == info.key())
+        {
+            Some(index) => index,
+            None => {
+                require!(info.is_writable, ErrorCode::InvalidBatchAccount);
+                self.accounts.push(InterfaceAccount::try_from(info)?);
+                self.amounts.push(0);
+                self.shares.push(Vec::new());
+                self.accounts.len() - 1
+            }
+        };
+
+        let account = &self.accounts[index];
+        require!(account.owner == owner, ErrorCode::InvalidTokenAccountOwner);
+        require!(account.mint == mint, ErrorCode::InvalidTokenMint);
+        self.amounts[index] = self.amounts[index]
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if amount > 0 {
+            self.shares[index].push((entry, amount));
+        }
+        Ok(())
+    }
+}
+
+/// Splits the fee withheld on one aggregated transfer across the entries that make it up, pro
+/// rata to their shares; the rounding remainder goes to the last entry.
+fn apportion_fee(
+    fee: u64,
+    total: u64,
+    shares: &[(usize, u64)],
+    entry_fees: &mut [u64],
+) -> Result<()> {
+    if fee == 0 {
+        return Ok(());
+    }
+
+    let mut remaining = fee;
+    for (position, (entry, amount)) in shares.iter().enumerate() {
+        let part = if position + 1 == shares.len() {
+            remaining
+        } else {
+            u64::try_from(u128::from(fee) * u128::from(*amount) / u128::from(total))
+                .map_err(|_| ErrorCode::MathOverflow)?
+        };
+        remaining -= part;
+        entry_fees[*entry] = entry_fees[*entry]
+            .checked_add(part)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    Ok(())
+}
+
+/// Settles several references from one vault. Every entry is validated and priced before any
+/// transfer, later entries for an agent see earlier ones in the trailing volume, and shares are
+/// summed per destination token account so each receives a single transfer.
+pub fn distribute_settlement_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeSettlementBatch<'info>>,
+    entries: Vec<SettlementBatchEntry>,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    assert_role(
+        &ctx.accounts.protocol_config,
+        &ctx.accounts.role_assignment,
+        None,
+        ctx.accounts.operator.key(),
+        ROLE_REVENUE_OPERATOR,
+    )?;
+    require!(
+        !entries.is_empty() && entries.len() <= MAX_BATCH_ENTRIES,
+        ErrorCode::InvalidBatch
+    );
+
+    let protocol = &ctx.accounts.protocol_config;
+    let vault = &ctx.accounts.settlement_vault;
+    let base_fee_bps = settlement_route(
+        protocol,
+        ctx.accounts.settlement_mint_config.as_deref(),
+        vault,
+        &ctx.accounts.protocol_treasury_token_account,
+    )?;
+    require!(
+        vault.owner == ctx.accounts.vault_authority.key(),
+        ErrorCode::InvalidTokenAccountOwner
+    );
+    require!(
+        ctx.accounts.protocol_treasury_token_account.mint == vault.mint,
+        ErrorCode::InvalidTokenMint
+    );
+
+    // Aggregated payouts are sent gross like single settlements; each receipt records its
+    // pro-rata part of the fee withheld on the transfers it contributed to.
+    let signer_seeds: &[&[u8]] = &[VAULT_AUTHORITY_SEED, &[protocol.vault_authority_bump]];
+    let transfer = VaultTransfer {
+        token_program: &ctx.accounts.token_program,
+        vault,
+        mint: &ctx.accounts.settlement_mint,
+        authority: &ctx.accounts.vault_authority,
+        signer_seeds,
+        fee_config: settlement_mint_fee_config(&ctx.accounts.settlement_mint.to_account_info())?,
+        epoch: Clock::get()?.epoch,
+    };
+    let now = Clock::get()?.unix_timestamp;
+    let operator = ctx.accounts.operator.to_account_info();
+    let fees = FeeInputs {
+        schedule: &ctx.accounts.fee_schedule,
+        referral_program: &ctx.accounts.referral_program,
+        base_fee_bps,
+        protocol,
+        payer: &operator,
+        system_program: &ctx.accounts.system_program,
+        now,
+    };
+
+    let mut accounts = ctx.remaining_accounts.iter();
+    let mut plans: Vec<SettlementPlan> = Vec::with_capacity(entries.len());
+    let mut payouts = Payouts::default();
+    let mut volumes = Vec::new();
+    let mut protocol_total = 0u64;
+    for (index, entry) in entries.iter().enumerate() {
+        let plan = plan_settlement(
+            ctx.program_id,
+            &fees,
+            vault.mint,
+            entry,
+            &mut accounts,
+            &plans,
+            &mut payouts,
+            &mut volumes,
+        )
+        .map_err(|err| batch_entry_error(index, err))?;
+        protocol_total = protocol_total
+            .checked_add(plan.protocol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        plans.push(plan);
+    }
+    require!(accounts.next().is_none(), ErrorCode::InvalidBatch);
+
+    let total = entries.iter().try_fold(0u64, |total, entry| {
+        total
+            .checked_add(entry.amount)
+            .ok_or(ErrorCode::MathOverflow)
+    })?;
+    require_unallocated(
+        vault,
+        claims_outstanding(&ctx.accounts.claim_ledger)?,
+        total,
+    )?;
+
+    let mut entry_fees = vec![0u64; entries.len()];
+    for ((account, amount), shares) in payouts
+        .accounts
+        .iter()
+        .zip(payouts.amounts.iter())
+        .zip(payouts.shares.iter())
+    {
+        let fee = transfer.send(account, *amount)?;
+        apportion_fee(fee, *amount, shares, &mut entry_fees)?;
+    }
+    let protocol_fee = transfer.send(
+        &ctx.accounts.protocol_treasury_token_account,
+        protocol_total,
+    )?;
+    let protocol_shares: Vec<(usize, u64)> = plans
+        .iter()
+        .map(|plan| plan.protocol_amount)
+        .enumerate()
+        .filter(|(_, amount)| *amount > 0)
+        .collect();
+    apportion_fee(
+        protocol_fee,
+        protocol_total,
+        &protocol_shares,
+        &mut entry_fees,
+    )?;
+
+    for volume in &volumes {
+        volume.exit(ctx.program_id)?;
+    }
+
+    for ((entry, plan), transfer_fee) in entries.iter().zip(plans.iter()).zip(entry_fees) {
+        create_pda_account(
+            &operator,
+            plan.receipt,
+            &ctx.accounts.system_program,
+            DistributionReceipt::SPACE,
+            &[
+                RECEIPT_SEED,
+                plan.agent.as_ref(),
+                &entry.reference,
+                &[plan.bump],
+            ],
+        )?;
+
+        let mut receipt = Account::<DistributionReceipt>::try_from_unchecked(plan.receipt)?;
+        receipt.agent = plan.agent;
+        receipt.reference = entry.reference;
+        receipt.amount = entry.amount;
+        receipt.mint = vault.mint;
+        receipt.transfer_fee = transfer_fee;
+        receipt.protocol_fee_bps = plan.protocol_fee_bps;
+        receipt.operator = operator.key();
+        receipt.distributed_at = now;
+        receipt.bump = plan.bump;
+        receipt.exit(ctx.program_id)?;
+    }
+
+    Ok(())
+}
+
+/// Validates one batch entry, prices it against the fee schedule and queues its payouts.
+/// Remaining accounts per entry: agent_identity, split_config, distribution_receipt,
+/// verification_record, reputation_state, settlement_volume, agent token account, then the
+/// platform, referrer and reserve token accounts for non-zero slots, then one per extra
+/// split recipient.
+#[allow(clippy::too_many_arguments)]
+fn plan_settlement<'info>(
+    program_id: &Pubkey,
+    fees: &FeeInputs<'_, 'info>,
+    mint: Pubkey,
+    entry: &SettlementBatchEntry,
+    accounts: &mut impl Iterator<Item = &'info AccountInfo<'info>>,
+    planned: &[SettlementPlan<'info>],
+    payouts: &mut Payouts<'info>,
+    volumes: &mut Vec<Account<'info, SettlementVolume>>,
+) -> Result<SettlementPlan<'info>> {
+    require!(!is_zero_hash(&entry.reference), ErrorCode::InvalidHash);
+    require!(entry.amount > 0, ErrorCode::InvalidAmount);
+
+    let agent_identity = Account::<AgentIdentity>::try_from(next_batch_account(accounts)?)?;
+    let agent = agent_identity.key();
+    let split = Account::<RevenueSplitConfig>::try_from(next_batch_account(accounts)?)?;
+    require!(split.agent == agent, ErrorCode::InvalidBatchAccount);
+    require!(split.vesting_duration == 0, ErrorCode::VestingNotSupported);
+    if split.referrer_bps > 0 {
+        require_direct_referral(fees.referral_program)?;
+    }
+
+    let receipt = next_batch_account(accounts)?;
+    let (expected, bump) = Pubkey::find_program_address(
+        &[RECEIPT_SEED, agent.as_ref(), &entry.reference],
+        program_id,
+    );
+    require!(
+        receipt.key() == expected && receipt.is_writable,
+        ErrorCode::InvalidBatchAccount
+    );
+    require!(
+        receipt.data_is_empty(),
+        ErrorCode::ReferenceAlreadyDistributed
+    );
+    require!(
+        planned.iter().all(|plan| plan.receipt.key() != expected),
+        ErrorCode::DuplicateBatchEntry
+    );
+
+    let verification = optional_batch_account::<VerificationRecord>(
+        next_batch_account(accounts)?,
+        &[VERIFICATION_SEED, agent.as_ref()],
+        program_id,
+    )?;
+    let reputation = optional_batch_account::<ReputationState>(
+        next_batch_account(accounts)?,
+        &[REPUTATION_SEED, agent.as_ref()],
+        program_id,
+    )?;
+    let volume = load_volume(volumes, next_batch_account(accounts)?, agent, mint, fees)?;
+    let protocol_fee_bps = scheduled_fee_bps(
+        fees.schedule,
+        fees.base_fee_bps,
+        fees.protocol,
+        verification.as_deref(),
+        reputation.as_deref(),
+        &mut volumes[volume],
+        entry.amount,
+        fees.now,
+    )?;
+
+    let index = planned.len();
+    let shares = split_settlement(entry.amount, &split, protocol_fee_bps)?;
+    payouts.add(
+        next_batch_account(accounts)?,
+        agent_identity.agent_wallet,
+        mint,
+        index,
+        shares.agent,
+    )?;
+    for (owner, bps, amount) in [
+        (split.platform, split.platform_bps, shares.platform),
+        (split.referrer, split.referrer_bps, shares.referrer),
+        (split.reserve_vault, split.reserve_bps, shares.reserve),
+    ] {
+        if bps > 0 {
+            payouts.add(next_batch_account(accounts)?, owner, mint, index, amount)?;
+        }
+    }
+    for (recipient, amount) in split.recipients.iter().zip(shares.recipients.iter()) {
+        payouts.add(
+            next_batch_account(accounts)?,
+            recipient.recipient,
+            mint,
+            index,
+            *amount,
+        )?;
+    }
+
+    Ok(SettlementPlan {
+        agent,
+        receipt,
+        bump,
+        protocol_fee_bps,
+        protocol_amount: shares.protocol,
+    })
+}
+
+/// Loads a per-agent PDA passed by address; `None` until the account exists.
+fn optional_batch_account<'info, T: AccountSerialize + AccountDeserialize + Owner + Clone>(
+    info: &'info AccountInfo<'info>,
+    seeds: &[&[u8]],
+    program_id: &Pubkey,
+) -> Result<Option<Account<'info, T>>> {
+    let (expected, _) = Pubkey::find_program_address(seeds, program_id);
+    require!(info.key() == expected, ErrorCode::InvalidBatchAccount);
+    if info.data_is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Account::try_from(info)?))
+}
+
+/// Loads an agent's trailing volume for the batch mint on first use, creating the account
+/// at the operator's expense.
+fn load_volume<'info>(
+    volumes: &mut Vec<Account<'info, SettlementVolume>>,
+    info: &'info AccountInfo<'info>,
+    agent: Pubkey,
+    mint: Pubkey,
+    fees: &FeeInputs<'_, 'info>,
+) -> Result<usize> {
+    if let Some(index) = volumes.iter().position(|volume| volume.key() == info.key()) {
+        return Ok(index);
+    }
+
+    let (expected, bump) = Pubkey::find_program_address(
+        &[SETTLEMENT_VOLUME_SEED, agent.as_ref(), mint.as_ref()],
+        &crate::ID,
+    );
+    require!(
+        info.key() == expected && info.is_writable,
+        ErrorCode::InvalidBatchAccount
+    );
+
+    let volume = if info.data_is_empty() {
+        create_pda_account(
+            fees.payer,
+            info,
+            fees.system_program,
+            SettlementVolume::SPACE,
+            &[
+                SETTLEMENT_VOLUME_SEED,
+                agent.as_ref(),
+                mint.as_ref(),
+                &[bump],
+            ],
+        )?;
+        let mut volume = Account::<SettlementVolume>::try_from_unchecked(info)?;
+        volume.agent = agent;
+        volume.mint = mint;
+        volume.bump = bump;
+        volume
+    } else {
+        Account::<SettlementVolume>::try_from(info)?
+    };
+    volumes.push(volume);
+    Ok(volumes.len() - 1)
+}
@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_UNSTAKE_REQUESTS, VAULT_AUTHORITY_SEED};
+use crate::contexts::{RequestUnstake, SlashStake, Stake, WithdrawUnstaked};
+use crate::errors::ErrorCode;
+use crate::helpers::{
+    is_zero_hash, require_mint_treasury, require_not_paused, transfer_from_vault, transfer_to_vault,
+};
+use crate::state::UnstakeRequest;
+
+pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let member = ctx.accounts.member.key();
+    // New vaults open in the current settlement mint; existing ones are bound to their own mint
+    // by the `token::mint` constraint.
+    if ctx.accounts.member_stake.member == Pubkey::default() {
+        require!(
+            ctx.accounts.stake_mint.key() == ctx.accounts.protocol_config.settlement_mint,
+            ErrorCode::SettlementTokenMismatch
+        );
+    }
+    require!(
+        ctx.accounts.member_token_account.owner == member,
+        ErrorCode::InvalidTokenAccountOwner
+    );
+    require!(
+        ctx.accounts.member_token_account.mint == ctx.accounts.stake_vault.mint,
+        ErrorCode::InvalidTokenMint
+    );
+
+    transfer_to_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.member_token_account,
+        &ctx.accounts.stake_vault,
+        &ctx.accounts.member,
+        amount,
+    )?;
+
+    let stake = &mut ctx.accounts.member_stake;
+    if stake.member == Pubkey::default() {
+        stake.member = member;
+        stake.bump = ctx.bumps.member_stake;
+    }
+
+    stake.amount = stake
+        .amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    stake.updated_at = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let available_at = now
+        .checked_add(ctx.accounts.protocol_config.unstake_cooldown)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let stake = &mut ctx.accounts.member_stake;
+    require!(amount <= stake.amount, ErrorCode::InvalidAmount);
+    require!(
+        stake.pending.len() < MAX_UNSTAKE_REQUESTS,
+        ErrorCode::UnstakeQueueFull
+    );
+
+    stake.amount -= amount;
+    stake.pending.push(UnstakeRequest {
+        amount,
+        available_at,
+    });
+    stake.updated_at = now;
+
+    Ok(())
+}
+
+pub fn withdraw_unstaked(ctx: Context<WithdrawUnstaked>) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+
+    require!(
+        ctx.accounts.member_token_account.owner == ctx.accounts.member.key(),
+        ErrorCode::InvalidTokenAccountOwner
+    );
+    require!(
+        ctx.accounts.member_token_account.mint == ctx.accounts.stake_vault.mint,
+        ErrorCode::InvalidTokenMint
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let stake = &mut ctx.accounts.member_stake;
+
+    let mut released = 0u64;
+    for request in stake
+        .pending
+        .iter()
+        .filter(|request| request.available_at <= now)
+    {
+        released = released
+            .checked_add(request.amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    require!(released > 0, ErrorCode::NothingToWithdraw);
+
+    stake.pending.retain(|request| request.available_at > now);
+    stake.updated_at = now;
+
+    let signer_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        &[ctx.accounts.protocol_config.vault_authority_bump],
+    ];
+
+    transfer_from_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.stake_vault,
+        &ctx.accounts.member_token_account,
+        &ctx.accounts.vault_authority,
+        signer_seeds,
+        released,
+    )?;
+
+    Ok(())
+}
+
+// Slashing draws from the active stake first and then from the newest pending
+// unstake requests, so queuing an unstake does not shield stake from penalties.
+pub fn slash_stake(ctx: Context<SlashStake>, amount: u64, reason_hash: [u8; 32]) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(!is_zero_hash(&reason_hash), ErrorCode::InvalidHash);
+
+    require_mint_treasury(
+        &ctx.accounts.protocol_config,
+        ctx.accounts.settlement_mint_config.as_deref(),
+        ctx.accounts.stake_vault.mint,
+        &ctx.accounts.protocol_treasury_token_account,
+    )?;
+
+    let stake = &mut ctx.accounts.member_stake;
+    let from_active = amount.min(stake.amount);
+    stake.amount -= from_active;
+
+    let mut remaining = amount - from_active;
+    while remaining > 0 {
+        let request = stake
+            .pending
+            .last_mut()
+            .ok_or(ErrorCode::InsufficientStake)?;
+        let taken = remaining.min(request.amount);
+        request.amount -= taken;
+        remaining -= taken;
+        if request.amount == 0 {
+            stake.pending.pop();
+        }
+    }
+
+    stake.slashed_total = stake
+        .slashed_total
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    stake.updated_at = Clock::get()?.unix_timestamp;
+
+    let signer_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        &[ctx.accounts.protocol_config.vault_authority_bump],
+    ];
+
+    transfer_from_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.stake_vault,
+        &ctx.accounts.protocol_treasury_token_account,
+        &ctx.accounts.vault_authority,
+        signer_seeds,
+        amount,
+    )?;
+
+    Ok(())
+}
@@ -26,6 +26,7 @@ pub fn request_verification(
     record.evidence_hash = request_hash;
     record.policy_hash = policy_hash;
     record.bump = ctx.bumps.verification_record;
+    record.suspension_reason_hash = [0; 32];
 
     Ok(())
 }
@@ -39,7 +40,9 @@ pub fn set_verification_status(
 ) -> Result<()> {
     require_not_paused(&ctx.accounts.protocol_config)?;
     assert_role(
+        &ctx.accounts.protocol_config,
         &ctx.accounts.role_assignment,
+        None,
         ctx.accounts.operator.key(),
         ROLE_VERIFICATION_OPERATOR,
     )?;
@@ -61,6 +64,7 @@ pub fn set_verification_status(
     record.evidence_hash = evidence_hash;
     record.policy_hash = policy_hash;
     record.bump = ctx.bumps.verification_record;
+    record.suspension_reason_hash = [0; 32];
 
     Ok(())
 }
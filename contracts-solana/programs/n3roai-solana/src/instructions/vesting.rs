@@ -0,0 +1,133 @@
+use std::cmp::Reverse;
+
+use anchor_lang::prelude::*;
+
+use crate::constants::VAULT_AUTHORITY_SEED;
+use crate::contexts::{ClawbackVesting, OpenVestingEscrow, ReleaseVesting};
+use crate::errors::ErrorCode;
+use crate::helpers::{
+    checkpoint_vesting, is_suspended, require_not_paused, settlement_mint_fee_config, VaultTransfer,
+};
+
+pub fn open_vesting_escrow(ctx: Context<OpenVestingEscrow>) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    settlement_mint_fee_config(&ctx.accounts.settlement_mint.to_account_info())?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let escrow = &mut ctx.accounts.vesting_escrow;
+    escrow.agent = ctx.accounts.agent_identity.key();
+    escrow.mint = ctx.accounts.settlement_mint.key();
+    escrow.vault = ctx.accounts.vesting_vault.key();
+    escrow.locked = 0;
+    escrow.vested = 0;
+    escrow.last_update = now;
+    escrow.total_deposited = 0;
+    escrow.total_released = 0;
+    escrow.total_clawed_back = 0;
+    escrow.tranches = Vec::new();
+    escrow.bump = ctx.bumps.vesting_escrow;
+
+    Ok(())
+}
+
+pub fn release_vesting(ctx: Context<ReleaseVesting>) -> Result<()> {
+    require_not_paused(&ctx.accounts.protocol_config)?;
+    require!(
+        !is_suspended(&ctx.accounts.verification_record)?,
+        ErrorCode::AgentSuspended
+    );
+    require!(
+        ctx.accounts.agent_token_account.owner == ctx.accounts.agent_identity.agent_wallet,
+        ErrorCode::InvalidTokenAccountOwner
+    );
+    require!(
+        ctx.accounts.agent_token_account.mint == ctx.accounts.vesting_escrow.mint,
+        ErrorCode::InvalidTokenMint
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    checkpoint_vesting(&mut ctx.accounts.vesting_escrow, now)?;
+    let amount = ctx.accounts.vesting_escrow.vested;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let signer_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        &[ctx.accounts.protocol_config.vault_authority_bump],
+    ];
+    let transfer = VaultTransfer {
+        token_program: &ctx.accounts.token_program,
+        vault: &ctx.accounts.vesting_vault,
+        mint: &ctx.accounts.settlement_mint,
+        authority: &ctx.accounts.vault_authority,
+        signer_seeds,
+        fee_config: settlement_mint_fee_config(&ctx.accounts.settlement_mint.to_account_info())?,
+        epoch: Clock::get()?.epoch,
+    };
+    transfer.send(&ctx.accounts.agent_token_account, amount)?;
+
+    let escrow = &mut ctx.accounts.vesting_escrow;
+    escrow.vested = 0;
+    escrow.total_released = escrow
+        .total_released
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(())
+}
+
+/// Returns escrowed agent revenue to a payer while the agent is suspended. Unvested tokens are
+/// taken first, latest-ending tranche first, then vested tokens that have not been released.
+pub fn clawback_vesting(ctx: Context<ClawbackVesting>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        is_suspended(&ctx.accounts.verification_record)?,
+        ErrorCode::AgentNotSuspended
+    );
+    require!(
+        ctx.accounts.payer_token_account.mint == ctx.accounts.vesting_escrow.mint,
+        ErrorCode::InvalidTokenMint
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let escrow = &mut ctx.accounts.vesting_escrow;
+    checkpoint_vesting(escrow, now)?;
+    let escrowed = escrow
+        .locked
+        .checked_add(escrow.vested)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(amount <= escrowed, ErrorCode::InsufficientEscrowBalance);
+
+    // Unvested tokens come out of the latest-ending tranches first.
+    let from_locked = amount.min(escrow.locked);
+    escrow.tranches.sort_by_key(|tranche| Reverse(tranche.end));
+    let mut remaining = from_locked;
+    for tranche in escrow.tranches.iter_mut() {
+        let taken = remaining.min(tranche.remaining);
+        tranche.remaining -= taken;
+        remaining -= taken;
+    }
+    escrow.tranches.retain(|tranche| tranche.remaining > 0);
+    escrow.locked -= from_locked;
+    escrow.vested -= amount - from_locked;
+    escrow.total_clawed_back = escrow
+        .total_clawed_back
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let signer_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        &[ctx.accounts.protocol_config.vault_authority_bump],
+    ];
+    let transfer = VaultTransfer {
+        token_program: &ctx.accounts.token_program,
+        vault: &ctx.accounts.vesting_vault,
+        mint: &ctx.accounts.settlement_mint,
+        authority: &ctx.accounts.vault_authority,
+        signer_seeds,
+        fee_config: settlement_mint_fee_config(&ctx.accounts.settlement_mint.to_account_info())?,
+        epoch: Clock::get()?.epoch,
+    };
+    transfer.send(&ctx.accounts.payer_token_account, amount)?;
+
+    Ok(())
+}
@@ -5,9 +5,13 @@ pub mod contexts;
 pub mod errors;
 pub mod helpers;
 pub mod instructions;
+pub mod math;
 pub mod state;
 
 use contexts::*;
+use state::{
+    CompressedSignalLeaf, FeeTier, ScoreBatchEntry, SettlementBatchEntry, SignalBatchEntry, SplitRecipient,
+};
 
 declare_id!("Fg6PaFpoGXkYsidMpWxTWqkZ7xw1i7hP5QZsM7hByX2s");
 
@@ -97,6 +101,35 @@ pub mod n3roai_solana {
         instructions::admin::set_protocol_treasury(ctx, protocol_treasury_token_account)
     }
 
+    pub fn set_sol_treasury(ctx: Context<AdminUpdate>, sol_treasury: Pubkey) -> Result<()> {
+        instructions::admin::set_sol_treasury(ctx, sol_treasury)
+    }
+
+    pub fn add_settlement_mint(
+        ctx: Context<AddSettlementMint>,
+        fee_override: bool,
+        protocol_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::admin::add_settlement_mint(ctx, fee_override, protocol_fee_bps)
+    }
+
+    pub fn update_settlement_mint(
+        ctx: Context<UpdateSettlementMint>,
+        fee_override: bool,
+        protocol_fee_bps: u16,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::admin::update_settlement_mint(ctx, fee_override, protocol_fee_bps, enabled)
+    }
+
+    pub fn set_fee_schedule(ctx: Context<SetFeeSchedule>, tiers: Vec<FeeTier>) -> Result<()> {
+        instructions::admin::set_fee_schedule(ctx, tiers)
+    }
+
+    pub fn set_referral_levels(ctx: Context<SetReferralLevels>, level_bps: Vec<u16>) -> Result<()> {
+        instructions::admin::set_referral_levels(ctx, level_bps)
+    }
+
     pub fn set_score_config(
         ctx: Context<AdminUpdate>,
         min_confidence_bps: u16,
@@ -106,6 +139,79 @@ pub mod n3roai_solana {
         instructions::admin::set_score_config(ctx, min_confidence_bps, score_alpha_bps, max_signal_age)
     }
 
+    pub fn set_score_half_life(ctx: Context<AdminUpdate>, score_half_life: i64) -> Result<()> {
+        instructions::admin::set_score_half_life(ctx, score_half_life)
+    }
+
+    pub fn set_score_aggregation(
+        ctx: Context<AdminUpdate>,
+        score_quorum: u8,
+        score_ballot_window: i64,
+    ) -> Result<()> {
+        instructions::admin::set_score_aggregation(ctx, score_quorum, score_ballot_window)
+    }
+
+    pub fn set_arbiter(ctx: Context<AdminUpdate>, arbiter: Pubkey) -> Result<()> {
+        instructions::admin::set_arbiter(ctx, arbiter)
+    }
+
+    pub fn set_challenge_config(
+        ctx: Context<AdminUpdate>,
+        challenge_window: i64,
+        challenge_bond: u64,
+    ) -> Result<()> {
+        instructions::admin::set_challenge_config(ctx, challenge_window, challenge_bond)
+    }
+
+    pub fn set_stake_config(
+        ctx: Context<AdminUpdate>,
+        min_oracle_stake: u64,
+        min_signaler_stake: u64,
+        unstake_cooldown: i64,
+    ) -> Result<()> {
+        instructions::admin::set_stake_config(
+            ctx,
+            min_oracle_stake,
+            min_signaler_stake,
+            unstake_cooldown,
+        )
+    }
+
+    pub fn set_reputation_epoch_length(
+        ctx: Context<AdminUpdate>,
+        reputation_epoch_length: i64,
+    ) -> Result<()> {
+        instructions::admin::set_reputation_epoch_length(ctx, reputation_epoch_length)
+    }
+
+    pub fn set_signal_commit_config(
+        ctx: Context<AdminUpdate>,
+        signal_reveal_window: i64,
+        require_signal_commit: bool,
+    ) -> Result<()> {
+        instructions::admin::set_signal_commit_config(
+            ctx,
+            signal_reveal_window,
+            require_signal_commit,
+        )
+    }
+
+    pub fn set_aggregation_model(
+        ctx: Context<AdminUpdate>,
+        aggregation_model: u8,
+        score_window_size: u8,
+        beta_prior_alpha: u32,
+        beta_prior_beta: u32,
+    ) -> Result<()> {
+        instructions::admin::set_aggregation_model(
+            ctx,
+            aggregation_model,
+            score_window_size,
+            beta_prior_alpha,
+            beta_prior_beta,
+        )
+    }
+
     pub fn set_require_verified_for_score(
         ctx: Context<AdminUpdate>,
         require_verified_for_score: bool,
@@ -117,6 +223,80 @@ pub mod n3roai_solana {
         instructions::admin::set_paused(ctx, paused)
     }
 
+    pub fn initialize_risk_flag_table(
+        ctx: Context<InitializeRiskFlagTable>,
+        suspension_window: i64,
+    ) -> Result<()> {
+        instructions::admin::initialize_risk_flag_table(ctx, suspension_window)
+    }
+
+    pub fn set_risk_flag_rule(
+        ctx: Context<UpdateRiskFlagTable>,
+        bit: u8,
+        name_hash: [u8; 32],
+        penalty_bps: u16,
+        weight_bps: u16,
+        suspend_threshold: u16,
+    ) -> Result<()> {
+        instructions::admin::set_risk_flag_rule(
+            ctx,
+            bit,
+            name_hash,
+            penalty_bps,
+            weight_bps,
+            suspend_threshold,
+        )
+    }
+
+    pub fn set_risk_flag_window(
+        ctx: Context<UpdateRiskFlagTable>,
+        suspension_window: i64,
+    ) -> Result<()> {
+        instructions::admin::set_risk_flag_window(ctx, suspension_window)
+    }
+
+    pub fn set_category(
+        ctx: Context<SetCategory>,
+        category_id: u16,
+        name_hash: [u8; 32],
+        score_alpha_bps: u16,
+        min_confidence_bps: u16,
+        active: bool,
+    ) -> Result<()> {
+        instructions::admin::set_category(
+            ctx,
+            category_id,
+            name_hash,
+            score_alpha_bps,
+            min_confidence_bps,
+            active,
+        )
+    }
+
+    pub fn initialize_leaderboard(
+        ctx: Context<InitializeLeaderboard>,
+        category_id: u16,
+        capacity: u16,
+        min_score_count: u32,
+        require_verified: bool,
+    ) -> Result<()> {
+        instructions::admin::initialize_leaderboard(
+            ctx,
+            category_id,
+            capacity,
+            min_score_count,
+            require_verified,
+        )
+    }
+
+    pub fn set_leaderboard_config(
+        ctx: Context<SetLeaderboardConfig>,
+        min_score_count: u32,
+        require_verified: bool,
+    ) -> Result<()> {
+        instructions::admin::set_leaderboard_config(ctx, min_score_count, require_verified)
+    }
+
     pub fn request_verification(
         ctx: Context<RequestVerification>,
         request_hash: [u8; 32],
@@ -162,14 +342,91 @@ pub mod n3roai_solana {
         )
     }
 
+    pub fn set_split_recipients(
+        ctx: Context<SetSplit>,
+        recipients: Vec<SplitRecipient>,
+    ) -> Result<()> {
+        instructions::revenue::set_split_recipients(ctx, recipients)
+    }
+
+    pub fn register_referral<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RegisterReferral<'info>>,
+        parent: Pubkey,
+    ) -> Result<()> {
+        instructions::referral::register_referral(ctx, parent)
+    }
+
+    pub fn set_referral_parent<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetReferralParent<'info>>,
+        parent: Pubkey,
+    ) -> Result<()> {
+        instructions::referral::set_referral_parent(ctx, parent)
+    }
+
+    pub fn set_split_vesting(ctx: Context<SetSplitVesting>, vesting_duration: i64) -> Result<()> {
+        instructions::revenue::set_split_vesting(ctx, vesting_duration)
+    }
+
     pub fn submit_signal(
         ctx: Context<SubmitSignal>,
         trade_id_hash: [u8; 32],
         result_hash: [u8; 32],
         context_hash: [u8; 32],
         risk_flags: u8,
+        category: u16,
     ) -> Result<()> {
-        instructions::reputation::submit_signal(ctx, trade_id_hash, result_hash, context_hash, risk_flags)
+        instructions::reputation::submit_signal(
+            ctx,
+            trade_id_hash,
+            result_hash,
+            context_hash,
+            risk_flags,
+            category,
+        )
+    }
+
+    pub fn commit_signal(ctx: Context<CommitSignal>, commitment: [u8; 32]) -> Result<()> {
+        instructions::commitment::commit_signal(ctx, commitment)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn reveal_signal(
+        ctx: Context<RevealSignal>,
+        commitment: [u8; 32],
+        trade_id_hash: [u8; 32],
+        result_hash: [u8; 32],
+        context_hash: [u8; 32],
+        risk_flags: u8,
+        category: u16,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::commitment::reveal_signal(
+            ctx,
+            commitment,
+            trade_id_hash,
+            result_hash,
+            context_hash,
+            risk_flags,
+            category,
+            salt,
+        )
+    }
+
+    pub fn expire_signal_commitment(
+        ctx: Context<ExpireSignalCommitment>,
+        commitment: [u8; 32],
+    ) -> Result<()> {
+        instructions::commitment::expire_signal_commitment(ctx, commitment)
+    }
+
+    pub fn report_trade_pnl(
+        ctx: Context<ReportTradePnl>,
+        trade_id_hash: [u8; 32],
+        notional: u64,
+        realized_pnl: i64,
+        holding_time: i64,
+    ) -> Result<()> {
+        instructions::pnl::report_trade_pnl(ctx, trade_id_hash, notional, realized_pnl, holding_time)
     }
 
     pub fn submit_score(
@@ -182,11 +439,238 @@ pub mod n3roai_solana {
         instructions::reputation::submit_score(ctx, trade_id_hash, score, confidence_bps, score_hash)
     }
 
-    pub fn distribute_settlement(
-        ctx: Context<DistributeSettlement>,
+    pub fn submit_signal_batch_root(
+        ctx: Context<SubmitSignalBatchRoot>,
+        root: [u8; 32],
+        leaf_count: u32,
+    ) -> Result<()> {
+        instructions::compressed::submit_signal_batch_root(ctx, root, leaf_count)
+    }
+
+    pub fn submit_compressed_score(
+        ctx: Context<SubmitCompressedScore>,
+        root: [u8; 32],
+        leaf: CompressedSignalLeaf,
+        proof: Vec<[u8; 32]>,
+        score: u16,
+        confidence_bps: u16,
+        score_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::compressed::submit_compressed_score(
+            ctx,
+            root,
+            leaf,
+            proof,
+            score,
+            confidence_bps,
+            score_hash,
+        )
+    }
+
+    pub fn submit_signals_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SubmitSignalsBatch<'info>>,
+        entries: Vec<SignalBatchEntry>,
+    ) -> Result<()> {
+        instructions::batch::submit_signals_batch(ctx, entries)
+    }
+
+    pub fn submit_scores_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SubmitScoresBatch<'info>>,
+        entries: Vec<ScoreBatchEntry>,
+    ) -> Result<()> {
+        instructions::batch::submit_scores_batch(ctx, entries)
+    }
+
+    pub fn submit_score_vote(
+        ctx: Context<SubmitScoreVote>,
+        trade_id_hash: [u8; 32],
+        score: u16,
+        confidence_bps: u16,
+        score_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::reputation::submit_score_vote(
+            ctx,
+            trade_id_hash,
+            score,
+            confidence_bps,
+            score_hash,
+        )
+    }
+
+    pub fn finalize_score(ctx: Context<FinalizeScore>, trade_id_hash: [u8; 32]) -> Result<()> {
+        instructions::reputation::finalize_score(ctx, trade_id_hash)
+    }
+
+    pub fn open_challenge(
+        ctx: Context<OpenChallenge>,
+        trade_id_hash: [u8; 32],
+        reason_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::challenge::open_challenge(ctx, trade_id_hash, reason_hash)
+    }
+
+    pub fn resolve_challenge(
+        ctx: Context<ResolveChallenge>,
+        trade_id_hash: [u8; 32],
+        upheld: bool,
+        resolution_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::challenge::resolve_challenge(ctx, trade_id_hash, upheld, resolution_hash)
+    }
+
+    pub fn get_reputation_score(ctx: Context<GetReputationScore>) -> Result<u16> {
+        instructions::reputation::get_reputation_score(ctx)
+    }
+
+    pub fn initialize_category_reputation(
+        ctx: Context<InitializeCategoryReputation>,
+        category_id: u16,
+    ) -> Result<()> {
+        instructions::reputation::initialize_category_reputation(ctx, category_id)
+    }
+
+    pub fn correct_score(
+        ctx: Context<CorrectScore>,
+        trade_id_hash: [u8; 32],
+        score: u16,
+        confidence_bps: u16,
+        score_hash: [u8; 32],
+        reason_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::reputation::correct_score(
+            ctx,
+            trade_id_hash,
+            score,
+            confidence_bps,
+            score_hash,
+            reason_hash,
+        )
+    }
+
+    pub fn migrate_reputation_track(ctx: Context<MigrateReputationTrack>) -> Result<()> {
+        instructions::reputation::migrate_reputation_track(ctx)
+    }
+
+    pub fn migrate_protocol_config(ctx: Context<MigrateProtocolConfig>) -> Result<()> {
+        instructions::migration::migrate_protocol_config(ctx)
+    }
+
+    pub fn migrate_reputation_state(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migration::migrate_reputation_state(ctx)
+    }
+
+    pub fn migrate_trade_signal(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migration::migrate_trade_signal(ctx)
+    }
+
+    pub fn migrate_revenue_split(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migration::migrate_revenue_split(ctx)
+    }
+
+    pub fn migrate_verification_record(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migration::migrate_verification_record(ctx)
+    }
+
+    pub fn migrate_distribution_receipt(ctx: Context<MigrateDistributionReceipt>) -> Result<()> {
+        instructions::migration::migrate_distribution_receipt(ctx)
+    }
+
+    pub fn close_reputation_epoch(ctx: Context<CloseReputationEpoch>) -> Result<()> {
+        instructions::reputation::close_reputation_epoch(ctx)
+    }
+
+    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        instructions::staking::stake(ctx, amount)
+    }
+
+    pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+        instructions::staking::request_unstake(ctx, amount)
+    }
+
+    pub fn withdraw_unstaked(ctx: Context<WithdrawUnstaked>) -> Result<()> {
+        instructions::staking::withdraw_unstaked(ctx)
+    }
+
+    pub fn slash_stake(
+        ctx: Context<SlashStake>,
+        amount: u64,
+        reason_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::staking::slash_stake(ctx, amount, reason_hash)
+    }
+
+    pub fn distribute_settlement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeSettlement<'info>>,
         reference: [u8; 32],
         amount: u64,
     ) -> Result<()> {
         instructions::revenue::distribute_settlement(ctx, reference, amount)
     }
+
+    pub fn distribute_settlement_sol<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeSettlementSol<'info>>,
+        reference: [u8; 32],
+        amount: u64,
+    ) -> Result<()> {
+        instructions::revenue::distribute_settlement_sol(ctx, reference, amount)
+    }
+
+    pub fn accrue_settlement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AccrueSettlement<'info>>,
+        reference: [u8; 32],
+        amount: u64,
+    ) -> Result<()> {
+        instructions::claims::accrue_settlement(ctx, reference, amount)
+    }
+
+    pub fn claim(ctx: Context<Claim>, amount: u64) -> Result<()> {
+        instructions::claims::claim(ctx, amount)
+    }
+
+    pub fn open_reserve_vault(
+        ctx: Context<OpenReserveVault>,
+        scope: Pubkey,
+        lockup_period: i64,
+    ) -> Result<()> {
+        instructions::reserve::open_reserve_vault(ctx, scope, lockup_period)
+    }
+
+    pub fn set_reserve_lockup(ctx: Context<SetReserveLockup>, lockup_period: i64) -> Result<()> {
+        instructions::reserve::set_reserve_lockup(ctx, lockup_period)
+    }
+
+    pub fn sync_reserve(ctx: Context<SyncReserve>) -> Result<()> {
+        instructions::reserve::sync_reserve_balance(ctx)
+    }
+
+    pub fn withdraw_reserve(ctx: Context<WithdrawReserve>, amount: u64) -> Result<()> {
+        instructions::reserve::withdraw_reserve(ctx, amount)
+    }
+
+    pub fn compensate_from_reserve(
+        ctx: Context<CompensateFromReserve>,
+        amount: u64,
+        reason_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::reserve::compensate_from_reserve(ctx, amount, reason_hash)
+    }
+
+    pub fn open_vesting_escrow(ctx: Context<OpenVestingEscrow>) -> Result<()> {
+        instructions::vesting::open_vesting_escrow(ctx)
+    }
+
+    pub fn release_vesting(ctx: Context<ReleaseVesting>) -> Result<()> {
+        instructions::vesting::release_vesting(ctx)
+    }
+
+    pub fn clawback_vesting(ctx: Context<ClawbackVesting>, amount: u64) -> Result<()> {
+        instructions::vesting::clawback_vesting(ctx, amount)
+    }
+
+    pub fn distribute_settlement_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeSettlementBatch<'info>>,
+        entries: Vec<SettlementBatchEntry>,
+    ) -> Result<()> {
+        instructions::revenue::distribute_settlement_batch(ctx, entries)
+    }
 }
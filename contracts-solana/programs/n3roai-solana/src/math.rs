@@ -0,0 +1,296 @@
+use anchor_lang::solana_program::keccak;
+
+use crate::constants::{MAX_BPS, SCORE_HISTOGRAM_BUCKETS};
+
+const Q32_SHIFT: u32 = 32;
+const Q32_ONE: u128 = 1 << Q32_SHIFT;
+
+pub const DECAY_FRACTION_BITS: u32 = 16;
+
+/// Marks an unused `ReputationState::score_window` slot. Occupied slots hold the
+/// score plus one, so zeroed accounts start with an empty window.
+pub const EMPTY_WINDOW_SLOT: u16 = 0;
+
+/// Fixed-point scale of `ReputationState::score_mean`; `score_m2` carries its square.
+pub const SCORE_STAT_SCALE: i128 = 1_000_000;
+
+const MAX_HALVINGS: i64 = 16;
+
+const MERKLE_NODE_PREFIX: u8 = 1;
+
+// 2^(-1 / 2^k) in Q32 fixed point for k = 1..=16.
+const HALF_LIFE_ROOTS_Q32: [u128; DECAY_FRACTION_BITS as usize] = [
+    3_037_000_500,
+    3_611_622_603,
+    3_938_502_376,
+    4_112_874_773,
+    4_202_935_003,
+    4_248_701_965,
+    4_271_771_996,
+    4_283_353_945,
+    4_289_156_690,
+    4_292_061_010,
+    4_293_513_907,
+    4_294_240_540,
+    4_294_603_903,
+    4_294_785_595,
+    4_294_876_445,
+    4_294_921_870,
+];
+
+/// Returns `score * 2^(-elapsed / half_life)` rounded down. Whole half-lives are
+/// applied as shifts and the remainder is quantized to `DECAY_FRACTION_BITS` bits.
+pub fn decay_score(score: u16, elapsed: i64, half_life: i64) -> u16 {
+    if half_life <= 0 || elapsed <= 0 || score == 0 {
+        return score;
+    }
+
+    let halvings = elapsed / half_life;
+    if halvings >= MAX_HALVINGS {
+        return 0;
+    }
+
+    let remainder = (elapsed % half_life) as u128;
+    let fraction = (remainder << DECAY_FRACTION_BITS) / (half_life as u128);
+
+    let mut factor = Q32_ONE;
+    for (index, root) in HALF_LIFE_ROOTS_Q32.iter().enumerate() {
+        let bit = DECAY_FRACTION_BITS - 1 - index as u32;
+        if (fraction >> bit) & 1 == 1 {
+            factor = (factor * root) >> Q32_SHIFT;
+        }
+    }
+
+    let decayed = ((score as u128 * factor) >> Q32_SHIFT) >> halvings;
+    decayed as u16
+}
+
+/// The score an update contributes: `score * confidence_bps / MAX_BPS`, rounded down.
+pub fn effective_score(score: u16, confidence_bps: u16) -> u16 {
+    (score as u32 * confidence_bps as u32 / MAX_BPS as u32) as u16
+}
+
+/// One EMA step: `(previous * (MAX_BPS - alpha) + effective * alpha) / MAX_BPS`, rounded down.
+pub fn ema_update(previous: u16, effective: u16, alpha_bps: u16) -> u16 {
+    let alpha = alpha_bps.min(MAX_BPS) as u32;
+    ((previous as u32 * (MAX_BPS as u32 - alpha) + effective as u32 * alpha) / MAX_BPS as u32)
+        as u16
+}
+
+/// Mean of the occupied slots of a score window, rounded down; `None` if all are empty.
+pub fn window_mean(slots: &[u16]) -> Option<u16> {
+    let (sum, count) = slots
+        .iter()
+        .filter(|slot| **slot != EMPTY_WINDOW_SLOT)
+        .fold((0u32, 0u32), |(sum, count), slot| {
+            (sum + *slot as u32 - 1, count + 1)
+        });
+    if count == 0 {
+        return None;
+    }
+    Some((sum / count) as u16)
+}
+
+/// Posterior mean in bps of a Beta model fed with confidence-weighted evidence: each
+/// update adds `confidence_bps` of evidence, `score / MAX_BPS` of it successful. Priors
+/// use the same unit, so `MAX_BPS` is one full-confidence observation.
+pub fn beta_mean(
+    total_weighted_score: u128,
+    total_weight: u64,
+    prior_alpha: u32,
+    prior_beta: u32,
+) -> u16 {
+    let numerator = prior_alpha as u128 * MAX_BPS as u128 + total_weighted_score;
+    let denominator = prior_alpha as u128 + prior_beta as u128 + total_weight as u128;
+    if denominator == 0 {
+        return 0;
+    }
+    (numerator / denominator).min(MAX_BPS as u128) as u16
+}
+
+/// Returns `(1 - alpha)^steps` in bps, i.e. how much of an EMA input survives
+/// `steps` later updates.
+pub fn ema_retention_bps(alpha_bps: u16, steps: u64) -> u16 {
+    let max = MAX_BPS as u64;
+    let mut base = MAX_BPS.saturating_sub(alpha_bps) as u64;
+    let mut retention = max;
+    let mut exponent = steps;
+
+    while exponent > 0 && retention > 0 {
+        if exponent & 1 == 1 {
+            retention = retention * base / max;
+        }
+        base = base * base / max;
+        exponent >>= 1;
+    }
+
+    retention as u16
+}
+
+/// Index of the lower confidence-weighted median of votes ordered by score. Each weight
+/// is capped at the ballot's median confidence, so no single vote holds half the weight.
+pub fn weighted_median_index(confidences: &[u16]) -> Option<usize> {
+    if confidences.is_empty() {
+        return None;
+    }
+
+    let mut sorted = confidences.to_vec();
+    sorted.sort_unstable();
+    let cap = sorted[(sorted.len() - 1) / 2] as u32;
+
+    let total: u32 = confidences.iter().map(|c| (*c as u32).min(cap)).sum();
+    if total == 0 {
+        return Some((confidences.len() - 1) / 2);
+    }
+
+    let mut cumulative = 0u32;
+    for (index, confidence) in confidences.iter().enumerate() {
+        cumulative += (*confidence as u32).min(cap);
+        if cumulative * 2 >= total {
+            return Some(index);
+        }
+    }
+
+    None
+}
+
+/// Welford update adding `score` to a sample of `count` scores. Returns the new
+/// `(mean, m2)` with the mean scaled by `SCORE_STAT_SCALE` and m2 by its square.
+pub fn welford_add(mean: u64, m2: u128, count: u32, score: u16) -> (u64, u128) {
+    let value = score as i128 * SCORE_STAT_SCALE;
+    let delta = value - mean as i128;
+    let next_mean = mean as i128 + delta / (count as i128 + 1);
+    let spread = (delta * (value - next_mean)).max(0) as u128;
+    (next_mean as u64, m2.saturating_add(spread))
+}
+
+/// Inverse of `welford_add`: removes `score` from a sample of `count` scores.
+pub fn welford_remove(mean: u64, m2: u128, count: u32, score: u16) -> (u64, u128) {
+    if count <= 1 {
+        return (0, 0);
+    }
+
+    let value = score as i128 * SCORE_STAT_SCALE;
+    let previous_mean = ((mean as i128 * count as i128 - value) / (count as i128 - 1)).max(0);
+    let spread = ((value - previous_mean) * (value - mean as i128)).max(0) as u128;
+    (previous_mean as u64, m2.saturating_sub(spread))
+}
+
+/// Histogram bucket of a score; buckets are equal-width and the top one includes `MAX_BPS`.
+pub fn score_bucket(score: u16) -> usize {
+    let width = MAX_BPS as usize / SCORE_HISTOGRAM_BUCKETS;
+    (score as usize / width).min(SCORE_HISTOGRAM_BUCKETS - 1)
+}
+
+/// Parents are keccak256(0x01 || min(a, b) || max(a, b)), so proofs carry no direction bits.
+pub fn verify_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
+    let mut node = leaf;
+    for sibling in proof {
+        let (left, right) = if node <= *sibling {
+            (node, *sibling)
+        } else {
+            (*sibling, node)
+        };
+        node = keccak::hashv(&[&[MERKLE_NODE_PREFIX], &left, &right]).to_bytes();
+    }
+    node == *root
+}
+
+// Fixed vectors shared with the TypeScript mirrors in src/blockchain/solana-program.ts.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash32(hex: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (index, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn decay_score_vectors() {
+        assert_eq!(decay_score(10_000, 3_600, 3_600), 5_000);
+        assert_eq!(decay_score(10_000, 1_800, 3_600), 7_071);
+        assert_eq!(decay_score(8_000, 5_400, 3_600), 2_828);
+        assert_eq!(decay_score(9_999, 1, 86_400), 9_999);
+        assert_eq!(decay_score(7_777, 100_000, 86_400), 3_486);
+        assert_eq!(decay_score(10_000, 16 * 3_600, 3_600), 0);
+        assert_eq!(decay_score(10_000, 3_600, 0), 10_000);
+    }
+
+    #[test]
+    fn ema_update_vectors() {
+        assert_eq!(ema_update(5_000, 8_000, 2_000), 5_600);
+        assert_eq!(ema_update(7_071, 0, 2_000), 5_656);
+        assert_eq!(ema_update(1_234, 9_876, 12_000), 9_876);
+    }
+
+    #[test]
+    fn window_mean_vectors() {
+        assert_eq!(window_mean(&[0, 101, 201, 0]), Some(150));
+        assert_eq!(window_mean(&[1, 2, 4]), Some(1));
+        assert_eq!(window_mean(&[0, 0]), None);
+    }
+
+    #[test]
+    fn beta_mean_vectors() {
+        assert_eq!(beta_mean(0, 0, 10_000, 10_000), 5_000);
+        assert_eq!(beta_mean(240_000_000, 30_000, 10_000, 10_000), 6_800);
+        assert_eq!(beta_mean(0, 0, 0, 0), 0);
+    }
+
+    #[test]
+    fn ema_retention_bps_vectors() {
+        assert_eq!(ema_retention_bps(2_000, 0), 10_000);
+        assert_eq!(ema_retention_bps(2_000, 2), 6_400);
+        assert_eq!(ema_retention_bps(2_000, 5), 3_276);
+        assert_eq!(ema_retention_bps(10_000, 3), 0);
+    }
+
+    #[test]
+    fn weighted_median_index_vectors() {
+        assert_eq!(weighted_median_index(&[]), None);
+        assert_eq!(weighted_median_index(&[5_000, 5_000, 5_000]), Some(1));
+        assert_eq!(weighted_median_index(&[1_000, 3_000, 3_000]), Some(1));
+        assert_eq!(
+            weighted_median_index(&[3_000, 3_000, 3_000, 1_000, 1_000]),
+            Some(1)
+        );
+        // A full-confidence outlier at either end is capped to the median confidence.
+        assert_eq!(weighted_median_index(&[10_000, 1_000, 1_000]), Some(1));
+        assert_eq!(weighted_median_index(&[1_000, 1_000, 10_000]), Some(1));
+        assert_eq!(
+            weighted_median_index(&[2_000, 9_000, 9_000, 1_000]),
+            Some(1)
+        );
+        assert_eq!(weighted_median_index(&[1_000, 1_000]), Some(0));
+    }
+
+    #[test]
+    fn welford_vectors() {
+        let first = welford_add(0, 0, 0, 4_000);
+        assert_eq!(first, (4_000_000_000, 0));
+        let second = welford_add(first.0, first.1, 1, 6_000);
+        assert_eq!(second, (5_000_000_000, 2_000_000_000_000_000_000));
+        let third = welford_add(second.0, second.1, 2, 8_000);
+        assert_eq!(third, (6_000_000_000, 8_000_000_000_000_000_000));
+
+        assert_eq!(welford_remove(third.0, third.1, 3, 8_000), second);
+        assert_eq!(welford_remove(first.0, first.1, 1, 4_000), (0, 0));
+    }
+
+    #[test]
+    fn verify_proof_vectors() {
+        let leaves = [[0x11u8; 32], [0x22u8; 32], [0x33u8; 32]];
+        let parent = hash32("39b74deb1be11fa6539dcbe7b676432d2f947f3a4b21b202ce3ce67936c5f33d");
+        let root = hash32("4f50001bbab66ab4665e469acc5faf6bf54c3045bb0cd6884ea3d257fc4aecbf");
+
+        assert!(verify_proof(leaves[0], &[leaves[1], leaves[2]], &root));
+        assert!(verify_proof(leaves[1], &[leaves[0], leaves[2]], &root));
+        assert!(verify_proof(leaves[2], &[parent], &root));
+        assert!(!verify_proof(leaves[0], &[leaves[2], leaves[1]], &root));
+        assert!(!verify_proof(leaves[2], &[], &root));
+    }
+}
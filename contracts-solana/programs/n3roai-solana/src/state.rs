@@ -1,6 +1,10 @@
 use anchor_lang::prelude::*;
 
-use crate::constants::MAX_URI_LEN;
+use crate::constants::{
+    MAX_BALLOT_VOTES, MAX_FEE_TIERS, MAX_REFERRAL_LEVELS, MAX_SCORE_WINDOW, MAX_SPLIT_RECIPIENTS,
+    MAX_UNSTAKE_REQUESTS, MAX_URI_LEN, MAX_VESTING_TRANCHES, RISK_FLAG_BITS,
+    SCORE_HISTOGRAM_BUCKETS, VOLUME_WINDOW_DAYS,
+};
 
 #[account]
 pub struct IdentityRegistryState {
@@ -44,10 +48,49 @@ pub struct ProtocolConfig {
     pub paused: bool,
     pub vault_authority_bump: u8,
     pub bump: u8,
+    pub score_half_life: i64,
+    pub score_quorum: u8,
+    pub score_ballot_window: i64,
+    pub arbiter: Pubkey,
+    pub challenge_window: i64,
+    pub challenge_bond: u64,
+    pub min_oracle_stake: u64,
+    pub min_signaler_stake: u64,
+    pub unstake_cooldown: i64,
+    pub reputation_epoch_length: i64,
+    pub signal_reveal_window: i64,
+    pub aggregation_model: u8,
+    pub score_window_size: u8,
+    pub beta_prior_alpha: u32,
+    pub beta_prior_beta: u32,
+    pub aggregation_version: u32,
+    pub aggregation_updated_at: i64,
+    pub sol_treasury: Pubkey,
+    pub require_signal_commit: bool,
 }
 
 impl ProtocolConfig {
-    pub const SPACE: usize = 8 + 32 + 32 + 32 + 32 + 2 + 2 + 2 + 8 + 1 + 1 + 1 + 1 + 1;
+    pub const LEGACY_SPACE: usize = 8 + 32 + 32 + 32 + 32 + 2 + 2 + 2 + 8 + 1 + 1 + 1 + 1 + 1;
+    pub const SPACE: usize = Self::LEGACY_SPACE
+        + 8 // score_half_life
+        + 1 // score_quorum
+        + 8 // score_ballot_window
+        + 32 // arbiter
+        + 8 // challenge_window
+        + 8 // challenge_bond
+        + 8 // min_oracle_stake
+        + 8 // min_signaler_stake
+        + 8 // unstake_cooldown
+        + 8 // reputation_epoch_length
+        + 8 // signal_reveal_window
+        + 1 // aggregation_model
+        + 1 // score_window_size
+        + 4 // beta_prior_alpha
+        + 4 // beta_prior_beta
+        + 4 // aggregation_version
+        + 8 // aggregation_updated_at
+        + 32 // sol_treasury
+        + 1; // require_signal_commit
 }
 
 #[account]
@@ -63,6 +106,31 @@ impl RoleAssignment {
     pub const SPACE: usize = 8 + 32 + 1 + 1 + 8 + 1;
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct UnstakeRequest {
+    pub amount: u64,
+    pub available_at: i64,
+}
+
+impl UnstakeRequest {
+    pub const SPACE: usize = 8 + 8;
+}
+
+#[account]
+pub struct MemberStake {
+    pub member: Pubkey,
+    pub amount: u64,
+    pub pending: Vec<UnstakeRequest>,
+    pub slashed_total: u64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl MemberStake {
+    pub const SPACE: usize =
+        8 + 32 + 8 + 4 + MAX_UNSTAKE_REQUESTS * UnstakeRequest::SPACE + 8 + 8 + 1;
+}
+
 #[account]
 pub struct VerificationRecord {
     pub agent: Pubkey,
@@ -73,10 +141,12 @@ pub struct VerificationRecord {
     pub evidence_hash: [u8; 32],
     pub policy_hash: [u8; 32],
     pub bump: u8,
+    pub suspension_reason_hash: [u8; 32],
 }
 
 impl VerificationRecord {
-    pub const SPACE: usize = 8 + 32 + 1 + 32 + 8 + 8 + 32 + 32 + 1;
+    pub const LEGACY_SPACE: usize = 8 + 32 + 1 + 32 + 8 + 8 + 32 + 32 + 1;
+    pub const SPACE: usize = Self::LEGACY_SPACE + 32;
 }
 
 #[account]
@@ -89,13 +159,91 @@ pub struct RevenueSplitConfig {
     pub reserve_vault: Pubkey,
     pub reserve_bps: u16,
     pub bump: u8,
+    pub vesting_duration: i64,
+    pub recipients: Vec<SplitRecipient>,
 }
 
 impl RevenueSplitConfig {
-    pub const SPACE: usize = 8 + 32 + 32 + 2 + 32 + 2 + 32 + 2 + 1;
+    pub const LEGACY_SPACE: usize = 8 + 32 + 32 + 2 + 32 + 2 + 32 + 2 + 1;
+    pub const SPACE: usize =
+        Self::LEGACY_SPACE + 8 + 4 + MAX_SPLIT_RECIPIENTS * SplitRecipient::SPACE;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct SplitRecipient {
+    pub recipient: Pubkey,
+    pub bps: u16,
+    pub label_hash: [u8; 32],
+}
+
+impl SplitRecipient {
+    pub const SPACE: usize = 32 + 2 + 32;
+}
+
+#[account]
+pub struct SignalCommitment {
+    pub agent: Pubkey,
+    pub signaler: Pubkey,
+    pub commitment: [u8; 32],
+    pub committed_at: i64,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl SignalCommitment {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct SignalBatch {
+    pub agent: Pubkey,
+    pub reporter: Pubkey,
+    pub root: [u8; 32],
+    pub leaf_count: u32,
+    pub scored_count: u32,
+    pub submitted_at: i64,
+    pub scored: Vec<u8>,
+    pub bump: u8,
+}
+
+impl SignalBatch {
+    pub fn space(leaf_count: u32) -> usize {
+        8 + 32 + 32 + 32 + 4 + 4 + 8 + 4 + (leaf_count as usize).div_ceil(8) + 1
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct CompressedSignalLeaf {
+    pub leaf_index: u32,
+    pub trade_id_hash: [u8; 32],
+    pub result_hash: [u8; 32],
+    pub context_hash: [u8; 32],
+    pub risk_flags: u8,
+    pub category: u16,
+    // Committed with the root; there is no signal account for `report_trade_pnl`.
+    pub pnl_reported: bool,
+    pub notional: u64,
+    pub realized_pnl: i64,
+    pub holding_time: i64,
+}
+
+/// Per-agent index of scored trade ids, so a trade is scored once whether it arrives as a signal
+/// account, a batch entry or a compressed leaf. The header is followed by `count` sorted entries,
+/// each the first 8 bytes of a trade id hash; every scored trade grows the account by one entry.
+#[account]
+pub struct ScoredTrades {
+    pub agent: Pubkey,
+    pub count: u32,
+    pub bump: u8,
+}
+
+impl ScoredTrades {
+    pub const SPACE: usize = 8 + 32 + 4 + 1;
+    pub const ENTRY_SPACE: usize = 8;
 }
 
 #[account]
+#[derive(Default)]
 pub struct TradeSignal {
     pub agent: Pubkey,
     pub trade_id_hash: [u8; 32],
@@ -111,10 +259,189 @@ pub struct TradeSignal {
     pub oracle: Pubkey,
     pub score_submitted_at: i64,
     pub bump: u8,
+    pub revealed_at: i64,
+    pub category: u16,
+    pub applied_score: u16,
+    pub applied_confidence_bps: u16,
+    pub rolling_before: u16,
+    pub score_sequence: u64,
+    pub category_rolling_before: u16,
+    pub category_score_sequence: u64,
+    pub correction_count: u16,
+    pub corrected_by: Pubkey,
+    pub corrected_at: i64,
+    pub correction_reason_hash: [u8; 32],
+    pub previous_score: u16,
+    pub previous_confidence_bps: u16,
+    pub pnl_reported: bool,
+    pub notional: u64,
+    pub realized_pnl: i64,
+    pub holding_time: i64,
+    pub challenge_status: u8,
 }
 
 impl TradeSignal {
-    pub const SPACE: usize = 8 + 32 + 32 + 32 + 32 + 32 + 8 + 1 + 1 + 32 + 2 + 2 + 32 + 8 + 1;
+    pub const LEGACY_SPACE: usize =
+        8 + 32 + 32 + 32 + 32 + 32 + 8 + 1 + 1 + 32 + 2 + 2 + 32 + 8 + 1;
+    pub const SPACE: usize = Self::LEGACY_SPACE
+        + 8 // revealed_at
+        + 2 // category
+        + 2 // applied_score
+        + 2 // applied_confidence_bps
+        + 2 // rolling_before
+        + 8 // score_sequence
+        + 2 // category_rolling_before
+        + 8 // category_score_sequence
+        + 2 // correction_count
+        + 32 // corrected_by
+        + 8 // corrected_at
+        + 32 // correction_reason_hash
+        + 2 // previous_score
+        + 2 // previous_confidence_bps
+        + 1 // pnl_reported
+        + 8 // notional
+        + 8 // realized_pnl
+        + 8 // holding_time
+        + 1; // challenge_status
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct SignalBatchEntry {
+    pub trade_id_hash: [u8; 32],
+    pub result_hash: [u8; 32],
+    pub context_hash: [u8; 32],
+    pub risk_flags: u8,
+    pub category: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ScoreBatchEntry {
+    pub trade_id_hash: [u8; 32],
+    pub score: u16,
+    pub confidence_bps: u16,
+    pub score_hash: [u8; 32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct SettlementBatchEntry {
+    pub reference: [u8; 32],
+    pub amount: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct BallotVote {
+    pub oracle: Pubkey,
+    pub score: u16,
+    pub confidence_bps: u16,
+    pub score_hash: [u8; 32],
+    pub submitted_at: i64,
+}
+
+impl BallotVote {
+    pub const SPACE: usize = 32 + 2 + 2 + 32 + 8;
+}
+
+#[account]
+pub struct ScoreBallot {
+    pub trade_signal: Pubkey,
+    pub agent: Pubkey,
+    pub opened_by: Pubkey,
+    pub opened_at: i64,
+    pub finalized: bool,
+    pub votes: Vec<BallotVote>,
+    pub bump: u8,
+}
+
+impl ScoreBallot {
+    pub const SPACE: usize =
+        8 + 32 + 32 + 32 + 8 + 1 + 4 + MAX_BALLOT_VOTES * BallotVote::SPACE + 1;
+}
+
+#[account]
+pub struct ScoreChallenge {
+    pub trade_signal: Pubkey,
+    pub agent: Pubkey,
+    pub challenger: Pubkey,
+    pub bond: u64,
+    pub reason_hash: [u8; 32],
+    pub opened_at: i64,
+    pub status: u8,
+    pub resolution_hash: [u8; 32],
+    pub resolved_at: i64,
+    pub bump: u8,
+}
+
+impl ScoreChallenge {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 8 + 32 + 8 + 1 + 32 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct RiskFlagRule {
+    pub name_hash: [u8; 32],
+    pub penalty_bps: u16,
+    pub weight_bps: u16,
+    pub suspend_threshold: u16,
+}
+
+impl RiskFlagRule {
+    pub const SPACE: usize = 32 + 2 + 2 + 2;
+}
+
+#[account]
+#[derive(Default)]
+pub struct RiskFlagTable {
+    pub rules: [RiskFlagRule; RISK_FLAG_BITS],
+    pub suspension_window: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl RiskFlagTable {
+    pub const SPACE: usize = 8 + RISK_FLAG_BITS * RiskFlagRule::SPACE + 8 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct LeaderboardEntry {
+    pub agent: Pubkey,
+    pub rolling_score: u16,
+    pub score_count: u32,
+    pub updated_at: i64,
+}
+
+impl LeaderboardEntry {
+    pub const SPACE: usize = 32 + 2 + 4 + 8;
+}
+
+#[account]
+pub struct Leaderboard {
+    pub category: u16,
+    pub capacity: u16,
+    pub min_score_count: u32,
+    pub require_verified: bool,
+    pub updated_at: i64,
+    pub entries: Vec<LeaderboardEntry>,
+    pub bump: u8,
+}
+
+impl Leaderboard {
+    pub fn space(capacity: u16) -> usize {
+        8 + 2 + 2 + 4 + 1 + 8 + 4 + LeaderboardEntry::SPACE * capacity as usize + 1
+    }
+}
+
+#[account]
+pub struct ReputationCategory {
+    pub id: u16,
+    pub name_hash: [u8; 32],
+    pub score_alpha_bps: u16,
+    pub min_confidence_bps: u16,
+    pub active: bool,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl ReputationCategory {
+    pub const SPACE: usize = 8 + 2 + 32 + 2 + 2 + 1 + 8 + 1;
 }
 
 #[account]
@@ -128,10 +455,260 @@ pub struct ReputationState {
     pub score_count: u32,
     pub last_updated: i64,
     pub bump: u8,
+    pub category: u16,
+    pub score_sequence: u64,
+    pub flag_window_start: i64,
+    pub flag_counts: [u16; RISK_FLAG_BITS],
+    pub epoch: u64,
+    pub epoch_started_at: i64,
+    pub score_mean: u64,
+    pub score_m2: u128,
+    pub score_histogram: [u32; SCORE_HISTOGRAM_BUCKETS],
+    pub aggregation_version: u32,
+    pub window_start_sequence: u64,
+    pub score_window: [u16; MAX_SCORE_WINDOW],
+    pub epoch_flag_counts: [u16; RISK_FLAG_BITS],
 }
 
 impl ReputationState {
-    pub const SPACE: usize = 8 + 32 + 16 + 8 + 2 + 2 + 2 + 4 + 8 + 1;
+    pub const LEGACY_SPACE: usize = 8 + 32 + 16 + 8 + 2 + 2 + 2 + 4 + 8 + 1;
+    pub const SPACE: usize = Self::LEGACY_SPACE
+        + 2 // category
+        + 8 // score_sequence
+        + 8 // flag_window_start
+        + 2 * RISK_FLAG_BITS // flag_counts
+        + 8 // epoch
+        + 8 // epoch_started_at
+        + 8 // score_mean
+        + 16 // score_m2
+        + 4 * SCORE_HISTOGRAM_BUCKETS // score_histogram
+        + 4 // aggregation_version
+        + 8 // window_start_sequence
+        + 2 * MAX_SCORE_WINDOW // score_window
+        + 2 * RISK_FLAG_BITS; // epoch_flag_counts
+}
+
+#[account]
+pub struct ReputationSnapshot {
+    pub reputation_state: Pubkey,
+    pub agent: Pubkey,
+    pub category: u16,
+    pub epoch: u64,
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub rolling_score: u16,
+    pub score_count: u32,
+    pub weighted_average: u16,
+    pub flag_counts: [u16; RISK_FLAG_BITS],
+    pub closed_at: i64,
+    pub bump: u8,
+}
+
+impl ReputationSnapshot {
+    pub const SPACE: usize = 8 + 32 + 32 + 2 + 8 + 8 + 8 + 2 + 4 + 2 + 2 * RISK_FLAG_BITS + 8 + 1;
+}
+
+#[account]
+pub struct PnlStats {
+    pub agent: Pubkey,
+    pub trade_count: u32,
+    pub reported_count: u32,
+    pub win_count: u32,
+    pub win_rate_bps: u16,
+    pub cumulative_pnl: i64,
+    pub peak_pnl: i64,
+    pub max_drawdown: u64,
+    pub total_notional: u128,
+    pub total_holding_time: u64,
+    pub average_holding_time: i64,
+    pub last_updated: i64,
+    pub bump: u8,
+}
+
+impl PnlStats {
+    pub const SPACE: usize = 8 + 32 + 4 + 4 + 4 + 2 + 8 + 8 + 8 + 16 + 8 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct SettlementMintConfig {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub treasury_token_account: Pubkey,
+    pub fee_override: bool,
+    pub protocol_fee_bps: u16,
+    pub enabled: bool,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl SettlementMintConfig {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 1 + 2 + 1 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct ReserveTranche {
+    pub amount: u64,
+    pub opened_at: i64,
+    pub unlock_at: i64,
+}
+
+impl ReserveTranche {
+    pub const SPACE: usize = 8 + 8 + 8;
+}
+
+#[account]
+pub struct ReserveVault {
+    pub scope: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub lockup_period: i64,
+    pub current: ReserveTranche,
+    pub previous: ReserveTranche,
+    pub unlocked: u64,
+    pub tracked_balance: u64,
+    pub total_inflow: u64,
+    pub total_withdrawn: u64,
+    pub total_compensated: u64,
+    pub last_compensation_hash: [u8; 32],
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl ReserveVault {
+    pub const SPACE: usize =
+        8 + 32 + 32 + 32 + 32 + 8 + ReserveTranche::SPACE * 2 + 8 + 8 + 8 + 8 + 8 + 32 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct FeeTier {
+    pub require_verified: bool,
+    pub min_rolling_score: u16,
+    pub min_volume: u64,
+    pub fee_bps: u16,
+}
+
+impl FeeTier {
+    pub const SPACE: usize = 1 + 2 + 8 + 2;
+}
+
+#[account]
+pub struct FeeSchedule {
+    pub tiers: Vec<FeeTier>,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl FeeSchedule {
+    pub const SPACE: usize = 8 + 4 + MAX_FEE_TIERS * FeeTier::SPACE + 8 + 1;
+}
+
+#[account]
+pub struct ReferralProgram {
+    pub level_bps: Vec<u16>,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl ReferralProgram {
+    pub const SPACE: usize = 8 + 4 + MAX_REFERRAL_LEVELS * 2 + 8 + 1;
+}
+
+#[account]
+pub struct ReferralAccount {
+    pub referrer: Pubkey,
+    pub parent: Pubkey,
+    pub registered_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl ReferralAccount {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct SettlementVolume {
+    pub agent: Pubkey,
+    pub mint: Pubkey,
+    pub daily_volume: [u64; VOLUME_WINDOW_DAYS],
+    pub last_day: i64,
+    pub bump: u8,
+}
+
+impl SettlementVolume {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 * VOLUME_WINDOW_DAYS + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct VestingTranche {
+    pub remaining: u64,
+    pub end: i64,
+}
+
+impl VestingTranche {
+    pub const SPACE: usize = 8 + 8;
+}
+
+#[account]
+pub struct VestingEscrow {
+    pub agent: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub locked: u64,
+    pub vested: u64,
+    pub last_update: i64,
+    pub total_deposited: u64,
+    pub total_released: u64,
+    pub total_clawed_back: u64,
+    pub tranches: Vec<VestingTranche>,
+    pub bump: u8,
+}
+
+impl VestingEscrow {
+    pub const SPACE: usize = 8
+        + 32
+        + 32
+        + 32
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 4
+        + MAX_VESTING_TRANCHES * VestingTranche::SPACE
+        + 1;
+}
+
+#[account]
+pub struct ClaimLedger {
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub outstanding: u64,
+    pub total_accrued: u64,
+    pub total_claimed: u64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl ClaimLedger {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct ClaimableBalance {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub total_accrued: u64,
+    pub total_claimed: u64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl ClaimableBalance {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1;
 }
 
 #[account]
@@ -142,8 +719,12 @@ pub struct DistributionReceipt {
     pub operator: Pubkey,
     pub distributed_at: i64,
     pub bump: u8,
+    pub mint: Pubkey,
+    pub transfer_fee: u64,
+    pub protocol_fee_bps: u16,
 }
 
 impl DistributionReceipt {
-    pub const SPACE: usize = 8 + 32 + 32 + 8 + 32 + 8 + 1;
+    pub const LEGACY_SPACE: usize = 8 + 32 + 32 + 8 + 32 + 8 + 1;
+    pub const SPACE: usize = Self::LEGACY_SPACE + 32 + 8 + 2;
 }
@@ -0,0 +1,273 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token::spl_token;
+use n3roai_solana::constants::{
+    CHALLENGE_REJECTED, CHALLENGE_SEED, CHALLENGE_UPHELD, CHALLENGE_VAULT_SEED, LEADERBOARD_SEED,
+    PNL_STATS_SEED, REPUTATION_SEED, RISK_FLAG_TABLE_SEED, ROLE_ORACLE, ROLE_SIGNALER,
+    SCORED_TRADES_SEED, SIGNAL_SEED, VERIFICATION_SEED,
+};
+use n3roai_solana::errors::ErrorCode;
+use n3roai_solana::state::{PnlStats, ReputationState, ScoreChallenge, TradeSignal};
+use n3roai_solana::{accounts, instruction};
+
+use common::{error, pda, role_assignment, Agent, Bank, Protocol};
+
+const BOND: u64 = 50;
+const TRADE_ID: [u8; 32] = [9; 32];
+
+/// A scored signal with PnL, challenged by the agent's owner.
+struct Challenged {
+    bank: Bank,
+    protocol: Protocol,
+    agent: Agent,
+    arbiter: Pubkey,
+    signal: Pubkey,
+    challenge: Pubkey,
+    challenge_vault: Pubkey,
+    challenger_tokens: Pubkey,
+}
+
+impl Challenged {
+    fn new() -> Self {
+        let mut bank = Bank::new();
+        let protocol = Protocol::new(&mut bank, 0);
+        let arbiter = bank.wallet();
+        bank.process(protocol.admin_update(), instruction::SetArbiter { arbiter })
+            .unwrap();
+        bank.process(
+            protocol.admin_update(),
+            instruction::SetChallengeConfig {
+                challenge_window: 1_000,
+                challenge_bond: BOND,
+            },
+        )
+        .unwrap();
+
+        let signaler = bank.wallet();
+        let oracle = bank.wallet();
+        protocol.set_role(&mut bank, ROLE_SIGNALER, signaler);
+        protocol.set_role(&mut bank, ROLE_ORACLE, oracle);
+        let agent = Agent::register(&mut bank);
+        let signal = pda(&[SIGNAL_SEED, agent.identity.as_ref(), &TRADE_ID]);
+
+        bank.process(
+            accounts::SubmitSignal {
+                protocol_config: protocol.config,
+                signaler,
+                role_assignment: role_assignment(ROLE_SIGNALER, signaler),
+                member_stake: None,
+                agent_identity: agent.identity,
+                category: None,
+                trade_signal: signal,
+                system_program: system_program::ID,
+            },
+            instruction::SubmitSignal {
+                trade_id_hash: TRADE_ID,
+                result_hash: [1; 32],
+                context_hash: [2; 32],
+                risk_flags: 0,
+                category: 0,
+            },
+        )
+        .unwrap();
+        bank.process(
+            accounts::ReportTradePnl {
+                protocol_config: protocol.config,
+                signaler,
+                role_assignment: role_assignment(ROLE_SIGNALER, signaler),
+                member_stake: None,
+                agent_identity: agent.identity,
+                trade_signal: signal,
+            },
+            instruction::ReportTradePnl {
+                trade_id_hash: TRADE_ID,
+                notional: 1_000,
+                realized_pnl: 120,
+                holding_time: 60,
+            },
+        )
+        .unwrap();
+        let reputation_state = pda(&[REPUTATION_SEED, agent.identity.as_ref()]);
+        bank.process(
+            accounts::SubmitScore {
+                protocol_config: protocol.config,
+                oracle,
+                role_assignment: role_assignment(ROLE_ORACLE, oracle),
+                member_stake: None,
+                agent_identity: agent.identity,
+                trade_signal: signal,
+                verification_record: pda(&[VERIFICATION_SEED, agent.identity.as_ref()]),
+                reputation_state,
+                pnl_stats: pda(&[PNL_STATS_SEED, agent.identity.as_ref()]),
+                scored_trades: pda(&[SCORED_TRADES_SEED, agent.identity.as_ref()]),
+                epoch_snapshot: None,
+                category: None,
+                category_reputation_state: None,
+                category_epoch_snapshot: None,
+                leaderboard: pda(&[LEADERBOARD_SEED, &0u16.to_le_bytes()]),
+                category_leaderboard: pda(&[LEADERBOARD_SEED, &0u16.to_le_bytes()]),
+                risk_flag_table: pda(&[RISK_FLAG_TABLE_SEED]),
+                system_program: system_program::ID,
+            },
+            instruction::SubmitScore {
+                trade_id_hash: TRADE_ID,
+                score: 8_000,
+                confidence_bps: 9_000,
+                score_hash: [3; 32],
+            },
+        )
+        .unwrap();
+
+        let challenge = pda(&[CHALLENGE_SEED, signal.as_ref()]);
+        let challenge_vault = pda(&[CHALLENGE_VAULT_SEED, challenge.as_ref()]);
+        let challenger_tokens = bank.create_token_account(protocol.mint, agent.owner, BOND);
+        bank.process(
+            accounts::OpenChallenge {
+                protocol_config: protocol.config,
+                challenger: agent.owner,
+                role_assignment: None,
+                member_stake: None,
+                agent_identity: agent.identity,
+                trade_signal: signal,
+                score_challenge: challenge,
+                settlement_mint: protocol.mint,
+                challenger_token_account: challenger_tokens,
+                challenge_vault,
+                vault_authority: protocol.vault_authority,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            instruction::OpenChallenge {
+                trade_id_hash: TRADE_ID,
+                reason_hash: [4; 32],
+            },
+        )
+        .unwrap();
+
+        Self {
+            bank,
+            protocol,
+            agent,
+            arbiter,
+            signal,
+            challenge,
+            challenge_vault,
+            challenger_tokens,
+        }
+    }
+
+    fn resolve(&mut self, upheld: bool, treasury: Pubkey) -> std::result::Result<(), ProgramError> {
+        let identity = self.agent.identity;
+        self.bank.process(
+            accounts::ResolveChallenge {
+                protocol_config: self.protocol.config,
+                arbiter: self.arbiter,
+                agent_identity: identity,
+                trade_signal: self.signal,
+                score_challenge: self.challenge,
+                reputation_state: pda(&[REPUTATION_SEED, identity.as_ref()]),
+                category: None,
+                category_reputation_state: None,
+                pnl_stats: pda(&[PNL_STATS_SEED, identity.as_ref()]),
+                verification_record: pda(&[VERIFICATION_SEED, identity.as_ref()]),
+                leaderboard: pda(&[LEADERBOARD_SEED, &0u16.to_le_bytes()]),
+                category_leaderboard: pda(&[LEADERBOARD_SEED, &0u16.to_le_bytes()]),
+                risk_flag_table: pda(&[RISK_FLAG_TABLE_SEED]),
+                challenge_vault: self.challenge_vault,
+                settlement_mint_config: None,
+                vault_authority: self.protocol.vault_authority,
+                challenger: self.agent.owner,
+                challenger_token_account: self.challenger_tokens,
+                protocol_treasury_token_account: treasury,
+                token_program: spl_token::ID,
+            },
+            instruction::ResolveChallenge {
+                trade_id_hash: TRADE_ID,
+                upheld,
+                resolution_hash: [5; 32],
+            },
+        )
+    }
+
+    fn reputation(&self) -> ReputationState {
+        self.bank
+            .account(&pda(&[REPUTATION_SEED, self.agent.identity.as_ref()]))
+    }
+
+    fn pnl_stats(&self) -> PnlStats {
+        self.bank
+            .account(&pda(&[PNL_STATS_SEED, self.agent.identity.as_ref()]))
+    }
+}
+
+#[test]
+fn upheld_challenge_refunds_the_bond_and_takes_the_score_back() {
+    let mut challenged = Challenged::new();
+    assert_eq!(
+        challenged.bank.token_balance(&challenged.challenge_vault),
+        BOND
+    );
+    assert_eq!(challenged.reputation().score_count, 1);
+    assert_eq!(challenged.pnl_stats().cumulative_pnl, 120);
+
+    let owner_lamports = challenged.bank.lamports(&challenged.agent.owner);
+    let vault_lamports = challenged.bank.lamports(&challenged.challenge_vault);
+    let treasury = challenged.protocol.treasury;
+    challenged.resolve(true, treasury).unwrap();
+
+    let bank = &challenged.bank;
+    assert_eq!(bank.token_balance(&challenged.challenger_tokens), BOND);
+    assert_eq!(bank.token_balance(&treasury), 0);
+    assert!(!bank.exists(&challenged.challenge_vault));
+    assert_eq!(
+        bank.lamports(&challenged.agent.owner),
+        owner_lamports + vault_lamports
+    );
+
+    let reputation = challenged.reputation();
+    assert_eq!(reputation.score_count, 0);
+    assert_eq!(reputation.rolling_score, 0);
+    let pnl = challenged.pnl_stats();
+    assert_eq!(pnl.trade_count, 0);
+    assert_eq!(pnl.reported_count, 0);
+    assert_eq!(pnl.win_count, 0);
+    assert_eq!(pnl.cumulative_pnl, 0);
+    assert_eq!(pnl.total_notional, 0);
+
+    let challenge = bank.account::<ScoreChallenge>(&challenged.challenge);
+    assert_eq!(challenge.status, CHALLENGE_UPHELD);
+    let signal = bank.account::<TradeSignal>(&challenged.signal);
+    assert_eq!(signal.challenge_status, CHALLENGE_UPHELD);
+}
+
+#[test]
+fn rejected_challenge_pays_the_bond_to_the_protocol_treasury() {
+    let mut challenged = Challenged::new();
+    let stray =
+        challenged
+            .bank
+            .create_token_account(challenged.protocol.mint, Pubkey::new_unique(), 0);
+    assert_eq!(
+        challenged.resolve(false, stray),
+        Err(error(ErrorCode::InvalidTreasuryAccount))
+    );
+
+    let treasury = challenged.protocol.treasury;
+    challenged.resolve(false, treasury).unwrap();
+
+    let bank = &challenged.bank;
+    assert_eq!(bank.token_balance(&treasury), BOND);
+    assert_eq!(bank.token_balance(&challenged.challenger_tokens), 0);
+    assert!(!bank.exists(&challenged.challenge_vault));
+    assert_eq!(challenged.reputation().score_count, 1);
+    assert_eq!(challenged.pnl_stats().trade_count, 1);
+    let challenge = bank.account::<ScoreChallenge>(&challenged.challenge);
+    assert_eq!(challenge.status, CHALLENGE_REJECTED);
+
+    // The bond vault closed with the first resolution.
+    assert!(challenged.resolve(true, treasury).is_err());
+}
@@ -0,0 +1,222 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_program;
+use anchor_spl::token::{spl_token, TokenAccount};
+use n3roai_solana::constants::{
+    CLAIMABLE_SEED, CLAIM_LEDGER_SEED, FEE_SCHEDULE_SEED, RECEIPT_SEED, REFERRAL_PROGRAM_SEED,
+    ROLE_REVENUE_OPERATOR, SETTLEMENT_VOLUME_SEED,
+};
+use n3roai_solana::errors::ErrorCode;
+use n3roai_solana::state::{ClaimLedger, ClaimableBalance, DistributionReceipt};
+use n3roai_solana::{accounts, instruction};
+
+use common::{error, pda, role_assignment, Agent, Bank, Protocol};
+
+const FUNDED: u64 = 1_500_000;
+
+struct Accruals {
+    bank: Bank,
+    protocol: Protocol,
+    agent: Agent,
+    operator: Pubkey,
+    platform: Pubkey,
+    treasury_owner: Pubkey,
+}
+
+impl Accruals {
+    /// A 5% protocol fee and 10% to the platform, with `FUNDED` tokens in the vault.
+    fn new() -> Self {
+        let mut bank = Bank::new();
+        let protocol = Protocol::new(&mut bank, 500);
+        bank.create_token_account_at(
+            protocol.settlement_vault,
+            protocol.mint,
+            protocol.vault_authority,
+            FUNDED,
+        );
+        let operator = bank.wallet();
+        protocol.set_role(&mut bank, ROLE_REVENUE_OPERATOR, operator);
+
+        let agent = Agent::register(&mut bank);
+        let platform = bank.wallet();
+        agent.set_split(
+            &mut bank,
+            &protocol,
+            instruction::SetSplit {
+                platform,
+                platform_bps: 1_000,
+                referrer: Pubkey::default(),
+                referrer_bps: 0,
+                reserve_vault: Pubkey::default(),
+                reserve_bps: 0,
+            },
+        );
+        let treasury_owner = bank.account::<TokenAccount>(&protocol.treasury).owner;
+
+        Self {
+            bank,
+            protocol,
+            agent,
+            operator,
+            platform,
+            treasury_owner,
+        }
+    }
+
+    fn claimable(&self, owner: Pubkey) -> Pubkey {
+        pda(&[
+            CLAIMABLE_SEED,
+            self.protocol.settlement_vault.as_ref(),
+            owner.as_ref(),
+        ])
+    }
+
+    fn ledger(&self) -> Pubkey {
+        pda(&[CLAIM_LEDGER_SEED, self.protocol.settlement_vault.as_ref()])
+    }
+
+    fn receipt(&self, reference: [u8; 32]) -> Pubkey {
+        pda(&[RECEIPT_SEED, self.agent.identity.as_ref(), &reference])
+    }
+
+    fn accrue(
+        &mut self,
+        reference: [u8; 32],
+        amount: u64,
+    ) -> std::result::Result<(), ProgramError> {
+        let identity = self.agent.identity;
+        let remaining = [self.agent.wallet, self.platform, self.treasury_owner]
+            .into_iter()
+            .map(|owner| AccountMeta::new(self.claimable(owner), false))
+            .collect();
+        self.bank.process_with_remaining(
+            accounts::AccrueSettlement {
+                protocol_config: self.protocol.config,
+                operator: self.operator,
+                role_assignment: role_assignment(ROLE_REVENUE_OPERATOR, self.operator),
+                agent_identity: identity,
+                split_config: self.agent.split_config(),
+                settlement_mint_config: None,
+                settlement_vault: self.protocol.settlement_vault,
+                vault_authority: self.protocol.vault_authority,
+                protocol_treasury_token_account: self.protocol.treasury,
+                reserve_wallet: Pubkey::new_unique(),
+                claim_ledger: self.ledger(),
+                fee_schedule: pda(&[FEE_SCHEDULE_SEED]),
+                referral_program: pda(&[REFERRAL_PROGRAM_SEED]),
+                verification_record: None,
+                reputation_state: None,
+                settlement_volume: pda(&[
+                    SETTLEMENT_VOLUME_SEED,
+                    identity.as_ref(),
+                    self.protocol.mint.as_ref(),
+                ]),
+                distribution_receipt: self.receipt(reference),
+                system_program: system_program::ID,
+            },
+            instruction::AccrueSettlement { reference, amount },
+            remaining,
+        )
+    }
+
+    fn claim(
+        &mut self,
+        owner: Pubkey,
+        destination: Pubkey,
+        amount: u64,
+    ) -> std::result::Result<(), ProgramError> {
+        self.bank.process(
+            accounts::Claim {
+                protocol_config: self.protocol.config,
+                owner,
+                claimable_balance: self.claimable(owner),
+                claim_ledger: self.ledger(),
+                settlement_vault: self.protocol.settlement_vault,
+                settlement_mint: self.protocol.mint,
+                vault_authority: self.protocol.vault_authority,
+                destination_token_account: destination,
+                token_program: spl_token::ID,
+            },
+            instruction::Claim { amount },
+        )
+    }
+}
+
+#[test]
+fn accrual_credits_every_share_without_moving_tokens() {
+    let mut accruals = Accruals::new();
+    accruals.accrue([1; 32], 1_000_000).unwrap();
+
+    let bank = &accruals.bank;
+    assert_eq!(
+        bank.token_balance(&accruals.protocol.settlement_vault),
+        FUNDED
+    );
+    assert_eq!(bank.token_balance(&accruals.protocol.treasury), 0);
+    for (owner, amount) in [
+        (accruals.agent.wallet, 850_000),
+        (accruals.platform, 100_000),
+        (accruals.treasury_owner, 50_000),
+    ] {
+        let balance = bank.account::<ClaimableBalance>(&accruals.claimable(owner));
+        assert_eq!(balance.owner, owner);
+        assert_eq!(balance.amount, amount);
+        assert_eq!(balance.total_accrued, amount);
+    }
+    let ledger = bank.account::<ClaimLedger>(&accruals.ledger());
+    assert_eq!(ledger.outstanding, 1_000_000);
+    assert_eq!(ledger.total_accrued, 1_000_000);
+    let receipt = bank.account::<DistributionReceipt>(&accruals.receipt([1; 32]));
+    assert_eq!(receipt.amount, 1_000_000);
+    assert_eq!(receipt.protocol_fee_bps, 500);
+
+    // A reference settles once, and later accruals must fit beside what is owed.
+    assert!(accruals.accrue([1; 32], 1_000).is_err());
+    assert_eq!(
+        accruals.accrue([2; 32], FUNDED - 1_000_000 + 1),
+        Err(error(ErrorCode::ClaimsUnderfunded))
+    );
+    accruals.accrue([2; 32], FUNDED - 1_000_000).unwrap();
+    let ledger = accruals.bank.account::<ClaimLedger>(&accruals.ledger());
+    assert_eq!(ledger.outstanding, FUNDED);
+}
+
+#[test]
+fn claims_pay_out_of_the_balance_and_the_ledger() {
+    let mut accruals = Accruals::new();
+    accruals.accrue([1; 32], 1_000_000).unwrap();
+    let platform = accruals.platform;
+    let destination =
+        accruals
+            .bank
+            .create_token_account(accruals.protocol.mint, Pubkey::new_unique(), 0);
+
+    assert_eq!(
+        accruals.claim(platform, destination, 100_001),
+        Err(error(ErrorCode::InsufficientClaimBalance))
+    );
+    let foreign_mint = accruals.bank.create_mint(6);
+    let stray = accruals
+        .bank
+        .create_token_account(foreign_mint, platform, 0);
+    assert_eq!(
+        accruals.claim(platform, stray, 1_000),
+        Err(error(ErrorCode::InvalidTokenMint))
+    );
+
+    accruals.claim(platform, destination, 60_000).unwrap();
+
+    let bank = &accruals.bank;
+    assert_eq!(bank.token_balance(&destination), 60_000);
+    assert_eq!(
+        bank.token_balance(&accruals.protocol.settlement_vault),
+        FUNDED - 60_000
+    );
+    let balance = bank.account::<ClaimableBalance>(&accruals.claimable(platform));
+    assert_eq!(balance.amount, 40_000);
+    assert_eq!(balance.total_claimed, 60_000);
+    let ledger = bank.account::<ClaimLedger>(&accruals.ledger());
+    assert_eq!(ledger.outstanding, 940_000);
+    assert_eq!(ledger.total_claimed, 60_000);
+}
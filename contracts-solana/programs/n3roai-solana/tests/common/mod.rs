@@ -0,0 +1,732 @@
+//! Native harness for instruction tests. Instructions run through the program's Anchor
+//! entrypoint against an in-memory account store, with System, SPL Token and Associated Token
+//! CPIs dispatched to their native processors and the clock and rent sysvars served from
+//! thread-local state.
+
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::sync::Once;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::{
+    self, ProgramResult, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER,
+};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::program_stubs::{self, SyscallStubs};
+use anchor_lang::solana_program::program_utils::limited_deserialize;
+use anchor_lang::solana_program::system_instruction::SystemInstruction;
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::spl_associated_token_account;
+use anchor_spl::token::spl_token;
+use n3roai_solana::constants::{
+    AGENT_SEED, IDENTITY_REGISTRY_SEED, PROTOCOL_CONFIG_SEED, ROLE_SEED, SETTLEMENT_MINT_SEED,
+    SETTLEMENT_VAULT_SEED, SPLIT_SEED, VAULT_AUTHORITY_SEED,
+};
+use n3roai_solana::state::{IdentityRegistryState, SplitRecipient};
+use n3roai_solana::{accounts, instruction};
+
+pub const LAMPORTS: u64 = 1_000_000_000_000;
+pub const START_TIME: i64 = 1_700_000_000;
+
+thread_local! {
+    static CLOCK: RefCell<Clock> = RefCell::new(Clock {
+        unix_timestamp: START_TIME,
+        ..Clock::default()
+    });
+    static CALLERS: RefCell<Vec<Pubkey>> = const { RefCell::new(Vec::new()) };
+    static RETURN_DATA: RefCell<Option<(Pubkey, Vec<u8>)>> = const { RefCell::new(None) };
+    static LOGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        LOGS.with(|logs| logs.borrow_mut().push(message.to_string()));
+    }
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let caller = CALLERS.with(|callers| *callers.borrow().last().unwrap());
+        let signers = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+
+        let mut infos = Vec::with_capacity(instruction.accounts.len());
+        for meta in &instruction.accounts {
+            let mut info = account_infos
+                .iter()
+                .find(|info| *info.key == meta.pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?
+                .clone();
+            if meta.is_signer && !info.is_signer && !signers.contains(&meta.pubkey) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if meta.is_writable && !info.is_writable {
+                return Err(ProgramError::InvalidArgument);
+            }
+            info.is_signer = meta.is_signer;
+            info.is_writable = meta.is_writable;
+            infos.push(info);
+        }
+
+        CALLERS.with(|callers| callers.borrow_mut().push(instruction.program_id));
+        let result = dispatch(&instruction.program_id, &infos, &instruction.data);
+        CALLERS.with(|callers| callers.borrow_mut().pop());
+        result
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        CLOCK.with(|clock| unsafe { *(var_addr as *mut Clock) = clock.borrow().clone() });
+        0
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        0
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        RETURN_DATA.with(|data| data.borrow().clone())
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        let caller = CALLERS.with(|callers| *callers.borrow().last().unwrap());
+        RETURN_DATA.with(|slot| *slot.borrow_mut() = Some((caller, data.to_vec())));
+    }
+}
+
+fn dispatch(program_id: &Pubkey, infos: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if *program_id == system_program::ID {
+        process_system(infos, data)
+    } else if *program_id == spl_token::ID {
+        spl_token::processor::Processor::process(program_id, infos, data)
+    } else if *program_id == spl_associated_token_account::ID {
+        spl_associated_token_account::processor::process_instruction(program_id, infos, data)
+    } else {
+        Err(ProgramError::IncorrectProgramId)
+    }
+}
+
+fn process_system(infos: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let instruction: SystemInstruction =
+        limited_deserialize(data, 1024).map_err(|_| ProgramError::InvalidInstructionData)?;
+    match instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let (from, to) = (&infos[0], &infos[1]);
+            if to.lamports() > 0 {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            move_lamports(from, to, lamports)?;
+            allocate(to, space)?;
+            assign(to, &owner)?;
+        }
+        SystemInstruction::Transfer { lamports } => move_lamports(&infos[0], &infos[1], lamports)?,
+        SystemInstruction::Allocate { space } => allocate(&infos[0], space)?,
+        SystemInstruction::Assign { owner } => assign(&infos[0], &owner)?,
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+    Ok(())
+}
+
+/// Only empty, system-owned accounts that sign can be allocated or assigned.
+fn require_unclaimed(info: &AccountInfo) -> ProgramResult {
+    if !info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !info.data_is_empty() || *info.owner != system_program::ID {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    Ok(())
+}
+
+fn allocate(info: &AccountInfo, space: u64) -> ProgramResult {
+    require_unclaimed(info)?;
+    info.realloc(space as usize, true)
+}
+
+fn assign(info: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if info.owner == owner {
+        return Ok(());
+    }
+    if !info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *info.owner != system_program::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+    info.assign(owner);
+    Ok(())
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if !from.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !from.data_is_empty() || *from.owner != system_program::ID {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let remaining = from
+        .lamports()
+        .checked_sub(lamports)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+#[derive(Clone)]
+struct StoredAccount {
+    lamports: u64,
+    data: Vec<u8>,
+    owner: Pubkey,
+    executable: bool,
+}
+
+/// In-memory ledger. A failed instruction leaves every account untouched.
+pub struct Bank {
+    accounts: HashMap<Pubkey, StoredAccount>,
+    pub payer: Pubkey,
+}
+
+impl Bank {
+    pub fn new() -> Self {
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            program_stubs::set_syscall_stubs(Box::new(Stubs));
+        });
+        CLOCK.with(|clock| clock.borrow_mut().unix_timestamp = START_TIME);
+
+        let mut bank = Self {
+            accounts: HashMap::new(),
+            payer: Pubkey::new_unique(),
+        };
+        for program in [
+            n3roai_solana::ID,
+            system_program::ID,
+            spl_token::ID,
+            spl_associated_token_account::ID,
+        ] {
+            bank.set(program, 1, Vec::new(), Pubkey::default(), true);
+        }
+        let rent = bincode_rent(&Rent::default());
+        bank.set(sysvar::rent::ID, 1, rent, sysvar::ID, false);
+        bank.fund(bank.payer);
+        bank
+    }
+
+    pub fn now(&self) -> i64 {
+        CLOCK.with(|clock| clock.borrow().unix_timestamp)
+    }
+
+    /// Moves the clock forward by `seconds`.
+    pub fn warp(&mut self, seconds: i64) {
+        CLOCK.with(|clock| clock.borrow_mut().unix_timestamp += seconds);
+    }
+
+    pub fn fund(&mut self, key: Pubkey) {
+        let account = self.accounts.entry(key).or_insert(StoredAccount {
+            lamports: 0,
+            data: Vec::new(),
+            owner: system_program::ID,
+            executable: false,
+        });
+        account.lamports += LAMPORTS;
+    }
+
+    /// A fresh system account with lamports to pay for what it creates.
+    pub fn wallet(&mut self) -> Pubkey {
+        let key = Pubkey::new_unique();
+        self.fund(key);
+        key
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.accounts.get(key).map_or(0, |account| account.lamports)
+    }
+
+    pub fn exists(&self, key: &Pubkey) -> bool {
+        self.accounts
+            .get(key)
+            .is_some_and(|account| account.lamports > 0 || !account.data.is_empty())
+    }
+
+    pub fn account<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let account = self.accounts.get(key).expect("account exists");
+        T::try_deserialize(&mut account.data.as_slice()).expect("account deserializes")
+    }
+
+    pub fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let key = Pubkey::new_unique();
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            mint_authority: Some(self.payer).into(),
+            supply: 0,
+            decimals,
+            is_initialized: true,
+            freeze_authority: None.into(),
+        }
+        .pack_into_slice(&mut data);
+        let lamports = Rent::default().minimum_balance(data.len());
+        self.set(key, lamports, data, spl_token::ID, false);
+        key
+    }
+
+    /// Creates a token account at `key` holding `amount`, minted out of thin air.
+    pub fn create_token_account_at(
+        &mut self,
+        key: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+    ) {
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        let lamports = Rent::default().minimum_balance(data.len());
+        self.set(key, lamports, data, spl_token::ID, false);
+
+        let mint_account = self.accounts.get_mut(&mint).expect("mint exists");
+        let mut state = spl_token::state::Mint::unpack(&mint_account.data).unwrap();
+        state.supply += amount;
+        state.pack_into_slice(&mut mint_account.data);
+    }
+
+    pub fn create_token_account(&mut self, mint: Pubkey, owner: Pubkey, amount: u64) -> Pubkey {
+        let key = Pubkey::new_unique();
+        self.create_token_account_at(key, mint, owner, amount);
+        key
+    }
+
+    /// Adds `amount` to an existing token account, as a transfer from outside the program would.
+    pub fn mint_to(&mut self, key: Pubkey, amount: u64) {
+        let account = self.accounts.get_mut(&key).expect("token account exists");
+        let mut state = spl_token::state::Account::unpack(&account.data).unwrap();
+        state.amount += amount;
+        state.pack_into_slice(&mut account.data);
+        let mint = state.mint;
+
+        let mint_account = self.accounts.get_mut(&mint).expect("mint exists");
+        let mut state = spl_token::state::Mint::unpack(&mint_account.data).unwrap();
+        state.supply += amount;
+        state.pack_into_slice(&mut mint_account.data);
+    }
+
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        let account = self.accounts.get(key).expect("token account exists");
+        spl_token::state::Account::unpack(&account.data)
+            .expect("token account unpacks")
+            .amount
+    }
+
+    /// Runs one instruction. The signers are whichever metas the instruction marks as such.
+    pub fn process(
+        &mut self,
+        accounts: impl ToAccountMetas,
+        args: impl InstructionData,
+    ) -> std::result::Result<(), ProgramError> {
+        self.process_with_remaining(accounts, args, Vec::new())
+    }
+
+    pub fn process_with_remaining(
+        &mut self,
+        accounts: impl ToAccountMetas,
+        args: impl InstructionData,
+        remaining: Vec<AccountMeta>,
+    ) -> std::result::Result<(), ProgramError> {
+        let mut metas = accounts.to_account_metas(None);
+        metas.extend(remaining);
+        self.execute(&metas, &args.data())
+    }
+
+    fn execute(
+        &mut self,
+        metas: &[AccountMeta],
+        data: &[u8],
+    ) -> std::result::Result<(), ProgramError> {
+        let mut input = self.serialize(metas, data);
+        CALLERS.with(|callers| callers.borrow_mut().push(n3roai_solana::ID));
+        RETURN_DATA.with(|slot| *slot.borrow_mut() = None);
+        LOGS.with(|logs| logs.borrow_mut().clear());
+
+        // SAFETY: `input` is laid out like the loader's aligned serialization and outlives
+        // every account info borrowed from it.
+        let (program_id, infos, data) =
+            unsafe { entrypoint::deserialize(input.as_mut_ptr() as *mut u8) };
+        let result = n3roai_solana::entry(program_id, &infos, data);
+        CALLERS.with(|callers| callers.borrow_mut().pop());
+        if let Err(err) = result {
+            // Shown with the output of a failing test.
+            LOGS.with(|logs| logs.borrow().iter().for_each(|log| eprintln!("{log}")));
+            return Err(err);
+        }
+
+        let mut updates = Vec::new();
+        for info in &infos {
+            let stored = self.accounts.get(info.key);
+            let lamports = info.lamports();
+            let account_data = info.data.borrow().to_vec();
+            let changed = stored.map_or(lamports > 0 || !account_data.is_empty(), |stored| {
+                stored.lamports != lamports
+                    || stored.data != account_data
+                    || stored.owner != *info.owner
+            });
+            assert!(
+                !changed || info.is_writable,
+                "read-only account {} was modified",
+                info.key
+            );
+            updates.push((
+                *info.key,
+                StoredAccount {
+                    lamports,
+                    data: account_data,
+                    owner: *info.owner,
+                    executable: info.executable,
+                },
+            ));
+        }
+        for (key, account) in updates {
+            if account.lamports == 0 {
+                self.accounts.remove(&key);
+            } else {
+                self.accounts.insert(key, account);
+            }
+        }
+        Ok(())
+    }
+
+    /// Serializes accounts the way the BPF loader does, with the realloc headroom
+    /// `AccountInfo::realloc` relies on.
+    fn serialize(&self, metas: &[AccountMeta], data: &[u8]) -> Vec<u64> {
+        // Duplicates point back at the position of the key's first occurrence, which carries
+        // the union of its privileges.
+        let mut unique: Vec<AccountMeta> = Vec::new();
+        let mut first = Vec::new();
+        let mut positions = Vec::with_capacity(metas.len());
+        for (position, meta) in metas.iter().enumerate() {
+            match unique
+                .iter_mut()
+                .position(|seen| seen.pubkey == meta.pubkey)
+            {
+                Some(index) => {
+                    unique[index].is_signer |= meta.is_signer;
+                    unique[index].is_writable |= meta.is_writable;
+                    positions.push(Some(first[index]));
+                }
+                None => {
+                    unique.push(meta.clone());
+                    first.push(position);
+                    positions.push(None);
+                }
+            }
+        }
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(metas.len() as u64).to_le_bytes());
+        let mut next = 0;
+        for position in positions {
+            if let Some(index) = position {
+                bytes.push(index as u8);
+                bytes.extend_from_slice(&[0; 7]);
+                continue;
+            }
+
+            let meta = &unique[next];
+            next += 1;
+            let account = self
+                .accounts
+                .get(&meta.pubkey)
+                .cloned()
+                .unwrap_or(StoredAccount {
+                    lamports: 0,
+                    data: Vec::new(),
+                    owner: system_program::ID,
+                    executable: false,
+                });
+            bytes.push(NON_DUP_MARKER);
+            bytes.push(meta.is_signer as u8);
+            bytes.push(meta.is_writable as u8);
+            bytes.push(account.executable as u8);
+            bytes.extend_from_slice(&[0; 4]);
+            bytes.extend_from_slice(meta.pubkey.as_ref());
+            bytes.extend_from_slice(account.owner.as_ref());
+            bytes.extend_from_slice(&account.lamports.to_le_bytes());
+            bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(&account.data);
+            bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            bytes.resize(bytes.len().next_multiple_of(8), 0);
+            bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+        }
+        bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes.extend_from_slice(n3roai_solana::ID.as_ref());
+
+        let mut words = vec![0u64; bytes.len().div_ceil(size_of::<u64>())];
+        // SAFETY: `words` spans at least `bytes.len()` bytes.
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                words.as_mut_ptr() as *mut u8,
+                bytes.len(),
+            );
+        }
+        words
+    }
+
+    fn set(&mut self, key: Pubkey, lamports: u64, data: Vec<u8>, owner: Pubkey, executable: bool) {
+        self.accounts.insert(
+            key,
+            StoredAccount {
+                lamports,
+                data,
+                owner,
+                executable,
+            },
+        );
+    }
+}
+
+/// The protocol as `initialize_protocol` leaves it, with a legacy settlement vault and
+/// treasury in one SPL Token mint.
+pub struct Protocol {
+    pub admin: Pubkey,
+    pub config: Pubkey,
+    pub vault_authority: Pubkey,
+    pub mint: Pubkey,
+    pub settlement_vault: Pubkey,
+    pub treasury: Pubkey,
+}
+
+impl Protocol {
+    pub fn new(bank: &mut Bank, protocol_fee_bps: u16) -> Self {
+        let admin = bank.wallet();
+        let config = pda(&[PROTOCOL_CONFIG_SEED]);
+        let vault_authority = pda(&[VAULT_AUTHORITY_SEED]);
+        let mint = bank.create_mint(6);
+        let settlement_vault = bank.create_token_account(mint, vault_authority, 0);
+        let treasury = bank.create_token_account(mint, Pubkey::new_unique(), 0);
+
+        bank.process(
+            accounts::InitializeProtocol {
+                protocol_config: config,
+                vault_authority,
+                admin,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeProtocol {
+                settlement_mint: mint,
+                settlement_vault,
+                protocol_treasury_token_account: treasury,
+                protocol_fee_bps,
+                min_confidence_bps: 0,
+                score_alpha_bps: 2_000,
+                max_signal_age: 0,
+                require_verified_for_score: false,
+                enforce_settlement_token: true,
+            },
+        )
+        .expect("initialize_protocol");
+
+        Self {
+            admin,
+            config,
+            vault_authority,
+            mint,
+            settlement_vault,
+            treasury,
+        }
+    }
+
+    pub fn admin_update(&self) -> accounts::AdminUpdate {
+        accounts::AdminUpdate {
+            protocol_config: self.config,
+            admin: self.admin,
+        }
+    }
+
+    pub fn set_role(&self, bank: &mut Bank, role: u8, member: Pubkey) {
+        bank.process(
+            accounts::SetRole {
+                protocol_config: self.config,
+                admin: self.admin,
+                member,
+                role_assignment: role_assignment(role, member),
+                system_program: system_program::ID,
+            },
+            instruction::SetRole { role, active: true },
+        )
+        .expect("set_role");
+    }
+
+    /// Registers `mint` with a fresh vault and treasury and returns `(config, vault, treasury)`.
+    pub fn add_settlement_mint(&self, bank: &mut Bank, mint: Pubkey) -> (Pubkey, Pubkey, Pubkey) {
+        let settlement_mint_config = pda(&[SETTLEMENT_MINT_SEED, mint.as_ref()]);
+        let settlement_vault = pda(&[SETTLEMENT_VAULT_SEED, mint.as_ref()]);
+        let treasury = bank.create_token_account(mint, Pubkey::new_unique(), 0);
+        bank.process(
+            accounts::AddSettlementMint {
+                protocol_config: self.config,
+                mint,
+                settlement_mint_config,
+                settlement_vault,
+                vault_authority: self.vault_authority,
+                treasury_token_account: treasury,
+                admin: self.admin,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::AddSettlementMint {
+                fee_override: false,
+                protocol_fee_bps: 0,
+            },
+        )
+        .expect("add_settlement_mint");
+        (settlement_mint_config, settlement_vault, treasury)
+    }
+}
+
+/// A registered agent and the wallet that owns it.
+pub struct Agent {
+    pub identity: Pubkey,
+    pub owner: Pubkey,
+    pub wallet: Pubkey,
+}
+
+impl Agent {
+    /// Registers the next agent, creating the identity registry on first use.
+    pub fn register(bank: &mut Bank) -> Self {
+        let identity_registry = pda(&[IDENTITY_REGISTRY_SEED]);
+        if !bank.exists(&identity_registry) {
+            let admin = bank.payer;
+            bank.process(
+                accounts::InitializeIdentityRegistry {
+                    identity_registry,
+                    admin,
+                    system_program: system_program::ID,
+                },
+                instruction::InitializeIdentityRegistry {},
+            )
+            .expect("initialize_identity_registry");
+        }
+
+        let next_agent_id = bank
+            .account::<IdentityRegistryState>(&identity_registry)
+            .next_agent_id;
+        let identity = pda(&[AGENT_SEED, &next_agent_id.to_le_bytes()]);
+        let owner = bank.wallet();
+        let wallet = Pubkey::new_unique();
+        bank.process(
+            accounts::RegisterAgent {
+                identity_registry,
+                agent_identity: identity,
+                owner,
+                system_program: system_program::ID,
+            },
+            instruction::RegisterAgent {
+                agent_wallet: wallet,
+                uri: "https://agents.example/1".to_string(),
+                metadata_hash: [1; 32],
+            },
+        )
+        .expect("register_agent");
+
+        Self {
+            identity,
+            owner,
+            wallet,
+        }
+    }
+}
+
+impl Agent {
+    pub fn split_config(&self) -> Pubkey {
+        pda(&[SPLIT_SEED, self.identity.as_ref()])
+    }
+
+    fn split_accounts(&self, protocol: &Protocol) -> accounts::SetSplit {
+        accounts::SetSplit {
+            protocol_config: protocol.config,
+            agent_identity: self.identity,
+            split_config: self.split_config(),
+            owner: self.owner,
+            system_program: system_program::ID,
+        }
+    }
+
+    pub fn set_split(&self, bank: &mut Bank, protocol: &Protocol, split: instruction::SetSplit) {
+        bank.process(self.split_accounts(protocol), split)
+            .expect("set_split");
+    }
+
+    pub fn set_split_recipients(
+        &self,
+        bank: &mut Bank,
+        protocol: &Protocol,
+        recipients: Vec<SplitRecipient>,
+    ) {
+        bank.process(
+            self.split_accounts(protocol),
+            instruction::SetSplitRecipients { recipients },
+        )
+        .expect("set_split_recipients");
+    }
+}
+
+/// A split paying only the agent.
+pub fn agent_only_split() -> instruction::SetSplit {
+    instruction::SetSplit {
+        platform: Pubkey::default(),
+        platform_bps: 0,
+        referrer: Pubkey::default(),
+        referrer_bps: 0,
+        reserve_vault: Pubkey::default(),
+        reserve_bps: 0,
+    }
+}
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &n3roai_solana::ID).0
+}
+
+pub fn role_assignment(role: u8, member: Pubkey) -> Pubkey {
+    pda(&[ROLE_SEED, &[role], member.as_ref()])
+}
+
+/// `Rent` as the runtime stores it: three little-endian fields, no padding.
+fn bincode_rent(rent: &Rent) -> Vec<u8> {
+    let mut data = Vec::with_capacity(17);
+    data.extend_from_slice(&rent.lamports_per_byte_year.to_le_bytes());
+    data.extend_from_slice(&rent.exemption_threshold.to_le_bytes());
+    data.push(rent.burn_percent);
+    data
+}
+
+/// The program error an Anchor error code surfaces as.
+pub fn error(code: n3roai_solana::errors::ErrorCode) -> ProgramError {
+    anchor_lang::error::Error::from(code).into()
+}
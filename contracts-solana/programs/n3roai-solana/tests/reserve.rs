@@ -0,0 +1,211 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_program;
+use anchor_spl::associated_token::{get_associated_token_address, spl_associated_token_account};
+use anchor_spl::token::spl_token;
+use n3roai_solana::constants::{RESERVE_SEED, SECONDS_PER_DAY, VERIFICATION_SEED};
+use n3roai_solana::errors::ErrorCode;
+use n3roai_solana::state::ReserveVault;
+use n3roai_solana::{accounts, instruction};
+
+use common::{error, pda, Agent, Bank, Protocol};
+
+const LOCKUP: i64 = 30 * SECONDS_PER_DAY;
+
+struct Reserve {
+    key: Pubkey,
+    scope: Pubkey,
+    owner: Pubkey,
+    token_account: Pubkey,
+}
+
+impl Reserve {
+    fn at(scope: Pubkey, owner: Pubkey, mint: Pubkey) -> Self {
+        let key = pda(&[RESERVE_SEED, scope.as_ref(), mint.as_ref()]);
+        Self {
+            key,
+            scope,
+            owner,
+            token_account: get_associated_token_address(&key, &mint),
+        }
+    }
+
+    fn open(
+        &self,
+        bank: &mut Bank,
+        protocol: &Protocol,
+        agent_identity: Option<Pubkey>,
+        scope: Pubkey,
+    ) -> std::result::Result<(), ProgramError> {
+        let reserve = Self::at(scope, self.owner, protocol.mint);
+        bank.process(
+            accounts::OpenReserveVault {
+                protocol_config: protocol.config,
+                agent_identity,
+                mint: protocol.mint,
+                reserve: reserve.key,
+                reserve_token_account: reserve.token_account,
+                owner: self.owner,
+                token_program: spl_token::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: system_program::ID,
+            },
+            instruction::OpenReserveVault {
+                scope,
+                lockup_period: LOCKUP,
+            },
+        )
+    }
+
+    /// Tokens reach the reserve account by plain transfer and the crank books them.
+    fn deposit(&self, bank: &mut Bank, amount: u64) {
+        bank.mint_to(self.token_account, amount);
+        bank.process(
+            accounts::SyncReserve {
+                reserve: self.key,
+                reserve_token_account: self.token_account,
+            },
+            instruction::SyncReserve {},
+        )
+        .expect("sync_reserve");
+    }
+
+    fn withdraw(
+        &self,
+        bank: &mut Bank,
+        protocol: &Protocol,
+        owner: Pubkey,
+        destination: Pubkey,
+        amount: u64,
+    ) -> std::result::Result<(), ProgramError> {
+        bank.process(
+            accounts::WithdrawReserve {
+                protocol_config: protocol.config,
+                reserve: self.key,
+                reserve_token_account: self.token_account,
+                mint: protocol.mint,
+                verification_record: pda(&[VERIFICATION_SEED, self.scope.as_ref()]),
+                destination_token_account: destination,
+                owner,
+                token_program: spl_token::ID,
+            },
+            instruction::WithdrawReserve { amount },
+        )
+    }
+
+    fn state(&self, bank: &Bank) -> ReserveVault {
+        bank.account(&self.key)
+    }
+}
+
+#[test]
+fn agent_reserve_locks_inflows_until_the_lockup_passes() {
+    let mut bank = Bank::new();
+    let protocol = Protocol::new(&mut bank, 0);
+    let agent = Agent::register(&mut bank);
+    let reserve = Reserve::at(agent.identity, agent.owner, protocol.mint);
+
+    assert_eq!(
+        reserve.open(&mut bank, &protocol, Some(agent.identity), agent.owner),
+        Err(error(ErrorCode::InvalidReserveScope))
+    );
+
+    // Tokens sent before the reserve opened are booked as its first inflow.
+    bank.create_token_account_at(reserve.token_account, protocol.mint, reserve.key, 50_000);
+    reserve
+        .open(&mut bank, &protocol, Some(agent.identity), agent.identity)
+        .unwrap();
+    let opened_at = bank.now();
+    reserve.deposit(&mut bank, 25_000);
+
+    let state = reserve.state(&bank);
+    assert_eq!(state.owner, agent.owner);
+    assert_eq!(state.token_account, reserve.token_account);
+    assert_eq!(state.current.amount, 75_000);
+    assert_eq!(state.current.unlock_at, opened_at + LOCKUP);
+    assert_eq!(state.tracked_balance, 75_000);
+    assert_eq!(state.total_inflow, 75_000);
+
+    let destination = bank.create_token_account(protocol.mint, agent.owner, 0);
+    assert_eq!(
+        reserve.withdraw(&mut bank, &protocol, agent.owner, destination, 1),
+        Err(error(ErrorCode::ReserveLocked))
+    );
+    let stranger = bank.wallet();
+    assert_eq!(
+        reserve.withdraw(&mut bank, &protocol, stranger, destination, 1),
+        Err(error(ErrorCode::Unauthorized))
+    );
+
+    bank.warp(LOCKUP);
+    reserve
+        .withdraw(&mut bank, &protocol, agent.owner, destination, 60_000)
+        .unwrap();
+    assert_eq!(bank.token_balance(&destination), 60_000);
+    assert_eq!(bank.token_balance(&reserve.token_account), 15_000);
+    let state = reserve.state(&bank);
+    assert_eq!(state.current.amount, 0);
+    assert_eq!(state.unlocked, 15_000);
+    assert_eq!(state.tracked_balance, 15_000);
+    assert_eq!(state.total_withdrawn, 60_000);
+}
+
+#[test]
+fn compensation_draws_locked_tranches_newest_first() {
+    let mut bank = Bank::new();
+    let protocol = Protocol::new(&mut bank, 0);
+    let arbiter = bank.wallet();
+    bank.process(protocol.admin_update(), instruction::SetArbiter { arbiter })
+        .unwrap();
+    let platform = bank.wallet();
+    let reserve = Reserve::at(platform, platform, protocol.mint);
+    reserve.open(&mut bank, &protocol, None, platform).unwrap();
+
+    // 100k unlocks, 50k sits in the previous tranche and 10k in the current one.
+    reserve.deposit(&mut bank, 100_000);
+    bank.warp(LOCKUP);
+    reserve.deposit(&mut bank, 20_000);
+    bank.warp(LOCKUP / 2);
+    reserve.deposit(&mut bank, 30_000);
+    bank.warp(LOCKUP / 2);
+    reserve.deposit(&mut bank, 10_000);
+    let state = reserve.state(&bank);
+    assert_eq!(state.unlocked, 100_000);
+    assert_eq!(state.previous.amount, 50_000);
+    assert_eq!(state.current.amount, 10_000);
+
+    let victim = bank.create_token_account(protocol.mint, Pubkey::new_unique(), 0);
+    let compensate = |bank: &mut Bank, amount: u64| {
+        bank.process(
+            accounts::CompensateFromReserve {
+                protocol_config: protocol.config,
+                arbiter,
+                reserve: reserve.key,
+                reserve_token_account: reserve.token_account,
+                mint: protocol.mint,
+                destination_token_account: victim,
+                token_program: spl_token::ID,
+            },
+            instruction::CompensateFromReserve {
+                amount,
+                reason_hash: [4; 32],
+            },
+        )
+    };
+    assert_eq!(
+        compensate(&mut bank, 160_001),
+        Err(error(ErrorCode::InsufficientReserveBalance))
+    );
+    compensate(&mut bank, 70_000).unwrap();
+
+    assert_eq!(bank.token_balance(&victim), 70_000);
+    assert_eq!(bank.token_balance(&reserve.token_account), 90_000);
+    let state = reserve.state(&bank);
+    assert_eq!(state.current.amount, 0);
+    assert_eq!(state.previous.amount, 0);
+    assert_eq!(state.unlocked, 90_000);
+    assert_eq!(state.tracked_balance, 90_000);
+    assert_eq!(state.total_compensated, 70_000);
+    assert_eq!(state.last_compensation_hash, [4; 32]);
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_program;
+use n3roai_solana::constants::{
+    FEE_SCHEDULE_SEED, RECEIPT_SEED, REFERRAL_PROGRAM_SEED, ROLE_REVENUE_OPERATOR,
+    SETTLEMENT_VOLUME_SEED, SOL_VAULT_SEED,
+};
+use n3roai_solana::errors::ErrorCode;
+use n3roai_solana::state::{DistributionReceipt, SplitRecipient};
+use n3roai_solana::{accounts, instruction};
+
+use common::{error, pda, role_assignment, Agent, Bank, Protocol};
+
+struct SolSettlement {
+    bank: Bank,
+    protocol: Protocol,
+    agent: Agent,
+    operator: Pubkey,
+    sol_vault: Pubkey,
+    sol_treasury: Pubkey,
+    platform: Pubkey,
+    recipient: Pubkey,
+}
+
+impl SolSettlement {
+    /// A 5% protocol fee, 10% to the platform and 5% to one extra recipient.
+    fn new() -> Self {
+        let mut bank = Bank::new();
+        let protocol = Protocol::new(&mut bank, 500);
+        let sol_treasury = Pubkey::new_unique();
+        bank.process(
+            protocol.admin_update(),
+            instruction::SetSolTreasury { sol_treasury },
+        )
+        .unwrap();
+        let operator = bank.wallet();
+        protocol.set_role(&mut bank, ROLE_REVENUE_OPERATOR, operator);
+
+        let agent = Agent::register(&mut bank);
+        let platform = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        agent.set_split(
+            &mut bank,
+            &protocol,
+            instruction::SetSplit {
+                platform,
+                platform_bps: 1_000,
+                referrer: Pubkey::default(),
+                referrer_bps: 0,
+                reserve_vault: Pubkey::default(),
+                reserve_bps: 0,
+            },
+        );
+        agent.set_split_recipients(
+            &mut bank,
+            &protocol,
+            vec![SplitRecipient {
+                recipient,
+                bps: 500,
+                label_hash: [6; 32],
+            }],
+        );
+
+        let sol_vault = pda(&[SOL_VAULT_SEED]);
+        bank.fund(sol_vault);
+
+        Self {
+            bank,
+            protocol,
+            agent,
+            operator,
+            sol_vault,
+            sol_treasury,
+            platform,
+            recipient,
+        }
+    }
+
+    fn receipt(&self, reference: [u8; 32]) -> Pubkey {
+        pda(&[RECEIPT_SEED, self.agent.identity.as_ref(), &reference])
+    }
+
+    fn distribute(
+        &mut self,
+        reference: [u8; 32],
+        amount: u64,
+        protocol_treasury: Pubkey,
+    ) -> std::result::Result<(), ProgramError> {
+        let identity = self.agent.identity;
+        self.bank.process_with_remaining(
+            accounts::DistributeSettlementSol {
+                protocol_config: self.protocol.config,
+                operator: self.operator,
+                role_assignment: role_assignment(ROLE_REVENUE_OPERATOR, self.operator),
+                agent_identity: identity,
+                split_config: self.agent.split_config(),
+                sol_vault: self.sol_vault,
+                agent_wallet: self.agent.wallet,
+                platform_wallet: self.platform,
+                referrer_wallet: Pubkey::new_unique(),
+                reserve_wallet: Pubkey::new_unique(),
+                protocol_treasury,
+                fee_schedule: pda(&[FEE_SCHEDULE_SEED]),
+                referral_program: pda(&[REFERRAL_PROGRAM_SEED]),
+                verification_record: None,
+                reputation_state: None,
+                settlement_volume: pda(&[
+                    SETTLEMENT_VOLUME_SEED,
+                    identity.as_ref(),
+                    Pubkey::default().as_ref(),
+                ]),
+                distribution_receipt: self.receipt(reference),
+                system_program: system_program::ID,
+            },
+            instruction::DistributeSettlementSol { reference, amount },
+            vec![AccountMeta::new(self.recipient, false)],
+        )
+    }
+}
+
+#[test]
+fn sol_settlement_pays_every_share_from_the_vault() {
+    let mut settlement = SolSettlement::new();
+    let vault_before = settlement.bank.lamports(&settlement.sol_vault);
+    let treasury = settlement.sol_treasury;
+    settlement.distribute([1; 32], 1_000_000, treasury).unwrap();
+
+    let bank = &settlement.bank;
+    assert_eq!(bank.lamports(&settlement.agent.wallet), 800_000);
+    assert_eq!(bank.lamports(&settlement.platform), 100_000);
+    assert_eq!(bank.lamports(&settlement.recipient), 50_000);
+    assert_eq!(bank.lamports(&treasury), 50_000);
+    assert_eq!(
+        bank.lamports(&settlement.sol_vault),
+        vault_before - 1_000_000
+    );
+
+    let receipt = bank.account::<DistributionReceipt>(&settlement.receipt([1; 32]));
+    assert_eq!(receipt.amount, 1_000_000);
+    assert_eq!(receipt.mint, Pubkey::default());
+    assert_eq!(receipt.protocol_fee_bps, 500);
+    assert_eq!(receipt.transfer_fee, 0);
+
+    // A reference settles once.
+    assert!(settlement.distribute([1; 32], 1_000, treasury).is_err());
+}
+
+#[test]
+fn sol_settlement_rejects_a_short_vault_and_a_foreign_treasury() {
+    let mut settlement = SolSettlement::new();
+    let treasury = settlement.sol_treasury;
+    let vault = settlement.bank.lamports(&settlement.sol_vault);
+    let reserved = Rent::default().minimum_balance(0);
+
+    assert_eq!(
+        settlement.distribute([2; 32], vault - reserved + 1, treasury),
+        Err(error(ErrorCode::InsufficientVaultBalance))
+    );
+    assert_eq!(
+        settlement.distribute([2; 32], 1_000, Pubkey::new_unique()),
+        Err(error(ErrorCode::InvalidTreasuryAccount))
+    );
+    assert_eq!(settlement.bank.lamports(&settlement.agent.wallet), 0);
+
+    settlement
+        .distribute([2; 32], vault - reserved, treasury)
+        .unwrap();
+    assert_eq!(settlement.bank.lamports(&settlement.sol_vault), reserved);
+}
@@ -0,0 +1,213 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token::spl_token;
+use n3roai_solana::constants::{STAKE_SEED, STAKE_VAULT_SEED};
+use n3roai_solana::errors::ErrorCode;
+use n3roai_solana::state::MemberStake;
+use n3roai_solana::{accounts, instruction};
+
+use common::{error, pda, Bank, Protocol};
+
+struct Member {
+    key: Pubkey,
+    token_account: Pubkey,
+    stake: Pubkey,
+    vault: Pubkey,
+}
+
+impl Member {
+    fn new(bank: &mut Bank, mint: Pubkey, balance: u64) -> Self {
+        let key = bank.wallet();
+        Self {
+            key,
+            token_account: bank.create_token_account(mint, key, balance),
+            stake: pda(&[STAKE_SEED, key.as_ref()]),
+            vault: pda(&[STAKE_VAULT_SEED, key.as_ref()]),
+        }
+    }
+
+    fn stake(&self, bank: &mut Bank, protocol: &Protocol, mint: Pubkey, amount: u64) {
+        bank.process(
+            accounts::Stake {
+                protocol_config: protocol.config,
+                member: self.key,
+                member_stake: self.stake,
+                stake_mint: mint,
+                member_token_account: self.token_account,
+                stake_vault: self.vault,
+                vault_authority: protocol.vault_authority,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            instruction::Stake { amount },
+        )
+        .expect("stake");
+    }
+
+    fn request_unstake(&self, bank: &mut Bank, protocol: &Protocol, amount: u64) {
+        bank.process(
+            accounts::RequestUnstake {
+                protocol_config: protocol.config,
+                member: self.key,
+                member_stake: self.stake,
+            },
+            instruction::RequestUnstake { amount },
+        )
+        .expect("request_unstake");
+    }
+
+    fn withdraw(
+        &self,
+        bank: &mut Bank,
+        protocol: &Protocol,
+    ) -> std::result::Result<(), ProgramError> {
+        bank.process(
+            accounts::WithdrawUnstaked {
+                protocol_config: protocol.config,
+                member: self.key,
+                member_stake: self.stake,
+                stake_vault: self.vault,
+                vault_authority: protocol.vault_authority,
+                member_token_account: self.token_account,
+                token_program: spl_token::ID,
+            },
+            instruction::WithdrawUnstaked {},
+        )
+    }
+
+    fn slash(
+        &self,
+        bank: &mut Bank,
+        protocol: &Protocol,
+        settlement_mint_config: Option<Pubkey>,
+        treasury: Pubkey,
+        amount: u64,
+    ) -> std::result::Result<(), ProgramError> {
+        bank.process(
+            accounts::SlashStake {
+                protocol_config: protocol.config,
+                authority: protocol.admin,
+                member: self.key,
+                member_stake: self.stake,
+                stake_vault: self.vault,
+                settlement_mint_config,
+                vault_authority: protocol.vault_authority,
+                protocol_treasury_token_account: treasury,
+                token_program: spl_token::ID,
+            },
+            instruction::SlashStake {
+                amount,
+                reason_hash: [7; 32],
+            },
+        )
+    }
+}
+
+#[test]
+fn unstaked_tokens_release_after_the_cooldown() {
+    let mut bank = Bank::new();
+    let protocol = Protocol::new(&mut bank, 0);
+    bank.process(
+        protocol.admin_update(),
+        instruction::SetStakeConfig {
+            min_oracle_stake: 0,
+            min_signaler_stake: 0,
+            unstake_cooldown: 100,
+        },
+    )
+    .unwrap();
+
+    let member = Member::new(&mut bank, protocol.mint, 1_000);
+    member.stake(&mut bank, &protocol, protocol.mint, 1_000);
+    member.request_unstake(&mut bank, &protocol, 400);
+    assert_eq!(bank.account::<MemberStake>(&member.stake).amount, 600);
+
+    assert_eq!(
+        member.withdraw(&mut bank, &protocol),
+        Err(error(ErrorCode::NothingToWithdraw))
+    );
+    bank.warp(100);
+    member.withdraw(&mut bank, &protocol).unwrap();
+
+    let stake = bank.account::<MemberStake>(&member.stake);
+    assert!(stake.pending.is_empty());
+    assert_eq!(bank.token_balance(&member.token_account), 400);
+    assert_eq!(bank.token_balance(&member.vault), 600);
+}
+
+#[test]
+fn slashing_reaches_into_pending_unstakes() {
+    let mut bank = Bank::new();
+    let protocol = Protocol::new(&mut bank, 0);
+    let member = Member::new(&mut bank, protocol.mint, 1_000);
+    member.stake(&mut bank, &protocol, protocol.mint, 1_000);
+    member.request_unstake(&mut bank, &protocol, 300);
+    member.request_unstake(&mut bank, &protocol, 300);
+
+    member
+        .slash(&mut bank, &protocol, None, protocol.treasury, 600)
+        .unwrap();
+
+    let stake = bank.account::<MemberStake>(&member.stake);
+    assert_eq!(stake.amount, 0);
+    assert_eq!(stake.pending.len(), 2);
+    assert_eq!(stake.pending[0].amount, 300);
+    assert_eq!(stake.pending[1].amount, 100);
+    assert_eq!(stake.slashed_total, 600);
+    assert_eq!(bank.token_balance(&protocol.treasury), 600);
+    assert_eq!(bank.token_balance(&member.vault), 400);
+
+    assert_eq!(
+        member.slash(&mut bank, &protocol, None, protocol.treasury, 401),
+        Err(error(ErrorCode::InsufficientStake))
+    );
+}
+
+#[test]
+fn slashing_pays_the_treasury_registered_for_the_stake_mint() {
+    let mut bank = Bank::new();
+    let protocol = Protocol::new(&mut bank, 0);
+    let mint = bank.create_mint(9);
+    let (mint_config, _, treasury) = protocol.add_settlement_mint(&mut bank, mint);
+    bank.process(
+        protocol.admin_update(),
+        instruction::SetSettlementToken {
+            settlement_mint: mint,
+            enforce_settlement_token: true,
+        },
+    )
+    .unwrap();
+
+    let member = Member::new(&mut bank, mint, 500);
+    member.stake(&mut bank, &protocol, mint, 500);
+
+    assert_eq!(
+        member.slash(
+            &mut bank,
+            &protocol,
+            Some(mint_config),
+            protocol.treasury,
+            100
+        ),
+        Err(error(ErrorCode::InvalidTokenMint))
+    );
+    let stray = bank.create_token_account(mint, Pubkey::new_unique(), 0);
+    assert_eq!(
+        member.slash(&mut bank, &protocol, Some(mint_config), stray, 100),
+        Err(error(ErrorCode::InvalidTreasuryAccount))
+    );
+    assert_eq!(
+        member.slash(&mut bank, &protocol, None, treasury, 100),
+        Err(error(ErrorCode::InvalidTreasuryAccount))
+    );
+
+    member
+        .slash(&mut bank, &protocol, Some(mint_config), treasury, 100)
+        .unwrap();
+    assert_eq!(bank.token_balance(&treasury), 100);
+    assert_eq!(bank.token_balance(&member.vault), 400);
+}
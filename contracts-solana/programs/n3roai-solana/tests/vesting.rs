@@ -0,0 +1,290 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_program;
+use anchor_spl::token::spl_token;
+use n3roai_solana::constants::{
+    CLAIM_LEDGER_SEED, FEE_SCHEDULE_SEED, RECEIPT_SEED, REFERRAL_PROGRAM_SEED,
+    ROLE_REVENUE_OPERATOR, ROLE_VERIFICATION_OPERATOR, SECONDS_PER_DAY, SETTLEMENT_VOLUME_SEED,
+    STATUS_SUSPENDED, VERIFICATION_SEED, VESTING_SEED, VESTING_VAULT_SEED,
+};
+use n3roai_solana::errors::ErrorCode;
+use n3roai_solana::state::VestingEscrow;
+use n3roai_solana::{accounts, instruction};
+
+use common::{agent_only_split, error, pda, role_assignment, Agent, Bank, Protocol};
+
+const AMOUNT: u64 = 1_000_000;
+const DURATION: i64 = 10 * SECONDS_PER_DAY;
+
+/// An agent-only split and a vault holding `AMOUNT`; tests set vesting and open the escrow.
+struct Vesting {
+    bank: Bank,
+    protocol: Protocol,
+    agent: Agent,
+    operator: Pubkey,
+    escrow: Pubkey,
+    vesting_vault: Pubkey,
+    agent_tokens: Pubkey,
+    stray: Pubkey,
+}
+
+impl Vesting {
+    fn new() -> Self {
+        let mut bank = Bank::new();
+        let protocol = Protocol::new(&mut bank, 0);
+        bank.create_token_account_at(
+            protocol.settlement_vault,
+            protocol.mint,
+            protocol.vault_authority,
+            AMOUNT,
+        );
+        let operator = bank.wallet();
+        protocol.set_role(&mut bank, ROLE_REVENUE_OPERATOR, operator);
+
+        let agent = Agent::register(&mut bank);
+        agent.set_split(&mut bank, &protocol, agent_only_split());
+
+        let escrow = pda(&[
+            VESTING_SEED,
+            agent.identity.as_ref(),
+            protocol.mint.as_ref(),
+        ]);
+        let vesting_vault = pda(&[VESTING_VAULT_SEED, escrow.as_ref()]);
+        let agent_tokens = bank.create_token_account(protocol.mint, agent.wallet, 0);
+        let stray = bank.create_token_account(protocol.mint, Pubkey::new_unique(), 0);
+
+        Self {
+            bank,
+            protocol,
+            agent,
+            operator,
+            escrow,
+            vesting_vault,
+            agent_tokens,
+            stray,
+        }
+    }
+
+    fn set_vesting(&mut self) {
+        self.bank
+            .process(
+                accounts::SetSplitVesting {
+                    protocol_config: self.protocol.config,
+                    agent_identity: self.agent.identity,
+                    split_config: self.agent.split_config(),
+                    admin: self.protocol.admin,
+                },
+                instruction::SetSplitVesting {
+                    vesting_duration: DURATION,
+                },
+            )
+            .unwrap();
+    }
+
+    fn open_escrow(&mut self) {
+        let payer = self.bank.payer;
+        self.bank
+            .process(
+                accounts::OpenVestingEscrow {
+                    protocol_config: self.protocol.config,
+                    agent_identity: self.agent.identity,
+                    settlement_mint: self.protocol.mint,
+                    vesting_escrow: self.escrow,
+                    vesting_vault: self.vesting_vault,
+                    vault_authority: self.protocol.vault_authority,
+                    payer,
+                    token_program: spl_token::ID,
+                    system_program: system_program::ID,
+                },
+                instruction::OpenVestingEscrow {},
+            )
+            .unwrap();
+    }
+
+    fn distribute(
+        &mut self,
+        reference: [u8; 32],
+        escrow: bool,
+    ) -> std::result::Result<(), ProgramError> {
+        let identity = self.agent.identity;
+        let vault = self.protocol.settlement_vault;
+        self.bank.process(
+            accounts::DistributeSettlement {
+                protocol_config: self.protocol.config,
+                operator: self.operator,
+                role_assignment: role_assignment(ROLE_REVENUE_OPERATOR, self.operator),
+                agent_identity: identity,
+                split_config: self.agent.split_config(),
+                settlement_mint_config: None,
+                settlement_vault: vault,
+                settlement_mint: self.protocol.mint,
+                vault_authority: self.protocol.vault_authority,
+                claim_ledger: pda(&[CLAIM_LEDGER_SEED, vault.as_ref()]),
+                agent_token_account: self.agent_tokens,
+                vesting_escrow: escrow.then_some(self.escrow),
+                vesting_vault: escrow.then_some(self.vesting_vault),
+                platform_token_account: self.stray,
+                referrer_token_account: self.stray,
+                reserve_token_account: self.stray,
+                reserve: None,
+                protocol_treasury_token_account: self.protocol.treasury,
+                fee_schedule: pda(&[FEE_SCHEDULE_SEED]),
+                referral_program: pda(&[REFERRAL_PROGRAM_SEED]),
+                verification_record: None,
+                reputation_state: None,
+                settlement_volume: pda(&[
+                    SETTLEMENT_VOLUME_SEED,
+                    identity.as_ref(),
+                    self.protocol.mint.as_ref(),
+                ]),
+                distribution_receipt: pda(&[RECEIPT_SEED, identity.as_ref(), &reference]),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::DistributeSettlement {
+                reference,
+                amount: AMOUNT,
+            },
+        )
+    }
+
+    fn release(&mut self) -> std::result::Result<(), ProgramError> {
+        self.bank.process(
+            accounts::ReleaseVesting {
+                protocol_config: self.protocol.config,
+                agent_identity: self.agent.identity,
+                vesting_escrow: self.escrow,
+                vesting_vault: self.vesting_vault,
+                settlement_mint: self.protocol.mint,
+                vault_authority: self.protocol.vault_authority,
+                verification_record: self.verification_record(),
+                agent_token_account: self.agent_tokens,
+                token_program: spl_token::ID,
+            },
+            instruction::ReleaseVesting {},
+        )
+    }
+
+    fn clawback(&mut self, amount: u64) -> std::result::Result<(), ProgramError> {
+        self.bank.process(
+            accounts::ClawbackVesting {
+                protocol_config: self.protocol.config,
+                authority: self.protocol.admin,
+                agent_identity: self.agent.identity,
+                vesting_escrow: self.escrow,
+                vesting_vault: self.vesting_vault,
+                settlement_mint: self.protocol.mint,
+                vault_authority: self.protocol.vault_authority,
+                verification_record: self.verification_record(),
+                payer_token_account: self.stray,
+                token_program: spl_token::ID,
+            },
+            instruction::ClawbackVesting { amount },
+        )
+    }
+
+    fn suspend(&mut self) {
+        let operator = self.bank.wallet();
+        self.protocol
+            .set_role(&mut self.bank, ROLE_VERIFICATION_OPERATOR, operator);
+        self.bank
+            .process(
+                accounts::SetVerificationStatus {
+                    protocol_config: self.protocol.config,
+                    operator,
+                    role_assignment: role_assignment(ROLE_VERIFICATION_OPERATOR, operator),
+                    agent_identity: self.agent.identity,
+                    verification_record: self.verification_record(),
+                    system_program: system_program::ID,
+                },
+                instruction::SetVerificationStatus {
+                    status: STATUS_SUSPENDED,
+                    evidence_hash: [8; 32],
+                    policy_hash: [0; 32],
+                    expires_at: 0,
+                },
+            )
+            .unwrap();
+    }
+
+    fn verification_record(&self) -> Pubkey {
+        pda(&[VERIFICATION_SEED, self.agent.identity.as_ref()])
+    }
+
+    fn escrow(&self) -> VestingEscrow {
+        self.bank.account(&self.escrow)
+    }
+}
+
+#[test]
+fn escrowed_agent_share_releases_linearly() {
+    let mut vesting = Vesting::new();
+    vesting.set_vesting();
+    assert_eq!(
+        vesting.distribute([1; 32], false),
+        Err(error(ErrorCode::VestingEscrowRequired))
+    );
+
+    vesting.open_escrow();
+    let start = vesting.bank.now();
+    vesting.distribute([1; 32], true).unwrap();
+    assert_eq!(vesting.bank.token_balance(&vesting.agent_tokens), 0);
+    assert_eq!(vesting.bank.token_balance(&vesting.vesting_vault), AMOUNT);
+
+    // The tranche ends on the first day boundary at least `DURATION` out.
+    let escrow = vesting.escrow();
+    let end =
+        (start + DURATION + SECONDS_PER_DAY - 1).div_euclid(SECONDS_PER_DAY) * SECONDS_PER_DAY;
+    assert_eq!(escrow.locked, AMOUNT);
+    assert_eq!(escrow.tranches.len(), 1);
+    assert_eq!(escrow.tranches[0].end, end);
+
+    let elapsed = (end - start) / 4;
+    vesting.bank.warp(elapsed);
+    vesting.release().unwrap();
+    let released = (AMOUNT as u128 * elapsed as u128 / (end - start) as u128) as u64;
+    assert_eq!(vesting.bank.token_balance(&vesting.agent_tokens), released);
+    let escrow = vesting.escrow();
+    assert_eq!(escrow.locked, AMOUNT - released);
+    assert_eq!(escrow.vested, 0);
+    assert_eq!(escrow.total_released, released);
+    assert_eq!(vesting.release(), Err(error(ErrorCode::InvalidAmount)));
+
+    vesting.bank.warp(end - start);
+    vesting.release().unwrap();
+    assert_eq!(vesting.bank.token_balance(&vesting.agent_tokens), AMOUNT);
+    assert_eq!(vesting.bank.token_balance(&vesting.vesting_vault), 0);
+    assert!(vesting.escrow().tranches.is_empty());
+}
+
+#[test]
+fn suspension_blocks_release_and_allows_clawback() {
+    let mut vesting = Vesting::new();
+    vesting.set_vesting();
+    vesting.open_escrow();
+    vesting.distribute([1; 32], true).unwrap();
+    assert_eq!(
+        vesting.clawback(100),
+        Err(error(ErrorCode::AgentNotSuspended))
+    );
+
+    vesting.bank.warp(DURATION / 2);
+    vesting.suspend();
+    assert_eq!(vesting.release(), Err(error(ErrorCode::AgentSuspended)));
+    assert_eq!(
+        vesting.clawback(AMOUNT + 1),
+        Err(error(ErrorCode::InsufficientEscrowBalance))
+    );
+
+    // Unvested tokens go first, then vested ones that were never released.
+    vesting.clawback(AMOUNT - 1).unwrap();
+    let escrow = vesting.escrow();
+    assert_eq!(escrow.locked, 0);
+    assert!(escrow.tranches.is_empty());
+    assert_eq!(escrow.vested, 1);
+    assert_eq!(escrow.total_clawed_back, AMOUNT - 1);
+    assert_eq!(vesting.bank.token_balance(&vesting.stray), AMOUNT - 1);
+    assert_eq!(vesting.bank.token_balance(&vesting.vesting_vault), 1);
+    assert_eq!(vesting.bank.token_balance(&vesting.agent_tokens), 0);
+}
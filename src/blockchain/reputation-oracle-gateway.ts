@@ -1,3 +1,4 @@
+import { randomBytes } from "node:crypto";
 import {
   Connection,
   SystemProgram,
@@ -12,10 +13,17 @@ import { REPUTATION_ORACLE_ABI } from "./abis";
 import { createWalletChainClient } from "./client";
 import {
   ROLE_SIGNALER,
+  computeSignalCommitment,
+  decodeProtocolConfigAccount,
+  encodeCommitSignalData,
+  encodeRevealSignalData,
   encodeSubmitSignalData,
   findAgentIdentityPda,
+  findCategoryPda,
+  findMemberStakePda,
   findProtocolConfigPda,
   findRoleAssignmentPda,
+  findSignalCommitmentPda,
   findSignalPda,
   hexBytes32ToBuffer,
   parseSolanaKeypair,
@@ -29,6 +37,7 @@ type SignalInput = {
   resultHash: `0x${string}`;
   contextHash?: `0x${string}`;
   riskFlags?: number;
+  category?: number;
 };
 
 type EvmSignalRuntime = {
@@ -151,6 +160,7 @@ export class ReputationOracleGateway {
       "contextHash"
     );
     const riskFlags = sanitizeRiskFlags(input.riskFlags ?? this.solana.defaultRiskFlags);
+    const category = input.category ?? 0;
 
     const protocolConfigPda = findProtocolConfigPda(this.solana.programId);
     const agentIdentityPda = findAgentIdentityPda(this.solana.programId, agentId);
@@ -160,29 +170,102 @@ export class ReputationOracleGateway {
       this.solana.signaler.publicKey
     );
     const tradeSignalPda = findSignalPda(this.solana.programId, agentIdentityPda, tradeIdHash);
+    const memberStakePda = findMemberStakePda(this.solana.programId, this.solana.signaler.publicKey);
+    const [protocolConfigInfo, memberStakeInfo] = await this.solana.connection.getMultipleAccountsInfo([
+      protocolConfigPda,
+      memberStakePda
+    ]);
 
-    const instruction = new TransactionInstruction({
-      programId: this.solana.programId,
-      keys: [
-        { pubkey: protocolConfigPda, isSigner: false, isWritable: false },
-        { pubkey: this.solana.signaler.publicKey, isSigner: true, isWritable: true },
-        { pubkey: roleAssignmentPda, isSigner: false, isWritable: false },
-        { pubkey: agentIdentityPda, isSigner: false, isWritable: false },
-        { pubkey: tradeSignalPda, isSigner: false, isWritable: true },
-        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }
-      ],
-      data: encodeSubmitSignalData({
+    if (!protocolConfigInfo) {
+      throw new Error("Solana protocol config account is missing");
+    }
+    const protocolConfig = decodeProtocolConfigAccount(Buffer.from(protocolConfigInfo.data));
+
+    const signerKeys = [
+      { pubkey: protocolConfigPda, isSigner: false, isWritable: false },
+      { pubkey: this.solana.signaler.publicKey, isSigner: true, isWritable: true },
+      { pubkey: roleAssignmentPda, isSigner: false, isWritable: false },
+      {
+        pubkey: memberStakeInfo ? memberStakePda : this.solana.programId,
+        isSigner: false,
+        isWritable: false
+      },
+      { pubkey: agentIdentityPda, isSigner: false, isWritable: false }
+    ];
+    const categoryKey = {
+      pubkey: category > 0 ? findCategoryPda(this.solana.programId, category) : this.solana.programId,
+      isSigner: false,
+      isWritable: false
+    };
+
+    let txHash: string;
+    if (protocolConfig.requireSignalCommit) {
+      // The commit fixes the signal time on-chain before any trade data is public.
+      const salt = randomBytes(32);
+      const commitment = computeSignalCommitment({
+        agentIdentity: agentIdentityPda,
         tradeIdHash,
         resultHash,
         contextHash,
-        riskFlags
-      })
-    });
+        riskFlags,
+        category,
+        salt
+      });
+      const commitmentPda = findSignalCommitmentPda(this.solana.programId, agentIdentityPda, commitment);
 
-    const transaction = new Transaction().add(instruction);
-    const txHash = await sendAndConfirmTransaction(this.solana.connection, transaction, [this.solana.signaler], {
-      commitment: "confirmed"
-    });
+      await this.sendSolana(
+        new TransactionInstruction({
+          programId: this.solana.programId,
+          keys: [
+            ...signerKeys,
+            { pubkey: commitmentPda, isSigner: false, isWritable: true },
+            { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }
+          ],
+          data: encodeCommitSignalData(commitment)
+        })
+      );
+
+      txHash = await this.sendSolana(
+        new TransactionInstruction({
+          programId: this.solana.programId,
+          keys: [
+            ...signerKeys,
+            categoryKey,
+            { pubkey: commitmentPda, isSigner: false, isWritable: true },
+            { pubkey: tradeSignalPda, isSigner: false, isWritable: true },
+            { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }
+          ],
+          data: encodeRevealSignalData({
+            commitment,
+            tradeIdHash,
+            resultHash,
+            contextHash,
+            riskFlags,
+            category,
+            salt
+          })
+        })
+      );
+    } else {
+      txHash = await this.sendSolana(
+        new TransactionInstruction({
+          programId: this.solana.programId,
+          keys: [
+            ...signerKeys,
+            categoryKey,
+            { pubkey: tradeSignalPda, isSigner: false, isWritable: true },
+            { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }
+          ],
+          data: encodeSubmitSignalData({
+            tradeIdHash,
+            resultHash,
+            contextHash,
+            riskFlags,
+            category
+          })
+        })
+      );
+    }
 
     this.logger.info("Signal submitted onchain", {
       chain: "solana",
@@ -192,6 +275,17 @@ export class ReputationOracleGateway {
       txHash
     });
   }
+
+  private async sendSolana(instruction: TransactionInstruction): Promise<string> {
+    if (!this.solana) {
+      throw new Error("Solana signal runtime is not configured");
+    }
+
+    const transaction = new Transaction().add(instruction);
+    return sendAndConfirmTransaction(this.solana.connection, transaction, [this.solana.signaler], {
+      commitment: "confirmed"
+    });
+  }
 }
 
 function sanitizeRiskFlags(value: number): number {
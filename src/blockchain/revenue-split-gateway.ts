@@ -17,14 +17,27 @@ import {
   ROLE_REVENUE_OPERATOR,
   decodeAgentIdentityAccount,
   decodeProtocolConfigAccount,
+  decodeReferralAccount,
+  decodeReferralProgramAccount,
   decodeRevenueSplitConfigAccount,
+  decodeSettlementMintConfigAccount,
   encodeDistributeSettlementData,
   findAgentIdentityPda,
+  findClaimLedgerPda,
   findDistributionReceiptPda,
+  findFeeSchedulePda,
   findProtocolConfigPda,
+  findReferralAccountPda,
+  findReferralProgramPda,
+  findReputationStatePda,
   findRoleAssignmentPda,
+  findSettlementMintConfigPda,
+  findSettlementVolumePda,
   findSplitConfigPda,
   findVaultAuthorityPda,
+  findVerificationRecordPda,
+  findVestingEscrowPda,
+  findVestingVaultPda,
   hexBytes32ToBuffer,
   parseSolanaKeypair,
   parseSolanaPublicKey,
@@ -49,6 +62,7 @@ type SolanaDistributionRuntime = {
   programId: PublicKey;
   operator: ReturnType<typeof parseSolanaKeypair>;
   tokenProgramId: PublicKey;
+  settlementMint?: PublicKey;
 };
 
 export class RevenueSplitGateway {
@@ -108,7 +122,10 @@ export class RevenueSplitGateway {
       operator: parseSolanaKeypair(solana.operatorSecretKey, "DISTRIBUTION_SOLANA_OPERATOR_SECRET_KEY"),
       tokenProgramId: solana.tokenProgramId
         ? parseSolanaPublicKey(solana.tokenProgramId, "DISTRIBUTION_SOLANA_TOKEN_PROGRAM_ID")
-        : DEFAULT_SPL_TOKEN_PROGRAM_ID
+        : DEFAULT_SPL_TOKEN_PROGRAM_ID,
+      settlementMint: solana.settlementMint
+        ? parseSolanaPublicKey(solana.settlementMint, "DISTRIBUTION_SOLANA_SETTLEMENT_MINT")
+        : undefined
     };
   }
 
@@ -186,6 +203,37 @@ export class RevenueSplitGateway {
     const agentIdentity = decodeAgentIdentityAccount(Buffer.from(agentIdentityInfo.data));
     const splitConfig = decodeRevenueSplitConfigAccount(Buffer.from(splitConfigInfo.data));
 
+    // With a registered mint, distribute from its own vault and treasury; otherwise use the legacy config route.
+    let settlementMint = protocolConfig.settlementMint;
+    let settlementVault = protocolConfig.settlementVault;
+    let treasuryTokenAccount = protocolConfig.protocolTreasuryTokenAccount;
+    let settlementMintConfigPda = this.solana.programId;
+    if (this.solana.settlementMint) {
+      settlementMintConfigPda = findSettlementMintConfigPda(this.solana.programId, this.solana.settlementMint);
+      const settlementMintConfigInfo = await this.solana.connection.getAccountInfo(settlementMintConfigPda);
+      if (!settlementMintConfigInfo) {
+        throw new Error(`Solana settlement mint is not registered: ${this.solana.settlementMint.toBase58()}`);
+      }
+      const settlementMintConfig = decodeSettlementMintConfigAccount(Buffer.from(settlementMintConfigInfo.data));
+      if (!settlementMintConfig.enabled) {
+        throw new Error(`Solana settlement mint is disabled: ${this.solana.settlementMint.toBase58()}`);
+      }
+      settlementMint = settlementMintConfig.mint;
+      settlementVault = settlementMintConfig.vault;
+      treasuryTokenAccount = settlementMintConfig.treasuryTokenAccount;
+    }
+
+    // Token-2022 mints need the Token-2022 program configured; the program rejects a mismatch in the CPI.
+    const settlementMintInfo = await this.solana.connection.getAccountInfo(settlementMint);
+    if (!settlementMintInfo) {
+      throw new Error(`Missing settlement mint: ${settlementMint.toBase58()}`);
+    }
+    if (!settlementMintInfo.owner.equals(this.solana.tokenProgramId)) {
+      throw new Error(
+        `Settlement mint ${settlementMint.toBase58()} is owned by ${settlementMintInfo.owner.toBase58()}, not the configured token program`
+      );
+    }
+
     const roleAssignmentPda = findRoleAssignmentPda(
       this.solana.programId,
       ROLE_REVENUE_OPERATOR,
@@ -194,48 +242,72 @@ export class RevenueSplitGateway {
     const vaultAuthorityPda = findVaultAuthorityPda(this.solana.programId);
     const distributionReceiptPda = findDistributionReceiptPda(this.solana.programId, agentIdentityPda, reference);
 
-    const agentTokenAccount = input.solanaAgentTokenAccount
-      ? parseSolanaPublicKey(input.solanaAgentTokenAccount, "solanaAgentTokenAccount")
-      : getAssociatedTokenAddressSync(
-          protocolConfig.settlementMint,
-          agentIdentity.agentWallet,
-          true,
-          this.solana.tokenProgramId
-        );
+    // Verification and reputation feed the fee schedule; absent accounts are passed as the program id (None).
+    const verificationRecordPda = findVerificationRecordPda(this.solana.programId, agentIdentityPda);
+    const reputationStatePda = findReputationStatePda(this.solana.programId, agentIdentityPda);
+    const [verificationRecordInfo, reputationStateInfo] = await this.solana.connection.getMultipleAccountsInfo([
+      verificationRecordPda,
+      reputationStatePda
+    ]);
+
+    // A vesting split escrows the agent share; the vesting vault then stands in for the agent token account.
+    const vesting = splitConfig.vestingDuration > 0n;
+    const vestingEscrowPda = findVestingEscrowPda(this.solana.programId, agentIdentityPda, settlementMint);
+    const vestingVaultPda = findVestingVaultPda(this.solana.programId, vestingEscrowPda);
+
+    const agentTokenAccount = vesting
+      ? vestingVaultPda
+      : input.solanaAgentTokenAccount
+        ? parseSolanaPublicKey(input.solanaAgentTokenAccount, "solanaAgentTokenAccount")
+        : getAssociatedTokenAddressSync(
+            settlementMint,
+            agentIdentity.agentWallet,
+            true,
+            this.solana.tokenProgramId
+          );
 
     const platformTokenAccount =
       splitConfig.platformBps > 0
         ? getAssociatedTokenAddressSync(
-            protocolConfig.settlementMint,
+            settlementMint,
             splitConfig.platform,
             true,
             this.solana.tokenProgramId
           )
-        : protocolConfig.settlementVault;
+        : settlementVault;
 
     const referrerTokenAccount =
       splitConfig.referrerBps > 0
         ? getAssociatedTokenAddressSync(
-            protocolConfig.settlementMint,
+            settlementMint,
             splitConfig.referrer,
             true,
             this.solana.tokenProgramId
           )
-        : protocolConfig.settlementVault;
+        : settlementVault;
 
     const reserveTokenAccount =
       splitConfig.reserveBps > 0
         ? getAssociatedTokenAddressSync(
-            protocolConfig.settlementMint,
+            settlementMint,
             splitConfig.reserveVault,
             true,
             this.solana.tokenProgramId
           )
-        : protocolConfig.settlementVault;
+        : settlementVault;
 
-    await this.ensureAccountExists(protocolConfig.settlementVault, "settlement vault");
-    await this.ensureAccountExists(protocolConfig.protocolTreasuryTokenAccount, "protocol treasury token account");
-    await this.ensureAccountExists(agentTokenAccount, "agent token account");
+    const { tokenProgramId } = this.solana;
+    const recipientTokenAccounts = splitConfig.recipients.map((entry) =>
+      getAssociatedTokenAddressSync(settlementMint, entry.recipient, true, tokenProgramId)
+    );
+
+    await this.ensureAccountExists(settlementVault, "settlement vault");
+    await this.ensureAccountExists(treasuryTokenAccount, "protocol treasury token account");
+    if (vesting) {
+      await this.ensureAccountExists(vestingEscrowPda, "vesting escrow");
+    } else {
+      await this.ensureAccountExists(agentTokenAccount, "agent token account");
+    }
 
     if (splitConfig.platformBps > 0) {
       await this.ensureAccountExists(platformTokenAccount, "platform token account");
@@ -243,8 +315,41 @@ export class RevenueSplitGateway {
     if (splitConfig.referrerBps > 0) {
       await this.ensureAccountExists(referrerTokenAccount, "referrer token account");
     }
+    // A reserve_vault owned by this program is a governed reserve; pass it so the inflow is booked right away.
+    let governedReserve = false;
     if (splitConfig.reserveBps > 0) {
       await this.ensureAccountExists(reserveTokenAccount, "reserve token account");
+      const reserveInfo = await this.solana.connection.getAccountInfo(splitConfig.reserveVault);
+      governedReserve = Boolean(reserveInfo?.owner.equals(this.solana.programId));
+    }
+    for (const recipientTokenAccount of recipientTokenAccounts) {
+      await this.ensureAccountExists(recipientTokenAccount, "split recipient token account");
+    }
+
+    // With admin referral levels, walk the referrer's upline: each level passes the current referrer's
+    // referral account and, when it has a parent, the parent's token account.
+    const referralProgramPda = findReferralProgramPda(this.solana.programId);
+    const referralAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[] = [];
+    const referralProgramInfo = await this.solana.connection.getAccountInfo(referralProgramPda);
+    if (referralProgramInfo && splitConfig.referrerBps > 0) {
+      const referralProgram = decodeReferralProgramAccount(Buffer.from(referralProgramInfo.data));
+      let current = splitConfig.referrer;
+      for (let level = 1; level < referralProgram.levelBps.length; level += 1) {
+        const referralAccountPda = findReferralAccountPda(this.solana.programId, current);
+        referralAccounts.push({ pubkey: referralAccountPda, isSigner: false, isWritable: false });
+        const referralAccountInfo = await this.solana.connection.getAccountInfo(referralAccountPda);
+        if (!referralAccountInfo) {
+          break;
+        }
+        const { parent } = decodeReferralAccount(Buffer.from(referralAccountInfo.data));
+        if (parent.equals(PublicKey.default)) {
+          break;
+        }
+        const parentTokenAccount = getAssociatedTokenAddressSync(settlementMint, parent, true, tokenProgramId);
+        await this.ensureAccountExists(parentTokenAccount, "referral upline token account");
+        referralAccounts.push({ pubkey: parentTokenAccount, isSigner: false, isWritable: true });
+        current = parent;
+      }
     }
 
     const instruction = new TransactionInstruction({
@@ -255,16 +360,41 @@ export class RevenueSplitGateway {
         { pubkey: roleAssignmentPda, isSigner: false, isWritable: false },
         { pubkey: agentIdentityPda, isSigner: false, isWritable: false },
         { pubkey: splitConfigPda, isSigner: false, isWritable: false },
-        { pubkey: protocolConfig.settlementVault, isSigner: false, isWritable: true },
+        { pubkey: settlementMintConfigPda, isSigner: false, isWritable: false },
+        { pubkey: settlementVault, isSigner: false, isWritable: true },
+        { pubkey: settlementMint, isSigner: false, isWritable: false },
         { pubkey: vaultAuthorityPda, isSigner: false, isWritable: false },
+        { pubkey: findClaimLedgerPda(this.solana.programId, settlementVault), isSigner: false, isWritable: false },
         { pubkey: agentTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: vesting ? vestingEscrowPda : this.solana.programId, isSigner: false, isWritable: vesting },
+        { pubkey: vesting ? vestingVaultPda : this.solana.programId, isSigner: false, isWritable: vesting },
         { pubkey: platformTokenAccount, isSigner: false, isWritable: true },
         { pubkey: referrerTokenAccount, isSigner: false, isWritable: true },
         { pubkey: reserveTokenAccount, isSigner: false, isWritable: true },
-        { pubkey: protocolConfig.protocolTreasuryTokenAccount, isSigner: false, isWritable: true },
+        {
+          pubkey: governedReserve ? splitConfig.reserveVault : this.solana.programId,
+          isSigner: false,
+          isWritable: governedReserve
+        },
+        { pubkey: treasuryTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: findFeeSchedulePda(this.solana.programId), isSigner: false, isWritable: false },
+        { pubkey: referralProgramPda, isSigner: false, isWritable: false },
+        {
+          pubkey: verificationRecordInfo ? verificationRecordPda : this.solana.programId,
+          isSigner: false,
+          isWritable: false
+        },
+        { pubkey: reputationStateInfo ? reputationStatePda : this.solana.programId, isSigner: false, isWritable: false },
+        {
+          pubkey: findSettlementVolumePda(this.solana.programId, agentIdentityPda, settlementMint),
+          isSigner: false,
+          isWritable: true
+        },
         { pubkey: distributionReceiptPda, isSigner: false, isWritable: true },
         { pubkey: this.solana.tokenProgramId, isSigner: false, isWritable: false },
-        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        ...recipientTokenAccounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })),
+        ...referralAccounts
       ],
       data: encodeDistributeSettlementData({ reference, amount })
     });
@@ -0,0 +1,73 @@
+import { pathToFileURL } from "node:url";
+import {
+  betaMean,
+  decayScore,
+  emaRetentionBps,
+  emaUpdate,
+  verifySignalMerkleProof,
+  welfordAdd,
+  welfordRemove,
+  windowMean
+} from "./solana-program";
+
+// Same vectors as the tests in contracts-solana/programs/n3roai-solana/src/math.rs.
+const MERKLE_LEAVES = [Buffer.alloc(32, 0x11), Buffer.alloc(32, 0x22), Buffer.alloc(32, 0x33)];
+const MERKLE_PARENT = Buffer.from("39b74deb1be11fa6539dcbe7b676432d2f947f3a4b21b202ce3ce67936c5f33d", "hex");
+const MERKLE_ROOT = Buffer.from("4f50001bbab66ab4665e469acc5faf6bf54c3045bb0cd6884ea3d257fc4aecbf", "hex");
+
+function expectEqual<T>(name: string, actual: T, expected: T): void {
+  const actualText = JSON.stringify(actual, (_, value) => (typeof value === "bigint" ? value.toString() : value));
+  const expectedText = JSON.stringify(expected, (_, value) => (typeof value === "bigint" ? value.toString() : value));
+  if (actualText !== expectedText) {
+    throw new Error(`${name}: expected ${expectedText}, got ${actualText}`);
+  }
+}
+
+// Throws on the first helper that disagrees with the on-chain math.
+export function checkSolanaMathVectors(): void {
+  expectEqual("decayScore(10000, 3600, 3600)", decayScore(10_000, 3_600n, 3_600n), 5_000);
+  expectEqual("decayScore(10000, 1800, 3600)", decayScore(10_000, 1_800n, 3_600n), 7_071);
+  expectEqual("decayScore(8000, 5400, 3600)", decayScore(8_000, 5_400n, 3_600n), 2_828);
+  expectEqual("decayScore(9999, 1, 86400)", decayScore(9_999, 1n, 86_400n), 9_999);
+  expectEqual("decayScore(7777, 100000, 86400)", decayScore(7_777, 100_000n, 86_400n), 3_486);
+  expectEqual("decayScore(10000, 57600, 3600)", decayScore(10_000, 16n * 3_600n, 3_600n), 0);
+  expectEqual("decayScore(10000, 3600, 0)", decayScore(10_000, 3_600n, 0n), 10_000);
+
+  expectEqual("emaUpdate(5000, 8000, 2000)", emaUpdate(5_000, 8_000, 2_000), 5_600);
+  expectEqual("emaUpdate(7071, 0, 2000)", emaUpdate(7_071, 0, 2_000), 5_656);
+  expectEqual("emaUpdate(1234, 9876, 12000)", emaUpdate(1_234, 9_876, 12_000), 9_876);
+
+  expectEqual("windowMean([0, 101, 201, 0])", windowMean([0, 101, 201, 0]), 150);
+  expectEqual("windowMean([1, 2, 4])", windowMean([1, 2, 4]), 1);
+  expectEqual("windowMean([0, 0])", windowMean([0, 0]), null);
+
+  expectEqual("betaMean(0, 0, 10000, 10000)", betaMean(0n, 0n, 10_000, 10_000), 5_000);
+  expectEqual("betaMean(240000000, 30000, 10000, 10000)", betaMean(240_000_000n, 30_000n, 10_000, 10_000), 6_800);
+  expectEqual("betaMean(0, 0, 0, 0)", betaMean(0n, 0n, 0, 0), 0);
+
+  expectEqual("emaRetentionBps(2000, 0)", emaRetentionBps(2_000, 0n), 10_000);
+  expectEqual("emaRetentionBps(2000, 2)", emaRetentionBps(2_000, 2n), 6_400);
+  expectEqual("emaRetentionBps(2000, 5)", emaRetentionBps(2_000, 5n), 3_276);
+  expectEqual("emaRetentionBps(10000, 3)", emaRetentionBps(10_000, 3n), 0);
+
+  const first = welfordAdd(0n, 0n, 0, 4_000);
+  expectEqual("welfordAdd first", first, [4_000_000_000n, 0n]);
+  const second = welfordAdd(first[0], first[1], 1, 6_000);
+  expectEqual("welfordAdd second", second, [5_000_000_000n, 2_000_000_000_000_000_000n]);
+  const third = welfordAdd(second[0], second[1], 2, 8_000);
+  expectEqual("welfordAdd third", third, [6_000_000_000n, 8_000_000_000_000_000_000n]);
+  expectEqual("welfordRemove third", welfordRemove(third[0], third[1], 3, 8_000), second);
+  expectEqual("welfordRemove first", welfordRemove(first[0], first[1], 1, 4_000), [0n, 0n]);
+
+  const [leaf0, leaf1, leaf2] = MERKLE_LEAVES;
+  expectEqual("verifySignalMerkleProof leaf 0", verifySignalMerkleProof(leaf0, [leaf1, leaf2], MERKLE_ROOT), true);
+  expectEqual("verifySignalMerkleProof leaf 1", verifySignalMerkleProof(leaf1, [leaf0, leaf2], MERKLE_ROOT), true);
+  expectEqual("verifySignalMerkleProof leaf 2", verifySignalMerkleProof(leaf2, [MERKLE_PARENT], MERKLE_ROOT), true);
+  expectEqual("verifySignalMerkleProof swapped", verifySignalMerkleProof(leaf0, [leaf2, leaf1], MERKLE_ROOT), false);
+  expectEqual("verifySignalMerkleProof empty", verifySignalMerkleProof(leaf2, [], MERKLE_ROOT), false);
+}
+
+if (process.argv[1] && import.meta.url === pathToFileURL(process.argv[1]).href) {
+  checkSolanaMathVectors();
+  console.log("Solana math vectors match");
+}
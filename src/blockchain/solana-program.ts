@@ -1,20 +1,78 @@
 import { createHash } from "node:crypto";
 import bs58 from "bs58";
 import { Keypair, PublicKey } from "@solana/web3.js";
+import { keccak256 } from "viem";
 
 const ACCOUNT_DISCRIMINATOR_SIZE = 8;
+const RISK_FLAG_BITS = 8;
+const LEADERBOARD_ENTRY_SIZE = 32 + 2 + 4 + 8;
+const SPLIT_RECIPIENT_SIZE = 32 + 2 + 32;
+const RESERVE_TRANCHE_SIZE = 8 + 8 + 8;
+const SCORE_HISTOGRAM_BUCKETS = 10;
+const SCORE_STAT_SCALE = 1_000_000;
+const MAX_SCORE_WINDOW = 32;
+const MAX_BPS = 10_000;
+const EMPTY_WINDOW_SLOT = 0;
+const U128_MAX = (1n << 128n) - 1n;
 
 const PROTOCOL_CONFIG_SEED = Buffer.from("protocol-config");
 const AGENT_SEED = Buffer.from("agent");
 const ROLE_SEED = Buffer.from("role");
+const VERIFICATION_SEED = Buffer.from("verification");
 const SPLIT_SEED = Buffer.from("split");
 const SIGNAL_SEED = Buffer.from("signal");
+const REPUTATION_SEED = Buffer.from("reputation");
+const STAKE_SEED = Buffer.from("stake");
+const CATEGORY_SEED = Buffer.from("category");
+const EPOCH_SNAPSHOT_SEED = Buffer.from("epoch-snapshot");
+const COMMITMENT_SEED = Buffer.from("commitment");
+const SIGNAL_BATCH_SEED = Buffer.from("signal-batch");
+const PNL_STATS_SEED = Buffer.from("pnl-stats");
+const SCORED_TRADES_SEED = Buffer.from("scored-trades");
+const LEADERBOARD_SEED = Buffer.from("leaderboard");
+const SETTLEMENT_MINT_SEED = Buffer.from("settlement-mint");
+const SETTLEMENT_VAULT_SEED = Buffer.from("settlement-vault");
+const CLAIM_LEDGER_SEED = Buffer.from("claim-ledger");
+const CLAIMABLE_SEED = Buffer.from("claimable");
+const FEE_SCHEDULE_SEED = Buffer.from("fee-schedule");
+const SETTLEMENT_VOLUME_SEED = Buffer.from("settlement-volume");
+const VESTING_SEED = Buffer.from("vesting");
+const RESERVE_SEED = Buffer.from("reserve");
+const REFERRAL_SEED = Buffer.from("referral");
+const REFERRAL_PROGRAM_SEED = Buffer.from("referral-program");
+const VESTING_VAULT_SEED = Buffer.from("vesting-vault");
+const SOL_VAULT_SEED = Buffer.from("sol-vault");
 const RECEIPT_SEED = Buffer.from("receipt");
 const VAULT_AUTHORITY_SEED = Buffer.from("vault-authority");
 
 export const ROLE_SIGNALER = 3;
 export const ROLE_REVENUE_OPERATOR = 4;
 
+export const AGGREGATION_EMA = 0;
+export const AGGREGATION_WINDOW = 1;
+export const AGGREGATION_BETA = 2;
+
+const DECAY_FRACTION_BITS = 16n;
+const MAX_DECAY_HALVINGS = 16n;
+const HALF_LIFE_ROOTS_Q32 = [
+  3_037_000_500n,
+  3_611_622_603n,
+  3_938_502_376n,
+  4_112_874_773n,
+  4_202_935_003n,
+  4_248_701_965n,
+  4_271_771_996n,
+  4_283_353_945n,
+  4_289_156_690n,
+  4_292_061_010n,
+  4_293_513_907n,
+  4_294_240_540n,
+  4_294_603_903n,
+  4_294_785_595n,
+  4_294_876_445n,
+  4_294_921_870n
+];
+
 export const DEFAULT_SPL_TOKEN_PROGRAM_ID = new PublicKey(
   "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
 );
@@ -23,9 +81,45 @@ export type SolanaProtocolConfig = {
   settlementMint: PublicKey;
   settlementVault: PublicKey;
   protocolTreasuryTokenAccount: PublicKey;
+  scoreHalfLife: bigint;
+  signalRevealWindow: bigint;
+  aggregationModel: number;
+  scoreWindowSize: number;
+  betaPriorAlpha: number;
+  betaPriorBeta: number;
+  aggregationVersion: number;
+  solTreasury: PublicKey;
+  requireSignalCommit: boolean;
   vaultAuthorityBump: number;
 };
 
+export type SolanaSettlementMintConfig = {
+  mint: PublicKey;
+  vault: PublicKey;
+  treasuryTokenAccount: PublicKey;
+  feeOverride: boolean;
+  protocolFeeBps: number;
+  enabled: boolean;
+};
+
+export type SolanaClaimLedger = {
+  vault: PublicKey;
+  mint: PublicKey;
+  outstanding: bigint;
+  totalAccrued: bigint;
+  totalClaimed: bigint;
+  updatedAt: bigint;
+};
+
+export type SolanaClaimableBalance = {
+  owner: PublicKey;
+  vault: PublicKey;
+  amount: bigint;
+  totalAccrued: bigint;
+  totalClaimed: bigint;
+  updatedAt: bigint;
+};
+
 export type SolanaRevenueSplitConfig = {
   platform: PublicKey;
   platformBps: number;
@@ -33,6 +127,137 @@ export type SolanaRevenueSplitConfig = {
   referrerBps: number;
   reserveVault: PublicKey;
   reserveBps: number;
+  vestingDuration: bigint;
+  recipients: SolanaSplitRecipient[];
+};
+
+export type SolanaReferralProgram = {
+  levelBps: number[];
+  updatedAt: bigint;
+};
+
+export type SolanaReferralAccount = {
+  referrer: PublicKey;
+  parent: PublicKey;
+  registeredAt: bigint;
+  updatedAt: bigint;
+};
+
+export type SolanaReserveTranche = {
+  amount: bigint;
+  openedAt: bigint;
+  unlockAt: bigint;
+};
+
+export type SolanaReserveVault = {
+  scope: PublicKey;
+  owner: PublicKey;
+  mint: PublicKey;
+  tokenAccount: PublicKey;
+  lockupPeriod: bigint;
+  current: SolanaReserveTranche;
+  previous: SolanaReserveTranche;
+  unlocked: bigint;
+  trackedBalance: bigint;
+  totalInflow: bigint;
+  totalWithdrawn: bigint;
+  totalCompensated: bigint;
+  lastCompensationHash: Buffer;
+  updatedAt: bigint;
+};
+
+export type SolanaVestingTranche = {
+  remaining: bigint;
+  end: bigint;
+};
+
+export type SolanaVestingEscrow = {
+  agent: PublicKey;
+  mint: PublicKey;
+  vault: PublicKey;
+  locked: bigint;
+  vested: bigint;
+  lastUpdate: bigint;
+  totalDeposited: bigint;
+  totalReleased: bigint;
+  totalClawedBack: bigint;
+  tranches: SolanaVestingTranche[];
+};
+
+export type SolanaFeeTier = {
+  requireVerified: boolean;
+  minRollingScore: number;
+  minVolume: bigint;
+  feeBps: number;
+};
+
+export type SolanaSplitRecipient = {
+  recipient: PublicKey;
+  bps: number;
+  labelHash: Buffer;
+};
+
+export type SolanaReputationState = {
+  agent: PublicKey;
+  category: number;
+  totalWeightedScore: bigint;
+  totalWeight: bigint;
+  rollingScore: number;
+  lastScore: number;
+  lastConfidenceBps: number;
+  scoreCount: number;
+  lastUpdated: bigint;
+  scoreMean: bigint;
+  scoreM2: bigint;
+  scoreHistogram: number[];
+  aggregationVersion: number;
+  windowStartSequence: bigint;
+  scoreWindow: number[];
+};
+
+export type SolanaReputationSnapshot = {
+  reputationState: PublicKey;
+  agent: PublicKey;
+  category: number;
+  epoch: bigint;
+  startedAt: bigint;
+  endedAt: bigint;
+  rollingScore: number;
+  scoreCount: number;
+  weightedAverage: number;
+  flagCounts: number[];
+  closedAt: bigint;
+};
+
+export type SolanaPnlStats = {
+  agent: PublicKey;
+  tradeCount: number;
+  reportedCount: number;
+  winCount: number;
+  winRateBps: number;
+  cumulativePnl: bigint;
+  peakPnl: bigint;
+  maxDrawdown: bigint;
+  totalNotional: bigint;
+  totalHoldingTime: bigint;
+  averageHoldingTime: bigint;
+  lastUpdated: bigint;
+};
+
+export type SolanaLeaderboardEntry = {
+  agent: PublicKey;
+  rollingScore: number;
+  scoreCount: number;
+  updatedAt: bigint;
+};
+
+export type SolanaLeaderboard = {
+  category: number;
+  capacity: number;
+  minScoreCount: number;
+  requireVerified: boolean;
+  updatedAt: bigint;
+  entries: SolanaLeaderboardEntry[];
 };
 
 export type SolanaAgentIdentity = {
@@ -57,7 +282,10 @@ export function parseSolanaKeypair(secret: string, fieldName: string): Keypair {
 }
 
 export function decodeProtocolConfigAccount(data: Buffer): SolanaProtocolConfig {
-  assertMinLength(data, ACCOUNT_DISCRIMINATOR_SIZE + 32 + 32 + 32 + 32 + 2 + 2 + 2 + 8 + 1 + 1 + 1 + 1 + 1);
+  assertMinLength(
+    data,
+    ACCOUNT_DISCRIMINATOR_SIZE + 32 + 32 + 32 + 32 + 2 + 2 + 2 + 8 + 1 + 1 + 1 + 1 + 1 + 8 + 1 + 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 4 + 4 + 4 + 8 + 32 + 1
+  );
 
   let offset = ACCOUNT_DISCRIMINATOR_SIZE;
   offset += 32; // admin
@@ -73,17 +301,149 @@ export function decodeProtocolConfigAccount(data: Buffer): SolanaProtocolConfig
 
   offset += 2 + 2 + 2 + 8 + 1 + 1 + 1;
   const vaultAuthorityBump = readU8(data, offset);
+  offset += 1;
+
+  offset += 1; // bump
+  const scoreHalfLife = readI64(data, offset);
+  offset += 8;
+
+  offset += 1 + 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8;
+  const signalRevealWindow = readI64(data, offset);
+  offset += 8;
+
+  const aggregationModel = readU8(data, offset);
+  offset += 1;
+
+  const scoreWindowSize = readU8(data, offset);
+  offset += 1;
+
+  const betaPriorAlpha = data.readUInt32LE(offset);
+  offset += 4;
+
+  const betaPriorBeta = data.readUInt32LE(offset);
+  offset += 4;
+
+  const aggregationVersion = data.readUInt32LE(offset);
+  offset += 4;
+
+  offset += 8;
+  const solTreasury = readPubkey(data, offset);
+  offset += 32;
+
+  const requireSignalCommit = readU8(data, offset) === 1;
 
   return {
     settlementMint,
     settlementVault,
     protocolTreasuryTokenAccount,
+    scoreHalfLife,
+    signalRevealWindow,
+    aggregationModel,
+    scoreWindowSize,
+    betaPriorAlpha,
+    betaPriorBeta,
+    aggregationVersion,
+    solTreasury,
+    requireSignalCommit,
     vaultAuthorityBump
   };
 }
 
+export function decodeSettlementMintConfigAccount(data: Buffer): SolanaSettlementMintConfig {
+  assertMinLength(data, ACCOUNT_DISCRIMINATOR_SIZE + 32 + 32 + 32 + 1 + 2 + 1 + 8 + 1);
+
+  let offset = ACCOUNT_DISCRIMINATOR_SIZE;
+  const mint = readPubkey(data, offset);
+  offset += 32;
+
+  const vault = readPubkey(data, offset);
+  offset += 32;
+
+  const treasuryTokenAccount = readPubkey(data, offset);
+  offset += 32;
+
+  const feeOverride = readU8(data, offset) === 1;
+  offset += 1;
+
+  const protocolFeeBps = readU16(data, offset);
+  offset += 2;
+
+  const enabled = readU8(data, offset) === 1;
+
+  return {
+    mint,
+    vault,
+    treasuryTokenAccount,
+    feeOverride,
+    protocolFeeBps,
+    enabled
+  };
+}
+
+export function decodeClaimLedgerAccount(data: Buffer): SolanaClaimLedger {
+  assertMinLength(data, ACCOUNT_DISCRIMINATOR_SIZE + 32 + 32 + 8 + 8 + 8 + 8 + 1);
+
+  let offset = ACCOUNT_DISCRIMINATOR_SIZE;
+  const vault = readPubkey(data, offset);
+  offset += 32;
+
+  const mint = readPubkey(data, offset);
+  offset += 32;
+
+  const outstanding = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const totalAccrued = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const totalClaimed = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const updatedAt = readI64(data, offset);
+
+  return {
+    vault,
+    mint,
+    outstanding,
+    totalAccrued,
+    totalClaimed,
+    updatedAt
+  };
+}
+
+export function decodeClaimableBalanceAccount(data: Buffer): SolanaClaimableBalance {
+  assertMinLength(data, ACCOUNT_DISCRIMINATOR_SIZE + 32 + 32 + 8 + 8 + 8 + 8 + 1);
+
+  let offset = ACCOUNT_DISCRIMINATOR_SIZE;
+  const owner = readPubkey(data, offset);
+  offset += 32;
+
+  const vault = readPubkey(data, offset);
+  offset += 32;
+
+  const amount = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const totalAccrued = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const totalClaimed = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const updatedAt = readI64(data, offset);
+
+  return {
+    owner,
+    vault,
+    amount,
+    totalAccrued,
+    totalClaimed,
+    updatedAt
+  };
+}
+
 export function decodeRevenueSplitConfigAccount(data: Buffer): SolanaRevenueSplitConfig {
-  assertMinLength(data, ACCOUNT_DISCRIMINATOR_SIZE + 32 + 32 + 2 + 32 + 2 + 32 + 2 + 1);
+  assertMinLength(data, ACCOUNT_DISCRIMINATOR_SIZE + 32 + 32 + 2 + 32 + 2 + 32 + 2 + 1 + 8);
 
   let offset = ACCOUNT_DISCRIMINATOR_SIZE;
   offset += 32; // agent
@@ -104,6 +464,26 @@ export function decodeRevenueSplitConfigAccount(data: Buffer): SolanaRevenueSpli
   offset += 32;
 
   const reserveBps = readU16(data, offset);
+  offset += 2;
+
+  offset += 1; // bump
+  const vestingDuration = readI64(data, offset);
+  offset += 8;
+
+  assertMinLength(data, offset + 4);
+  const recipientCount = data.readUInt32LE(offset);
+  offset += 4;
+
+  assertMinLength(data, offset + recipientCount * SPLIT_RECIPIENT_SIZE);
+  const recipients: SolanaSplitRecipient[] = [];
+  for (let index = 0; index < recipientCount; index += 1) {
+    recipients.push({
+      recipient: readPubkey(data, offset),
+      bps: readU16(data, offset + 32),
+      labelHash: Buffer.from(data.subarray(offset + 34, offset + 66))
+    });
+    offset += SPLIT_RECIPIENT_SIZE;
+  }
 
   return {
     platform,
@@ -111,7 +491,179 @@ export function decodeRevenueSplitConfigAccount(data: Buffer): SolanaRevenueSpli
     referrer,
     referrerBps,
     reserveVault,
-    reserveBps
+    reserveBps,
+    vestingDuration,
+    recipients
+  };
+}
+
+export function decodeReferralProgramAccount(data: Buffer): SolanaReferralProgram {
+  assertMinLength(data, ACCOUNT_DISCRIMINATOR_SIZE + 4);
+
+  let offset = ACCOUNT_DISCRIMINATOR_SIZE;
+  const levelCount = data.readUInt32LE(offset);
+  offset += 4;
+
+  assertMinLength(data, offset + levelCount * 2 + 8);
+  const levelBps: number[] = [];
+  for (let index = 0; index < levelCount; index += 1) {
+    levelBps.push(readU16(data, offset));
+    offset += 2;
+  }
+
+  const updatedAt = readI64(data, offset);
+
+  return {
+    levelBps,
+    updatedAt
+  };
+}
+
+export function decodeReferralAccount(data: Buffer): SolanaReferralAccount {
+  assertMinLength(data, ACCOUNT_DISCRIMINATOR_SIZE + 32 + 32 + 8 + 8 + 1);
+
+  let offset = ACCOUNT_DISCRIMINATOR_SIZE;
+  const referrer = readPubkey(data, offset);
+  offset += 32;
+
+  const parent = readPubkey(data, offset);
+  offset += 32;
+
+  const registeredAt = readI64(data, offset);
+  offset += 8;
+
+  const updatedAt = readI64(data, offset);
+
+  return {
+    referrer,
+    parent,
+    registeredAt,
+    updatedAt
+  };
+}
+
+export function decodeReserveVaultAccount(data: Buffer): SolanaReserveVault {
+  assertMinLength(data, ACCOUNT_DISCRIMINATOR_SIZE + 32 * 4 + 8 + RESERVE_TRANCHE_SIZE * 2 + 8 * 5 + 32 + 8 + 1);
+
+  let offset = ACCOUNT_DISCRIMINATOR_SIZE;
+  const scope = readPubkey(data, offset);
+  offset += 32;
+
+  const owner = readPubkey(data, offset);
+  offset += 32;
+
+  const mint = readPubkey(data, offset);
+  offset += 32;
+
+  const tokenAccount = readPubkey(data, offset);
+  offset += 32;
+
+  const lockupPeriod = readI64(data, offset);
+  offset += 8;
+
+  const tranches: SolanaReserveTranche[] = [];
+  for (let index = 0; index < 2; index += 1) {
+    tranches.push({
+      amount: data.readBigUInt64LE(offset),
+      openedAt: readI64(data, offset + 8),
+      unlockAt: readI64(data, offset + 16)
+    });
+    offset += RESERVE_TRANCHE_SIZE;
+  }
+
+  const unlocked = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const trackedBalance = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const totalInflow = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const totalWithdrawn = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const totalCompensated = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const lastCompensationHash = Buffer.from(data.subarray(offset, offset + 32));
+  offset += 32;
+
+  const updatedAt = readI64(data, offset);
+
+  return {
+    scope,
+    owner,
+    mint,
+    tokenAccount,
+    lockupPeriod,
+    current: tranches[0],
+    previous: tranches[1],
+    unlocked,
+    trackedBalance,
+    totalInflow,
+    totalWithdrawn,
+    totalCompensated,
+    lastCompensationHash,
+    updatedAt
+  };
+}
+
+export function decodeVestingEscrowAccount(data: Buffer): SolanaVestingEscrow {
+  assertMinLength(data, ACCOUNT_DISCRIMINATOR_SIZE + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 4);
+
+  let offset = ACCOUNT_DISCRIMINATOR_SIZE;
+  const agent = readPubkey(data, offset);
+  offset += 32;
+
+  const mint = readPubkey(data, offset);
+  offset += 32;
+
+  const vault = readPubkey(data, offset);
+  offset += 32;
+
+  const locked = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const vested = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const lastUpdate = readI64(data, offset);
+  offset += 8;
+
+  const totalDeposited = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const totalReleased = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const totalClawedBack = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const trancheCount = data.readUInt32LE(offset);
+  offset += 4;
+
+  assertMinLength(data, offset + trancheCount * 16);
+  const tranches: SolanaVestingTranche[] = [];
+  for (let index = 0; index < trancheCount; index += 1) {
+    tranches.push({
+      remaining: data.readBigUInt64LE(offset),
+      end: readI64(data, offset + 8)
+    });
+    offset += 16;
+  }
+
+  return {
+    agent,
+    mint,
+    vault,
+    locked,
+    vested,
+    lastUpdate,
+    totalDeposited,
+    totalReleased,
+    totalClawedBack,
+    tranches
   };
 }
 
@@ -125,6 +677,357 @@ export function decodeAgentIdentityAccount(data: Buffer): SolanaAgentIdentity {
   return { agentWallet: readPubkey(data, offset) };
 }
 
+export function decodeReputationStateAccount(data: Buffer): SolanaReputationState {
+  assertMinLength(
+    data,
+    ACCOUNT_DISCRIMINATOR_SIZE + 32 + 16 + 8 + 2 + 2 + 2 + 4 + 8 + 1 + 2 + 8 + 8 + 2 * RISK_FLAG_BITS + 8 + 8 + 8 + 16 + 4 * SCORE_HISTOGRAM_BUCKETS + 4 + 8 + 2 * MAX_SCORE_WINDOW
+  );
+
+  let offset = ACCOUNT_DISCRIMINATOR_SIZE;
+  const agent = readPubkey(data, offset);
+  offset += 32;
+
+  const totalWeightedScore = readU128(data, offset);
+  offset += 16;
+
+  const totalWeight = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const rollingScore = readU16(data, offset);
+  offset += 2;
+
+  const lastScore = readU16(data, offset);
+  offset += 2;
+
+  const lastConfidenceBps = readU16(data, offset);
+  offset += 2;
+
+  const scoreCount = data.readUInt32LE(offset);
+  offset += 4;
+
+  const lastUpdated = readI64(data, offset);
+  offset += 8;
+
+  offset += 1; // bump
+  const category = readU16(data, offset);
+  offset += 2;
+
+  // Skip score_sequence, flag_window_start, flag_counts, epoch and epoch_started_at.
+  offset += 8 + 8 + 2 * RISK_FLAG_BITS + 8 + 8;
+
+  const scoreMean = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const scoreM2 = readU128(data, offset);
+  offset += 16;
+
+  const scoreHistogram: number[] = [];
+  for (let bucket = 0; bucket < SCORE_HISTOGRAM_BUCKETS; bucket += 1) {
+    scoreHistogram.push(data.readUInt32LE(offset));
+    offset += 4;
+  }
+
+  const aggregationVersion = data.readUInt32LE(offset);
+  offset += 4;
+
+  const windowStartSequence = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const scoreWindow: number[] = [];
+  for (let slot = 0; slot < MAX_SCORE_WINDOW; slot += 1) {
+    scoreWindow.push(readU16(data, offset));
+    offset += 2;
+  }
+
+  return {
+    agent,
+    category,
+    totalWeightedScore,
+    totalWeight,
+    rollingScore,
+    lastScore,
+    lastConfidenceBps,
+    scoreCount,
+    lastUpdated,
+    scoreMean,
+    scoreM2,
+    scoreHistogram,
+    aggregationVersion,
+    windowStartSequence,
+    scoreWindow
+  };
+}
+
+// Population standard deviation of applied scores, in score units.
+export function scoreStdDev(state: SolanaReputationState): number {
+  if (state.scoreCount === 0) {
+    return 0;
+  }
+  const variance = Number(state.scoreM2 / BigInt(state.scoreCount)) / (SCORE_STAT_SCALE * SCORE_STAT_SCALE);
+  return Math.sqrt(variance);
+}
+
+export function decodeReputationSnapshotAccount(data: Buffer): SolanaReputationSnapshot {
+  assertMinLength(data, ACCOUNT_DISCRIMINATOR_SIZE + 32 + 32 + 2 + 8 + 8 + 8 + 2 + 4 + 2 + 2 * RISK_FLAG_BITS + 8 + 1);
+
+  let offset = ACCOUNT_DISCRIMINATOR_SIZE;
+  const reputationState = readPubkey(data, offset);
+  offset += 32;
+
+  const agent = readPubkey(data, offset);
+  offset += 32;
+
+  const category = readU16(data, offset);
+  offset += 2;
+
+  const epoch = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const startedAt = readI64(data, offset);
+  offset += 8;
+
+  const endedAt = readI64(data, offset);
+  offset += 8;
+
+  const rollingScore = readU16(data, offset);
+  offset += 2;
+
+  const scoreCount = data.readUInt32LE(offset);
+  offset += 4;
+
+  const weightedAverage = readU16(data, offset);
+  offset += 2;
+
+  const flagCounts: number[] = [];
+  for (let bit = 0; bit < RISK_FLAG_BITS; bit += 1) {
+    flagCounts.push(readU16(data, offset));
+    offset += 2;
+  }
+
+  const closedAt = readI64(data, offset);
+
+  return {
+    reputationState,
+    agent,
+    category,
+    epoch,
+    startedAt,
+    endedAt,
+    rollingScore,
+    scoreCount,
+    weightedAverage,
+    flagCounts,
+    closedAt
+  };
+}
+
+export function decodePnlStatsAccount(data: Buffer): SolanaPnlStats {
+  assertMinLength(data, ACCOUNT_DISCRIMINATOR_SIZE + 32 + 4 + 4 + 4 + 2 + 8 + 8 + 8 + 16 + 8 + 8 + 8 + 1);
+
+  let offset = ACCOUNT_DISCRIMINATOR_SIZE;
+  const agent = readPubkey(data, offset);
+  offset += 32;
+
+  const tradeCount = data.readUInt32LE(offset);
+  offset += 4;
+
+  const reportedCount = data.readUInt32LE(offset);
+  offset += 4;
+
+  const winCount = data.readUInt32LE(offset);
+  offset += 4;
+
+  const winRateBps = readU16(data, offset);
+  offset += 2;
+
+  const cumulativePnl = readI64(data, offset);
+  offset += 8;
+
+  const peakPnl = readI64(data, offset);
+  offset += 8;
+
+  const maxDrawdown = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const totalNotional = readU128(data, offset);
+  offset += 16;
+
+  const totalHoldingTime = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  const averageHoldingTime = readI64(data, offset);
+  offset += 8;
+
+  const lastUpdated = readI64(data, offset);
+
+  return {
+    agent,
+    tradeCount,
+    reportedCount,
+    winCount,
+    winRateBps,
+    cumulativePnl,
+    peakPnl,
+    maxDrawdown,
+    totalNotional,
+    totalHoldingTime,
+    averageHoldingTime,
+    lastUpdated
+  };
+}
+
+export function decodeLeaderboardAccount(data: Buffer): SolanaLeaderboard {
+  assertMinLength(data, ACCOUNT_DISCRIMINATOR_SIZE + 2 + 2 + 4 + 1 + 8 + 4);
+
+  let offset = ACCOUNT_DISCRIMINATOR_SIZE;
+  const category = readU16(data, offset);
+  offset += 2;
+
+  const capacity = readU16(data, offset);
+  offset += 2;
+
+  const minScoreCount = data.readUInt32LE(offset);
+  offset += 4;
+
+  const requireVerified = readU8(data, offset) === 1;
+  offset += 1;
+
+  const updatedAt = readI64(data, offset);
+  offset += 8;
+
+  const entryCount = data.readUInt32LE(offset);
+  offset += 4;
+
+  assertMinLength(data, offset + entryCount * LEADERBOARD_ENTRY_SIZE);
+  const entries: SolanaLeaderboardEntry[] = [];
+  for (let index = 0; index < entryCount; index += 1) {
+    entries.push({
+      agent: readPubkey(data, offset),
+      rollingScore: readU16(data, offset + 32),
+      scoreCount: data.readUInt32LE(offset + 34),
+      updatedAt: readI64(data, offset + 38)
+    });
+    offset += LEADERBOARD_ENTRY_SIZE;
+  }
+
+  return {
+    category,
+    capacity,
+    minScoreCount,
+    requireVerified,
+    updatedAt,
+    entries
+  };
+}
+
+// Mirrors math::decay_score in the Solana program bit for bit.
+export function decayScore(score: number, elapsed: bigint, halfLife: bigint): number {
+  if (halfLife <= 0n || elapsed <= 0n || score === 0) {
+    return score;
+  }
+
+  const halvings = elapsed / halfLife;
+  if (halvings >= MAX_DECAY_HALVINGS) {
+    return 0;
+  }
+
+  const fraction = ((elapsed % halfLife) << DECAY_FRACTION_BITS) / halfLife;
+
+  let factor = 1n << 32n;
+  HALF_LIFE_ROOTS_Q32.forEach((root, index) => {
+    const bit = DECAY_FRACTION_BITS - 1n - BigInt(index);
+    if (((fraction >> bit) & 1n) === 1n) {
+      factor = (factor * root) >> 32n;
+    }
+  });
+
+  return Number(((BigInt(score) * factor) >> 32n) >> halvings);
+}
+
+// Aggregation model math, mirroring programs/n3roai-solana/src/math.rs bit for bit.
+export function effectiveScore(score: number, confidenceBps: number): number {
+  return Math.floor((score * confidenceBps) / MAX_BPS);
+}
+
+export function emaUpdate(previous: number, effective: number, alphaBps: number): number {
+  const alpha = Math.min(alphaBps, MAX_BPS);
+  return Math.floor((previous * (MAX_BPS - alpha) + effective * alpha) / MAX_BPS);
+}
+
+// Slots hold score + 1; 0 marks an empty slot.
+export function windowMean(slots: number[]): number | null {
+  const occupied = slots.filter((slot) => slot !== EMPTY_WINDOW_SLOT);
+  if (occupied.length === 0) {
+    return null;
+  }
+  const sum = occupied.reduce((total, slot) => total + slot - 1, 0);
+  return Math.floor(sum / occupied.length);
+}
+
+export function betaMean(
+  totalWeightedScore: bigint,
+  totalWeight: bigint,
+  priorAlpha: number,
+  priorBeta: number
+): number {
+  const numerator = BigInt(priorAlpha) * BigInt(MAX_BPS) + totalWeightedScore;
+  const denominator = BigInt(priorAlpha) + BigInt(priorBeta) + totalWeight;
+  if (denominator === 0n) {
+    return 0;
+  }
+  const mean = numerator / denominator;
+  return Number(mean > BigInt(MAX_BPS) ? BigInt(MAX_BPS) : mean);
+}
+
+// (1 - alpha)^steps in bps, squaring with the same per-step rounding as the program.
+export function emaRetentionBps(alphaBps: number, steps: bigint): number {
+  const max = BigInt(MAX_BPS);
+  let base = BigInt(Math.max(MAX_BPS - alphaBps, 0));
+  let retention = max;
+  let exponent = steps;
+
+  while (exponent > 0n && retention > 0n) {
+    if ((exponent & 1n) === 1n) {
+      retention = (retention * base) / max;
+    }
+    base = (base * base) / max;
+    exponent >>= 1n;
+  }
+
+  return Number(retention);
+}
+
+// Welford steps on (mean, m2) scaled by SCORE_STAT_SCALE and its square.
+export function welfordAdd(mean: bigint, m2: bigint, count: number, score: number): [bigint, bigint] {
+  const value = BigInt(score) * BigInt(SCORE_STAT_SCALE);
+  const delta = value - mean;
+  const nextMean = mean + delta / BigInt(count + 1);
+  const spread = delta * (value - nextMean);
+  const total = m2 + (spread > 0n ? spread : 0n);
+  return [nextMean, total > U128_MAX ? U128_MAX : total];
+}
+
+export function welfordRemove(mean: bigint, m2: bigint, count: number, score: number): [bigint, bigint] {
+  if (count <= 1) {
+    return [0n, 0n];
+  }
+
+  const value = BigInt(score) * BigInt(SCORE_STAT_SCALE);
+  const quotient = (mean * BigInt(count) - value) / BigInt(count - 1);
+  const previousMean = quotient > 0n ? quotient : 0n;
+  const product = (value - previousMean) * (value - mean);
+  const spread = product > 0n ? product : 0n;
+  return [previousMean, m2 > spread ? m2 - spread : 0n];
+}
+
+export function currentRollingScore(
+  reputation: SolanaReputationState,
+  protocolConfig: SolanaProtocolConfig,
+  nowUnixSeconds: bigint
+): number {
+  return decayScore(reputation.rollingScore, nowUnixSeconds - reputation.lastUpdated, protocolConfig.scoreHalfLife);
+}
+
 export function findProtocolConfigPda(programId: PublicKey): PublicKey {
   return PublicKey.findProgramAddressSync([PROTOCOL_CONFIG_SEED], programId)[0];
 }
@@ -137,6 +1040,14 @@ export function findRoleAssignmentPda(programId: PublicKey, role: number, member
   return PublicKey.findProgramAddressSync([ROLE_SEED, Buffer.from([role]), member.toBuffer()], programId)[0];
 }
 
+export function findMemberStakePda(programId: PublicKey, member: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([STAKE_SEED, member.toBuffer()], programId)[0];
+}
+
+export function findVerificationRecordPda(programId: PublicKey, agentIdentity: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([VERIFICATION_SEED, agentIdentity.toBuffer()], programId)[0];
+}
+
 export function findSplitConfigPda(programId: PublicKey, agentIdentity: PublicKey): PublicKey {
   return PublicKey.findProgramAddressSync([SPLIT_SEED, agentIdentity.toBuffer()], programId)[0];
 }
@@ -145,6 +1056,52 @@ export function findSignalPda(programId: PublicKey, agentIdentity: PublicKey, tr
   return PublicKey.findProgramAddressSync([SIGNAL_SEED, agentIdentity.toBuffer(), tradeIdHash], programId)[0];
 }
 
+export function findReputationStatePda(programId: PublicKey, agentIdentity: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([REPUTATION_SEED, agentIdentity.toBuffer()], programId)[0];
+}
+
+export function findCategoryReputationStatePda(
+  programId: PublicKey,
+  agentIdentity: PublicKey,
+  category: number
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [REPUTATION_SEED, agentIdentity.toBuffer(), toU16LeBuffer(category, "category")],
+    programId
+  )[0];
+}
+
+export function findReputationSnapshotPda(programId: PublicKey, reputationState: PublicKey, epoch: bigint): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [EPOCH_SNAPSHOT_SEED, reputationState.toBuffer(), toU64LeBuffer(epoch)],
+    programId
+  )[0];
+}
+
+export function findSignalCommitmentPda(programId: PublicKey, agentIdentity: PublicKey, commitment: Buffer): PublicKey {
+  return PublicKey.findProgramAddressSync([COMMITMENT_SEED, agentIdentity.toBuffer(), commitment], programId)[0];
+}
+
+export function findSignalBatchPda(programId: PublicKey, agentIdentity: PublicKey, root: Buffer): PublicKey {
+  return PublicKey.findProgramAddressSync([SIGNAL_BATCH_SEED, agentIdentity.toBuffer(), root], programId)[0];
+}
+
+export function findPnlStatsPda(programId: PublicKey, agentIdentity: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([PNL_STATS_SEED, agentIdentity.toBuffer()], programId)[0];
+}
+
+export function findScoredTradesPda(programId: PublicKey, agentIdentity: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([SCORED_TRADES_SEED, agentIdentity.toBuffer()], programId)[0];
+}
+
+export function findLeaderboardPda(programId: PublicKey, category: number): PublicKey {
+  return PublicKey.findProgramAddressSync([LEADERBOARD_SEED, toU16LeBuffer(category, "category")], programId)[0];
+}
+
+export function findCategoryPda(programId: PublicKey, category: number): PublicKey {
+  return PublicKey.findProgramAddressSync([CATEGORY_SEED, toU16LeBuffer(category, "category")], programId)[0];
+}
+
 export function findDistributionReceiptPda(
   programId: PublicKey,
   agentIdentity: PublicKey,
@@ -153,6 +1110,57 @@ export function findDistributionReceiptPda(
   return PublicKey.findProgramAddressSync([RECEIPT_SEED, agentIdentity.toBuffer(), reference], programId)[0];
 }
 
+export function findSettlementMintConfigPda(programId: PublicKey, mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([SETTLEMENT_MINT_SEED, mint.toBuffer()], programId)[0];
+}
+
+export function findSettlementVaultPda(programId: PublicKey, mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([SETTLEMENT_VAULT_SEED, mint.toBuffer()], programId)[0];
+}
+
+export function findClaimLedgerPda(programId: PublicKey, settlementVault: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([CLAIM_LEDGER_SEED, settlementVault.toBuffer()], programId)[0];
+}
+
+export function findClaimableBalancePda(programId: PublicKey, settlementVault: PublicKey, owner: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([CLAIMABLE_SEED, settlementVault.toBuffer(), owner.toBuffer()], programId)[0];
+}
+
+export function findFeeSchedulePda(programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([FEE_SCHEDULE_SEED], programId)[0];
+}
+
+export function findSettlementVolumePda(programId: PublicKey, agentIdentity: PublicKey, mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [SETTLEMENT_VOLUME_SEED, agentIdentity.toBuffer(), mint.toBuffer()],
+    programId
+  )[0];
+}
+
+export function findReferralProgramPda(programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([REFERRAL_PROGRAM_SEED], programId)[0];
+}
+
+export function findReferralAccountPda(programId: PublicKey, referrer: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([REFERRAL_SEED, referrer.toBuffer()], programId)[0];
+}
+
+export function findReserveVaultPda(programId: PublicKey, scope: PublicKey, mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([RESERVE_SEED, scope.toBuffer(), mint.toBuffer()], programId)[0];
+}
+
+export function findVestingEscrowPda(programId: PublicKey, agentIdentity: PublicKey, mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([VESTING_SEED, agentIdentity.toBuffer(), mint.toBuffer()], programId)[0];
+}
+
+export function findVestingVaultPda(programId: PublicKey, vestingEscrow: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([VESTING_VAULT_SEED, vestingEscrow.toBuffer()], programId)[0];
+}
+
+export function findSolVaultPda(programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([SOL_VAULT_SEED], programId)[0];
+}
+
 export function findVaultAuthorityPda(programId: PublicKey): PublicKey {
   return PublicKey.findProgramAddressSync([VAULT_AUTHORITY_SEED], programId)[0];
 }
@@ -162,13 +1170,187 @@ export function encodeSubmitSignalData(args: {
   resultHash: Buffer;
   contextHash: Buffer;
   riskFlags: number;
+  category: number;
 }): Buffer {
   return Buffer.concat([
     anchorDiscriminator("submit_signal"),
     ensureBytes32(args.tradeIdHash, "tradeIdHash"),
     ensureBytes32(args.resultHash, "resultHash"),
     ensureBytes32(args.contextHash, "contextHash"),
-    Buffer.from([ensureU8(args.riskFlags, "riskFlags")])
+    Buffer.from([ensureU8(args.riskFlags, "riskFlags")]),
+    toU16LeBuffer(args.category, "category")
+  ]);
+}
+
+// keccak256(agent || tradeIdHash || resultHash || contextHash || riskFlags || category u16 LE || salt)
+export function computeSignalCommitment(args: {
+  agentIdentity: PublicKey;
+  tradeIdHash: Buffer;
+  resultHash: Buffer;
+  contextHash: Buffer;
+  riskFlags: number;
+  category: number;
+  salt: Buffer;
+}): Buffer {
+  const preimage = Buffer.concat([
+    args.agentIdentity.toBuffer(),
+    ensureBytes32(args.tradeIdHash, "tradeIdHash"),
+    ensureBytes32(args.resultHash, "resultHash"),
+    ensureBytes32(args.contextHash, "contextHash"),
+    Buffer.from([ensureU8(args.riskFlags, "riskFlags")]),
+    toU16LeBuffer(args.category, "category"),
+    ensureBytes32(args.salt, "salt")
+  ]);
+  return keccakBuffer(preimage);
+}
+
+// Mirrors the compressed signal leaf in instructions::compressed.
+export function computeSignalLeaf(args: {
+  agentIdentity: PublicKey;
+  leafIndex: number;
+  tradeIdHash: Buffer;
+  resultHash: Buffer;
+  contextHash: Buffer;
+  riskFlags: number;
+  category: number;
+  // Omit for a trade without a PnL report; it then counts as a trade that did not win.
+  pnl?: { notional: bigint; realizedPnl: bigint; holdingTime: bigint };
+}): Buffer {
+  const leafIndex = Buffer.alloc(4);
+  leafIndex.writeUInt32LE(args.leafIndex);
+  const pnl = args.pnl ?? { notional: 0n, realizedPnl: 0n, holdingTime: 0n };
+
+  return keccakBuffer(
+    Buffer.concat([
+      Buffer.from([0]),
+      args.agentIdentity.toBuffer(),
+      leafIndex,
+      ensureBytes32(args.tradeIdHash, "tradeIdHash"),
+      ensureBytes32(args.resultHash, "resultHash"),
+      ensureBytes32(args.contextHash, "contextHash"),
+      Buffer.from([ensureU8(args.riskFlags, "riskFlags")]),
+      toU16LeBuffer(args.category, "category"),
+      Buffer.from([args.pnl ? 1 : 0]),
+      toU64LeBuffer(pnl.notional),
+      toI64LeBuffer(pnl.realizedPnl),
+      toI64LeBuffer(pnl.holdingTime)
+    ])
+  );
+}
+
+// Builds the sorted-pair tree used on-chain; odd nodes are carried up unchanged.
+export function buildSignalMerkleTree(leaves: Buffer[]): { root: Buffer; proofs: Buffer[][] } {
+  if (leaves.length === 0) {
+    throw new Error("Merkle tree needs at least one leaf");
+  }
+
+  const proofs: Buffer[][] = leaves.map(() => []);
+  let level = leaves.map((leaf, index) => ({ hash: leaf, members: [index] }));
+
+  while (level.length > 1) {
+    const next: typeof level = [];
+    for (let index = 0; index < level.length; index += 2) {
+      const left = level[index];
+      const right = level[index + 1];
+      if (!right) {
+        next.push(left);
+        continue;
+      }
+
+      left.members.forEach((member) => proofs[member].push(right.hash));
+      right.members.forEach((member) => proofs[member].push(left.hash));
+      const [low, high] = Buffer.compare(left.hash, right.hash) <= 0 ? [left.hash, right.hash] : [right.hash, left.hash];
+      next.push({
+        hash: keccakBuffer(Buffer.concat([Buffer.from([1]), low, high])),
+        members: [...left.members, ...right.members]
+      });
+    }
+    level = next;
+  }
+
+  return { root: level[0].hash, proofs };
+}
+
+// Mirrors math::verify_proof: siblings are hashed in sorted order, so proofs carry no direction bits.
+export function verifySignalMerkleProof(leaf: Buffer, proof: Buffer[], root: Buffer): boolean {
+  let node = leaf;
+  for (const sibling of proof) {
+    const [low, high] = Buffer.compare(node, sibling) <= 0 ? [node, sibling] : [sibling, node];
+    node = keccakBuffer(Buffer.concat([Buffer.from([1]), low, high]));
+  }
+  return node.equals(root);
+}
+
+export function encodeCommitSignalData(commitment: Buffer): Buffer {
+  return Buffer.concat([anchorDiscriminator("commit_signal"), ensureBytes32(commitment, "commitment")]);
+}
+
+export function encodeRevealSignalData(args: {
+  commitment: Buffer;
+  tradeIdHash: Buffer;
+  resultHash: Buffer;
+  contextHash: Buffer;
+  riskFlags: number;
+  category: number;
+  salt: Buffer;
+}): Buffer {
+  return Buffer.concat([
+    anchorDiscriminator("reveal_signal"),
+    ensureBytes32(args.commitment, "commitment"),
+    ensureBytes32(args.tradeIdHash, "tradeIdHash"),
+    ensureBytes32(args.resultHash, "resultHash"),
+    ensureBytes32(args.contextHash, "contextHash"),
+    Buffer.from([ensureU8(args.riskFlags, "riskFlags")]),
+    toU16LeBuffer(args.category, "category"),
+    ensureBytes32(args.salt, "salt")
+  ]);
+}
+
+export function encodeReportTradePnlData(args: {
+  tradeIdHash: Buffer;
+  notional: bigint;
+  realizedPnl: bigint;
+  holdingTime: bigint;
+}): Buffer {
+  return Buffer.concat([
+    anchorDiscriminator("report_trade_pnl"),
+    ensureBytes32(args.tradeIdHash, "tradeIdHash"),
+    toU64LeBuffer(args.notional),
+    toI64LeBuffer(args.realizedPnl),
+    toI64LeBuffer(args.holdingTime)
+  ]);
+}
+
+export function encodeSetSplitRecipientsData(recipients: SolanaSplitRecipient[]): Buffer {
+  const count = Buffer.alloc(4);
+  count.writeUInt32LE(recipients.length);
+  return Buffer.concat([
+    anchorDiscriminator("set_split_recipients"),
+    count,
+    ...recipients.map((entry) =>
+      Buffer.concat([
+        entry.recipient.toBuffer(),
+        toU16LeBuffer(entry.bps, "bps"),
+        ensureBytes32(entry.labelHash, "labelHash")
+      ])
+    )
+  ]);
+}
+
+export function encodeSetFeeScheduleData(tiers: SolanaFeeTier[]): Buffer {
+  const count = Buffer.alloc(4);
+  count.writeUInt32LE(tiers.length);
+  return Buffer.concat([
+    anchorDiscriminator("set_fee_schedule"),
+    count,
+    ...tiers.map((tier) =>
+      Buffer.concat([
+        Buffer.from([tier.requireVerified ? 1 : 0]),
+        toU16LeBuffer(tier.minRollingScore, "minRollingScore"),
+        toU64LeBuffer(tier.minVolume),
+        toU16LeBuffer(tier.feeBps, "feeBps")
+      ])
+    )
   ]);
 }
 
@@ -180,6 +1362,107 @@ export function encodeDistributeSettlementData(args: { reference: Buffer; amount
   ]);
 }
 
+// Same layout as distribute_settlement; extra recipient wallets (not token accounts) follow as remaining accounts.
+export function encodeDistributeSettlementSolData(args: { reference: Buffer; amount: bigint }): Buffer {
+  return Buffer.concat([
+    anchorDiscriminator("distribute_settlement_sol"),
+    ensureBytes32(args.reference, "reference"),
+    toU64LeBuffer(args.amount)
+  ]);
+}
+
+// Remaining accounts: claimable balances of the agent wallet, platform/referrer/reserve for non-zero slots,
+// each split recipient, then the treasury token account owner when the protocol fee is non-zero.
+export function encodeAccrueSettlementData(args: { reference: Buffer; amount: bigint }): Buffer {
+  return Buffer.concat([
+    anchorDiscriminator("accrue_settlement"),
+    ensureBytes32(args.reference, "reference"),
+    toU64LeBuffer(args.amount)
+  ]);
+}
+
+export function encodeClaimData(amount: bigint): Buffer {
+  return Buffer.concat([anchorDiscriminator("claim"), toU64LeBuffer(amount)]);
+}
+
+export function encodeSetReferralLevelsData(levelBps: number[]): Buffer {
+  const count = Buffer.alloc(4);
+  count.writeUInt32LE(levelBps.length);
+  return Buffer.concat([
+    anchorDiscriminator("set_referral_levels"),
+    count,
+    ...levelBps.map((bps, index) => toU16LeBuffer(bps, `levelBps[${index}]`))
+  ]);
+}
+
+// Pass the parent's referral account when `parent` is not the default key (root).
+export function encodeRegisterReferralData(parent: PublicKey): Buffer {
+  return Buffer.concat([anchorDiscriminator("register_referral"), parent.toBuffer()]);
+}
+
+// Remaining accounts: the new parent's referral account, then each ancestor's up to a root.
+export function encodeSetReferralParentData(parent: PublicKey): Buffer {
+  return Buffer.concat([anchorDiscriminator("set_referral_parent"), parent.toBuffer()]);
+}
+
+// Pass the agent identity to open an agent reserve (scope = agent identity); omit it for a platform
+// reserve (scope = owner). The reserve token account is the reserve PDA's associated token account.
+export function encodeOpenReserveVaultData(args: { scope: PublicKey; lockupPeriod: bigint }): Buffer {
+  return Buffer.concat([
+    anchorDiscriminator("open_reserve_vault"),
+    args.scope.toBuffer(),
+    toI64LeBuffer(args.lockupPeriod)
+  ]);
+}
+
+export function encodeSetReserveLockupData(lockupPeriod: bigint): Buffer {
+  return Buffer.concat([anchorDiscriminator("set_reserve_lockup"), toI64LeBuffer(lockupPeriod)]);
+}
+
+export function encodeSyncReserveData(): Buffer {
+  return anchorDiscriminator("sync_reserve");
+}
+
+export function encodeWithdrawReserveData(amount: bigint): Buffer {
+  return Buffer.concat([anchorDiscriminator("withdraw_reserve"), toU64LeBuffer(amount)]);
+}
+
+export function encodeCompensateFromReserveData(args: { amount: bigint; reasonHash: Buffer }): Buffer {
+  return Buffer.concat([
+    anchorDiscriminator("compensate_from_reserve"),
+    toU64LeBuffer(args.amount),
+    ensureBytes32(args.reasonHash, "reasonHash")
+  ]);
+}
+
+export function encodeSetSplitVestingData(vestingDuration: bigint): Buffer {
+  return Buffer.concat([anchorDiscriminator("set_split_vesting"), toI64LeBuffer(vestingDuration)]);
+}
+
+export function encodeOpenVestingEscrowData(): Buffer {
+  return anchorDiscriminator("open_vesting_escrow");
+}
+
+export function encodeReleaseVestingData(): Buffer {
+  return anchorDiscriminator("release_vesting");
+}
+
+export function encodeClawbackVestingData(amount: bigint): Buffer {
+  return Buffer.concat([anchorDiscriminator("clawback_vesting"), toU64LeBuffer(amount)]);
+}
+
+// Remaining accounts per entry: agentIdentity, splitConfig, distributionReceipt, agent token account,
+// platform/referrer/reserve token accounts for non-zero slots, then one per split recipient.
+export function encodeDistributeSettlementBatchData(entries: { reference: Buffer; amount: bigint }[]): Buffer {
+  const count = Buffer.alloc(4);
+  count.writeUInt32LE(entries.length);
+  return Buffer.concat([
+    anchorDiscriminator("distribute_settlement_batch"),
+    count,
+    ...entries.map((entry) => Buffer.concat([ensureBytes32(entry.reference, "reference"), toU64LeBuffer(entry.amount)]))
+  ]);
+}
+
 export function hexBytes32ToBuffer(value: string, fieldName: string): Buffer {
   if (!/^0x[a-fA-F0-9]{64}$/.test(value)) {
     throw new Error(`${fieldName} must be a bytes32 hex string`);
@@ -242,6 +1525,30 @@ function toU64LeBuffer(value: bigint): Buffer {
   return out;
 }
 
+function toI64LeBuffer(value: bigint): Buffer {
+  if (value < -9_223_372_036_854_775_808n || value > 9_223_372_036_854_775_807n) {
+    throw new Error("int64 value out of range");
+  }
+
+  const out = Buffer.alloc(8);
+  out.writeBigInt64LE(value);
+  return out;
+}
+
+function keccakBuffer(value: Buffer): Buffer {
+  return Buffer.from(keccak256(value).slice(2), "hex");
+}
+
+function toU16LeBuffer(value: number, fieldName: string): Buffer {
+  if (!Number.isInteger(value) || value < 0 || value > 65_535) {
+    throw new Error(`${fieldName} must be uint16`);
+  }
+
+  const out = Buffer.alloc(2);
+  out.writeUInt16LE(value);
+  return out;
+}
+
 function ensureBytes32(value: Buffer, fieldName: string): Buffer {
   if (value.length !== 32) {
     throw new Error(`${fieldName} must be 32 bytes`);
@@ -264,6 +1571,14 @@ function readU16(data: Buffer, offset: number): number {
   return data.readUInt16LE(offset);
 }
 
+function readI64(data: Buffer, offset: number): bigint {
+  return data.readBigInt64LE(offset);
+}
+
+function readU128(data: Buffer, offset: number): bigint {
+  return data.readBigUInt64LE(offset) + (data.readBigUInt64LE(offset + 8) << 64n);
+}
+
 function readU8(data: Buffer, offset: number): number {
   return data.readUInt8(offset);
 }
@@ -28,6 +28,7 @@ export type DistributionConfig = {
     programId?: string;
     operatorSecretKey?: string;
     tokenProgramId: string;
+    settlementMint?: string;
   };
 };
 
@@ -108,7 +109,8 @@ export function loadConfig(env: NodeJS.ProcessEnv = process.env): AppConfig {
         operatorSecretKey: readOptionalString(env, "DISTRIBUTION_SOLANA_OPERATOR_SECRET_KEY"),
         tokenProgramId:
           readOptionalSolanaAddress(env, "DISTRIBUTION_SOLANA_TOKEN_PROGRAM_ID") ??
-          DEFAULT_SOLANA_TOKEN_PROGRAM_ID
+          DEFAULT_SOLANA_TOKEN_PROGRAM_ID,
+        settlementMint: readOptionalSolanaAddress(env, "DISTRIBUTION_SOLANA_SETTLEMENT_MINT")
       }
     },
     signal: {